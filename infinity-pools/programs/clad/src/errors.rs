@@ -130,6 +130,10 @@ pub enum ErrorCode {
     TryToDeserializePriceAccount,
     #[msg("Invalid argument provided")]
     InvalidArgument,
+    #[msg("Pool price is outside the allowed band around the oracle price")]
+    OraclePriceOutOfBand,
+    #[msg("Exceeded max oracle price band")]
+    OraclePriceBandMaxExceeded,
 
     #[msg("Collateral calculation error")]
     CollateralCalculationError,
@@ -140,6 +144,92 @@ pub enum ErrorCode {
     InvalidRepaymentAmount,
     #[msg("Invalid loan duration")]
     InvalidLoanDuration,
+    #[msg("Trade position is above the maintenance margin")]
+    TradePositionNotLiquidatable,
+    #[msg("Exceeded max liquidation bonus")]
+    LiquidationBonusMaxExceeded,
+    #[msg("Invalid interest rate model")]
+    InvalidInterestRateModel,
+    #[msg("Collateral amount must be greater than zero")]
+    ZeroCollateralAmount,
+    #[msg("Swap router is not allowed for the Globalpool")]
+    InvalidSwapRouter,
+    #[msg("Invalid swap instruction data")]
+    InvalidSwapInstructionData,
+    #[msg("Invalid oracle observation window")]
+    InvalidOracleObservationWindow,
+    #[msg("Oracle observation window is older than the oldest observation")]
+    OracleObservationTooOld,
+    #[msg("Two-hop swap through the same Globalpool")]
+    DuplicateTwoHopPool,
+    #[msg("Output mint of the first swap is not the input mint of the second swap")]
+    InvalidIntermediaryMint,
+    #[msg("Intermediate token amount of the two swaps does not match")]
+    IntermediateTokenAmountMismatch,
+    #[msg("A flash loan is already in progress for the Globalpool")]
+    FlashLoanInProgress,
+    #[msg("No flash loan is in progress for the Globalpool")]
+    FlashLoanNotInProgress,
+    #[msg("Flash loan is not repaid later in the transaction")]
+    FlashLoanNotRepaid,
+    #[msg("Flash loan instructions must be called directly, not through CPI")]
+    FlashLoanCpiNotAllowed,
+    #[msg("Invalid reward index")]
+    InvalidRewardIndex,
+    #[msg("Reward vault holds less than a day of emissions")]
+    RewardVaultAmountInsufficient,
+    #[msg("Liquidity is borrowed by trade positions")]
+    LiquidityBorrowed,
+    #[msg("Exceeded the position's liquidity available to queue for withdrawal")]
+    LiquidityQueuedExceeded,
+    #[msg("No queued liquidity is free to redeem")]
+    NoRedeemableLiquidity,
+    #[msg("Invalid dynamic fee config")]
+    InvalidDynamicFeeConfig,
+    #[msg("Limit order array is not for the tick array")]
+    InvalidLimitOrderArray,
+    #[msg("Limit orders are pending in the tick array, but its limit order array is missing")]
+    LimitOrderArrayMissing,
+    #[msg("Limit orders must be placed on a single tick spacing within a tick array")]
+    InvalidLimitOrderTickIndex,
+    #[msg("Limit order range must be entirely above or below the current price")]
+    LimitOrderRangeInPrice,
+    #[msg("Filled limit orders of the range must be claimed before new orders")]
+    LimitOrderRangeUnclaimed,
+    #[msg("Limit order is already filled")]
+    LimitOrderFilled,
+    #[msg("Limit order is not filled yet")]
+    LimitOrderNotFilled,
+
+    #[msg("Tick array has liquidity at some of its ticks and cannot be closed")]
+    CloseTickArrayNotEmpty,
+
+    #[msg("Liquidity positions must be on the same range of the same Globalpool")]
+    LiquidityPositionRangeMismatch,
+    #[msg("Liquidity queued for withdrawal must be redeemed first")]
+    LiquidityQueued,
+    #[msg("Liquidity position must have no liquidity to change its range")]
+    LiquidityNonZero,
+    #[msg("Maintenance margin out of bounds")]
+    InvalidMaintenanceMargin,
+    #[msg("Tick interest account of the tick must be initialized to queue liquidity")]
+    TickInterestNotInitialized,
+    #[msg("Funder does not match the limit order array")]
+    InvalidLimitOrderArrayFunder,
+    #[msg("Tick array has a funder, who must close it")]
+    TickArrayFunderSet,
+
+    // Permission errors
+    #[msg("Swap is not allowed")]
+    SwapNotAllowed,
+    #[msg("Adding liquidity is not allowed")]
+    AddLiquidityNotAllowed,
+    #[msg("Removing liquidity is not allowed")]
+    RemoveLiquidityNotAllowed,
+    #[msg("Opening trade position is not allowed")]
+    OpenPositionNotAllowed,
+    #[msg("Closing trade position is not allowed")]
+    ClosePositionNotAllowed,
 }
 
 impl From<TryFromIntError> for ErrorCode {
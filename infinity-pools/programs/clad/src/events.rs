@@ -0,0 +1,183 @@
+use anchor_lang::prelude::*;
+
+//
+// Events emitted on Globalpool state transitions, for indexers.
+// `sqrt_price` and `tick_current_index` are the Globalpool's after the transition.
+//
+
+#[event]
+pub struct Swapped {
+    pub globalpool: Pubkey,
+    pub token_authority: Pubkey,
+    pub a_to_b: bool,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub protocol_fee: u64,
+    pub sqrt_price: u128,
+    pub tick_current_index: i32,
+}
+
+#[event]
+pub struct LiquidityIncreased {
+    pub globalpool: Pubkey,
+    pub position: Pubkey,
+    pub liquidity_amount: u128,
+    pub token_amount_a: u64,
+    pub token_amount_b: u64,
+    pub sqrt_price: u128,
+    pub tick_current_index: i32,
+}
+
+#[event]
+pub struct LiquidityDecreased {
+    pub globalpool: Pubkey,
+    pub position: Pubkey,
+    pub liquidity_amount: u128,
+    pub token_amount_a: u64,
+    pub token_amount_b: u64,
+    pub sqrt_price: u128,
+    pub tick_current_index: i32,
+}
+
+#[event]
+pub struct FeesCollected {
+    pub globalpool: Pubkey,
+    pub position: Pubkey,
+    pub fee_amount_a: u64,
+    pub fee_amount_b: u64,
+}
+
+#[event]
+pub struct LiquidityWithdrawalQueued {
+    pub globalpool: Pubkey,
+    pub position: Pubkey,
+    pub liquidity_amount: u128,
+    pub liquidity_queued: u128,
+}
+
+#[event]
+pub struct LiquidityPositionSplit {
+    pub globalpool: Pubkey,
+    pub position: Pubkey,
+    pub new_position: Pubkey,
+    pub liquidity_amount: u128,
+}
+
+#[event]
+pub struct LiquidityPositionsMerged {
+    pub globalpool: Pubkey,
+    pub position: Pubkey,
+    pub merged_position: Pubkey,
+    pub liquidity_amount: u128,
+}
+
+#[event]
+pub struct LiquidityPositionRebalanced {
+    pub globalpool: Pubkey,
+    pub position: Pubkey,
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    pub liquidity_removed: u128,
+    pub liquidity_added: u128,
+    pub token_amount_a_removed: u64,
+    pub token_amount_b_removed: u64,
+    pub token_amount_a_added: u64,
+    pub token_amount_b_added: u64,
+}
+
+#[event]
+pub struct InterestCollected {
+    pub globalpool: Pubkey,
+    pub position: Pubkey,
+    pub interest_amount_a: u64,
+    pub interest_amount_b: u64,
+}
+
+#[event]
+pub struct RewardCollected {
+    pub globalpool: Pubkey,
+    pub position: Pubkey,
+    pub reward_index: u8,
+    pub reward_amount: u64,
+}
+
+#[event]
+pub struct ProtocolFeesCollected {
+    pub globalpool: Pubkey,
+    pub fee_amount_a: u64,
+    pub fee_amount_b: u64,
+}
+
+#[event]
+pub struct TradePositionOpened {
+    pub globalpool: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub is_borrow_a: bool,
+    pub liquidity_borrowed_delta: i128, // Positive, borrowed from the ticks
+    pub loan_token_swapped: u64,
+    pub trade_token_amount: u64,
+    pub collateral_amount: u64,
+    pub interest_amount: u64, // Zero if the position accrues interest
+    pub sqrt_price: u128,
+    pub tick_current_index: i32,
+}
+
+#[event]
+pub struct TradePositionRepaid {
+    pub globalpool: Pubkey,
+    pub position: Pubkey,
+    pub liquidity_borrowed_delta: i128, // Negative, repaid (all of the position's if fully repaid)
+    pub interest_owed_liquidity: u128,
+    pub repay_amount_a: u64,
+    pub repay_amount_b: u64,
+    pub collateral_amount: u64, // Leftover collateral of the repaid share
+    pub profit_amount: u64,
+    pub sqrt_price: u128,
+    pub tick_current_index: i32,
+}
+
+#[event]
+pub struct TradePositionLiquidated {
+    pub globalpool: Pubkey,
+    pub position: Pubkey,
+    pub liquidator: Pubkey,
+    pub liquidity_borrowed_delta: i128, // Negative, all of the position's
+    pub interest_owed_liquidity: u128,
+    pub repay_amount_a: u64,
+    pub repay_amount_b: u64,
+    pub collateral_amount: u64, // Leftover collateral kept on the position for the owner
+    pub liquidation_bonus_amount: u64,
+    pub sqrt_price: u128,
+    pub tick_current_index: i32,
+}
+
+#[event]
+pub struct LimitOrderOpened {
+    pub globalpool: Pubkey,
+    pub limit_order: Pubkey,
+    pub owner: Pubkey,
+    pub tick_lower_index: i32,
+    pub a_to_b: bool,
+    pub liquidity_amount: u128,
+    pub token_amount: u64,
+}
+
+#[event]
+pub struct LimitOrderCancelled {
+    pub globalpool: Pubkey,
+    pub limit_order: Pubkey,
+    pub owner: Pubkey,
+    pub token_amount_a: u64,
+    pub token_amount_b: u64,
+}
+
+#[event]
+pub struct LimitOrderClaimed {
+    pub globalpool: Pubkey,
+    pub limit_order: Pubkey,
+    pub owner: Pubkey,
+    pub claimer: Pubkey,
+    pub token_amount_a: u64,
+    pub token_amount_b: u64,
+}
@@ -0,0 +1,14 @@
+use {crate::state::*, anchor_lang::prelude::*};
+
+#[derive(Accounts)]
+pub struct AcceptFeeAuthority<'info> {
+    #[account(mut)]
+    pub globalpool: Account<'info, Globalpool>,
+
+    #[account(address = globalpool.pending_fee_authority)]
+    pub pending_fee_authority: Signer<'info>,
+}
+
+pub fn accept_fee_authority(ctx: Context<AcceptFeeAuthority>) -> Result<()> {
+    Ok(ctx.accounts.globalpool.accept_fee_authority())
+}
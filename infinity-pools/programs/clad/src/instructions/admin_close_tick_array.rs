@@ -0,0 +1,49 @@
+use {
+    super::{close_limit_order_array, verify_tick_array_closable},
+    crate::{errors::ErrorCode, state::*},
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct AdminCloseTickArray<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"clad"],
+        bump = clad.clad_bump,
+        has_one = admin,
+    )]
+    pub clad: Box<Account<'info, Clad>>,
+
+    pub globalpool: Account<'info, Globalpool>,
+
+    #[account(
+        mut,
+        has_one = globalpool,
+        constraint = tick_array.load()?.funder == Pubkey::default() @ ErrorCode::TickArrayFunderSet,
+        close = admin,
+    )]
+    pub tick_array: AccountLoader<'info, TickArray>,
+
+    /// CHECK: Limit orders of the tick array, closed along with it if created
+    #[account(mut, seeds = [b"limit_order_array", tick_array.key().as_ref()], bump)]
+    pub limit_order_array: UncheckedAccount<'info>,
+
+    /// CHECK: Funder of the limit_order_array receiving its rent, verified if it is created
+    #[account(mut)]
+    pub limit_order_array_funder: UncheckedAccount<'info>,
+}
+
+/*
+  Closes a tick array without liquidity left at any of its ticks that has no funder to close it,
+  ie. initialized before tick arrays recorded their funder. Its rent goes to the admin.
+*/
+pub fn admin_close_tick_array(ctx: Context<AdminCloseTickArray>) -> Result<()> {
+    verify_tick_array_closable(&ctx.accounts.tick_array)?;
+
+    close_limit_order_array(
+        &ctx.accounts.limit_order_array,
+        &ctx.accounts.limit_order_array_funder,
+    )
+}
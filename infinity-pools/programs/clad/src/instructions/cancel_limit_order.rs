@@ -0,0 +1,202 @@
+use {
+    crate::{
+        errors::ErrorCode,
+        events::LimitOrderCancelled,
+        manager::{
+            globalpool_manager::{next_globalpool_liquidity, next_globalpool_reward_infos},
+            limit_order_manager::calculate_limit_order_fees,
+            liquidity_manager::calculate_liquidity_token_deltas,
+            tick_manager::{next_fee_growths_inside, next_tick_modify_liquidity_update},
+        },
+        math::convert_to_liquidity_delta,
+        state::*,
+        util::{to_timestamp_u64, transfer_from_vault_to_owner},
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::token::{self, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct CancelLimitOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [b"clad"], bump = clad.clad_bump)]
+    pub clad: Box<Account<'info, Clad>>,
+
+    #[account(mut)]
+    pub globalpool: Box<Account<'info, Globalpool>>,
+
+    #[account(mut, has_one = globalpool, has_one = owner, close = owner)]
+    pub limit_order: Box<Account<'info, LimitOrder>>,
+
+    #[account(mut, has_one = globalpool)]
+    pub tick_array: AccountLoader<'info, TickArray>,
+
+    #[account(mut, seeds = [b"limit_order_array", tick_array.key().as_ref()], bump)]
+    pub limit_order_array: AccountLoader<'info, LimitOrderArray>,
+
+    #[account(mut, constraint = token_owner_account_a.mint == globalpool.token_mint_a)]
+    pub token_owner_account_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = token_owner_account_b.mint == globalpool.token_mint_b)]
+    pub token_owner_account_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = token_vault_a.key() == globalpool.token_vault_a)]
+    pub token_vault_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = token_vault_b.key() == globalpool.token_vault_b)]
+    pub token_vault_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CancelLimitOrderParams {
+    token_min_a: u64,
+    token_min_b: u64,
+}
+
+/*
+  Cancels a limit order that is not filled yet, withdrawing its liquidity (partly converted if the
+  price is in the range) and the fees it earned, and closes it.
+*/
+pub fn cancel_limit_order(
+    ctx: Context<CancelLimitOrder>,
+    params: &CancelLimitOrderParams,
+) -> Result<()> {
+    require!(
+        ctx.accounts.clad.permissions.allow_remove_liquidity,
+        ErrorCode::RemoveLiquidityNotAllowed
+    );
+
+    let globalpool = &ctx.accounts.globalpool;
+    let limit_order = &ctx.accounts.limit_order;
+    let tick_spacing = globalpool.tick_spacing;
+    let tick_lower_index = limit_order.tick_lower_index;
+    let tick_upper_index = limit_order.tick_upper_index(tick_spacing);
+    let liquidity_delta = convert_to_liquidity_delta(limit_order.liquidity, false)?;
+    let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
+
+    let mut tick_array = ctx.accounts.tick_array.load_mut()?;
+    let mut limit_order_array = ctx.accounts.limit_order_array.load_mut()?;
+
+    let mut range = limit_order_array.get_range(tick_lower_index, tick_spacing)?;
+    if limit_order.is_filled(&range) {
+        return Err(ErrorCode::LimitOrderFilled.into());
+    }
+
+    let tick_lower = *tick_array.get_tick(tick_lower_index, tick_spacing)?;
+    let tick_upper = *tick_array.get_tick(tick_upper_index, tick_spacing)?;
+
+    let next_reward_infos = next_globalpool_reward_infos(globalpool, timestamp)?;
+    let next_liquidity = next_globalpool_liquidity(
+        globalpool,
+        tick_upper_index,
+        tick_lower_index,
+        liquidity_delta,
+    )?;
+
+    let (fee_growth_inside_a, fee_growth_inside_b) = next_fee_growths_inside(
+        globalpool.tick_current_index,
+        &tick_lower,
+        tick_lower_index,
+        &tick_upper,
+        tick_upper_index,
+        globalpool.fee_growth_global_a,
+        globalpool.fee_growth_global_b,
+    );
+    let (fee_amount_a, fee_amount_b) =
+        calculate_limit_order_fees(limit_order, fee_growth_inside_a, fee_growth_inside_b);
+
+    let tick_lower_update = next_tick_modify_liquidity_update(
+        &tick_lower,
+        tick_lower_index,
+        globalpool.tick_current_index,
+        globalpool.fee_growth_global_a,
+        globalpool.fee_growth_global_b,
+        &next_reward_infos,
+        liquidity_delta,
+        false,
+        false,
+    )?;
+
+    let tick_upper_update = next_tick_modify_liquidity_update(
+        &tick_upper,
+        tick_upper_index,
+        globalpool.tick_current_index,
+        globalpool.fee_growth_global_a,
+        globalpool.fee_growth_global_b,
+        &next_reward_infos,
+        liquidity_delta,
+        true,
+        false,
+    )?;
+
+    let (delta_a, delta_b) = calculate_liquidity_token_deltas(
+        tick_lower_index,
+        tick_upper_index,
+        globalpool.tick_current_index,
+        globalpool.sqrt_price,
+        liquidity_delta,
+    )?;
+
+    let token_amount_a = delta_a
+        .checked_add(fee_amount_a)
+        .ok_or(ErrorCode::AmountCalcOverflow)?;
+    let token_amount_b = delta_b
+        .checked_add(fee_amount_b)
+        .ok_or(ErrorCode::AmountCalcOverflow)?;
+    if token_amount_a < params.token_min_a || token_amount_b < params.token_min_b {
+        return Err(ErrorCode::TokenMinSubceeded.into());
+    }
+
+    tick_array.update_tick(tick_lower_index, tick_spacing, &tick_lower_update)?;
+    tick_array.update_tick(tick_upper_index, tick_spacing, &tick_upper_update)?;
+
+    range.liquidity = range
+        .liquidity
+        .checked_sub(limit_order.liquidity)
+        .ok_or(ErrorCode::LiquidityUnderflow)?;
+    limit_order_array.update_range(tick_lower_index, tick_spacing, &range)?;
+    tick_array.update_limit_order_range_count(true, range.has_pending_orders());
+
+    let is_in_range = globalpool.tick_current_index >= tick_lower_index
+        && globalpool.tick_current_index < tick_upper_index;
+    let order_liquidity = limit_order.liquidity;
+
+    let globalpool = &mut ctx.accounts.globalpool;
+    globalpool.update_rewards_and_liquidity(next_reward_infos, next_liquidity, timestamp)?;
+    if is_in_range {
+        globalpool.liquidity_limit_orders = globalpool
+            .liquidity_limit_orders
+            .saturating_sub(order_liquidity);
+    }
+
+    transfer_from_vault_to_owner(
+        &ctx.accounts.globalpool,
+        &ctx.accounts.token_vault_a,
+        &ctx.accounts.token_owner_account_a,
+        &ctx.accounts.token_program,
+        token_amount_a,
+    )?;
+
+    transfer_from_vault_to_owner(
+        &ctx.accounts.globalpool,
+        &ctx.accounts.token_vault_b,
+        &ctx.accounts.token_owner_account_b,
+        &ctx.accounts.token_program,
+        token_amount_b,
+    )?;
+
+    emit!(LimitOrderCancelled {
+        globalpool: ctx.accounts.globalpool.key(),
+        limit_order: ctx.accounts.limit_order.key(),
+        owner: ctx.accounts.owner.key(),
+        token_amount_a,
+        token_amount_b,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use {
+    crate::{
+        errors::ErrorCode,
+        events::LimitOrderClaimed,
+        manager::limit_order_manager::calculate_limit_order_proceeds,
+        state::*,
+        util::transfer_from_vault_to_owner,
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::token::{self, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct ClaimLimitOrder<'info> {
+    // The owner or a keeper, the proceeds go to the owner either way
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [b"clad"], bump = clad.clad_bump)]
+    pub clad: Box<Account<'info, Clad>>,
+
+    pub globalpool: Box<Account<'info, Globalpool>>,
+
+    #[account(mut, has_one = globalpool, has_one = owner, close = owner)]
+    pub limit_order: Box<Account<'info, LimitOrder>>,
+
+    /// CHECK: safe, the owner of the limit order receives its rent
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(mut, constraint = limit_order_array.load()?.globalpool == globalpool.key())]
+    pub limit_order_array: AccountLoader<'info, LimitOrderArray>,
+
+    #[account(
+        mut,
+        constraint = token_owner_account_a.mint == globalpool.token_mint_a,
+        constraint = token_owner_account_a.owner == limit_order.owner
+    )]
+    pub token_owner_account_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = token_owner_account_b.mint == globalpool.token_mint_b,
+        constraint = token_owner_account_b.owner == limit_order.owner
+    )]
+    pub token_owner_account_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = token_vault_a.key() == globalpool.token_vault_a)]
+    pub token_vault_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = token_vault_b.key() == globalpool.token_vault_b)]
+    pub token_vault_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+}
+
+/*
+  Claims the proceeds of a filled limit order for its owner, ie. the converted tokens and the fees
+  earned until the fill, and closes it.
+*/
+pub fn claim_limit_order(ctx: Context<ClaimLimitOrder>) -> Result<()> {
+    require!(
+        ctx.accounts.clad.permissions.allow_remove_liquidity,
+        ErrorCode::RemoveLiquidityNotAllowed
+    );
+
+    let limit_order = &ctx.accounts.limit_order;
+    let tick_spacing = ctx.accounts.globalpool.tick_spacing;
+
+    let (token_amount_a, token_amount_b) = {
+        let mut limit_order_array = ctx.accounts.limit_order_array.load_mut()?;
+        let mut range = limit_order_array.get_range(limit_order.tick_lower_index, tick_spacing)?;
+        if !limit_order.is_filled(&range) {
+            return Err(ErrorCode::LimitOrderNotFilled.into());
+        }
+
+        let proceeds = calculate_limit_order_proceeds(limit_order, &range, tick_spacing)?;
+
+        range.liquidity_unclaimed = range
+            .liquidity_unclaimed
+            .checked_sub(limit_order.liquidity)
+            .ok_or(ErrorCode::LiquidityUnderflow)?;
+        limit_order_array.update_range(limit_order.tick_lower_index, tick_spacing, &range)?;
+
+        proceeds
+    };
+
+    transfer_from_vault_to_owner(
+        &ctx.accounts.globalpool,
+        &ctx.accounts.token_vault_a,
+        &ctx.accounts.token_owner_account_a,
+        &ctx.accounts.token_program,
+        token_amount_a,
+    )?;
+
+    transfer_from_vault_to_owner(
+        &ctx.accounts.globalpool,
+        &ctx.accounts.token_vault_b,
+        &ctx.accounts.token_owner_account_b,
+        &ctx.accounts.token_program,
+        token_amount_b,
+    )?;
+
+    emit!(LimitOrderClaimed {
+        globalpool: ctx.accounts.globalpool.key(),
+        limit_order: ctx.accounts.limit_order.key(),
+        owner: ctx.accounts.limit_order.owner,
+        claimer: ctx.accounts.authority.key(),
+        token_amount_a,
+        token_amount_b,
+    });
+
+    Ok(())
+}
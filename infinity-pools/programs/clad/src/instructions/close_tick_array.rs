@@ -0,0 +1,69 @@
+use {crate::errors::ErrorCode, crate::state::*, anchor_lang::prelude::*};
+
+#[derive(Accounts)]
+pub struct CloseTickArray<'info> {
+    pub globalpool: Account<'info, Globalpool>,
+
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    #[account(mut, has_one = globalpool, has_one = funder, close = funder)]
+    pub tick_array: AccountLoader<'info, TickArray>,
+
+    /// CHECK: Limit orders of the tick array, closed along with it if created
+    #[account(mut, seeds = [b"limit_order_array", tick_array.key().as_ref()], bump)]
+    pub limit_order_array: UncheckedAccount<'info>,
+
+    /// CHECK: Funder of the limit_order_array receiving its rent, verified if it is created
+    #[account(mut)]
+    pub limit_order_array_funder: UncheckedAccount<'info>,
+}
+
+/*
+  Closes a tick array without liquidity left at any of its ticks, refunding its rent to the funder
+  who initialized it. The tick array can be initialized again once liquidity comes back to its
+  range.
+*/
+pub fn close_tick_array(ctx: Context<CloseTickArray>) -> Result<()> {
+    verify_tick_array_closable(&ctx.accounts.tick_array)?;
+
+    close_limit_order_array(
+        &ctx.accounts.limit_order_array,
+        &ctx.accounts.limit_order_array_funder,
+    )
+}
+
+pub fn verify_tick_array_closable(tick_array: &AccountLoader<TickArray>) -> Result<()> {
+    let tick_array = tick_array.load()?;
+    if !tick_array.is_empty() || tick_array.limit_order_range_count > 0 {
+        return Err(ErrorCode::CloseTickArrayNotEmpty.into());
+    }
+
+    Ok(())
+}
+
+/*
+  Closes the limit order array of a tick array being closed, if it is created, refunding its rent
+  to its funder. Filled orders must be claimed first, as their proceeds are kept in the array.
+*/
+pub fn close_limit_order_array<'info>(
+    limit_order_array: &AccountInfo<'info>,
+    limit_order_array_funder: &AccountInfo<'info>,
+) -> Result<()> {
+    if limit_order_array.owner != &crate::ID || limit_order_array.data_is_empty() {
+        return Ok(());
+    }
+
+    let limit_order_array = AccountLoader::<LimitOrderArray>::try_from(limit_order_array)?;
+    {
+        let limit_order_array = limit_order_array.load()?;
+        if limit_order_array.funder != limit_order_array_funder.key() {
+            return Err(ErrorCode::InvalidLimitOrderArrayFunder.into());
+        }
+        if !limit_order_array.is_empty() {
+            return Err(ErrorCode::CloseTickArrayNotEmpty.into());
+        }
+    }
+
+    limit_order_array.close(limit_order_array_funder.clone())
+}
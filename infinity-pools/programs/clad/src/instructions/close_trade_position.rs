@@ -5,6 +5,7 @@ use {
         state::*,
         util::{
             // burn_and_close_user_position_token,
+            to_timestamp_u64,
             transfer_from_vault_to_owner,
             verify_position_authority,
         },
@@ -18,6 +19,9 @@ pub struct CloseTradePosition<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
+    #[account(seeds = [b"clad"], bump = clad.clad_bump)]
+    pub clad: Box<Account<'info, Clad>>,
+
     #[account(mut)]
     pub globalpool: Box<Account<'info, Globalpool>>,
 
@@ -69,11 +73,38 @@ pub struct CloseTradePosition<'info> {
     #[account(mut, has_one = globalpool)]
     pub tick_array_upper: AccountLoader<'info, TickArray>,
 
+    #[account(
+        mut,
+        seeds = [
+            b"tick_interest".as_ref(),
+            globalpool.key().as_ref(),
+            position.tick_lower_index.to_le_bytes().as_ref()
+        ],
+        bump,
+    )]
+    pub tick_interest_lower: Box<Account<'info, TickInterest>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"tick_interest".as_ref(),
+            globalpool.key().as_ref(),
+            position.tick_upper_index.to_le_bytes().as_ref()
+        ],
+        bump,
+    )]
+    pub tick_interest_upper: Box<Account<'info, TickInterest>>,
+
     #[account(address = token::ID)]
     pub token_program: Program<'info, Token>,
 }
 
 pub fn close_trade_position(ctx: Context<CloseTradePosition>) -> Result<()> {
+    require!(
+        ctx.accounts.clad.permissions.allow_close_position,
+        ErrorCode::ClosePositionNotAllowed
+    );
+
     verify_position_authority(&ctx.accounts.position_token_account, &ctx.accounts.owner)?;
 
     if !TradePosition::is_position_empty(&ctx.accounts.position) {
@@ -83,6 +114,8 @@ pub fn close_trade_position(ctx: Context<CloseTradePosition>) -> Result<()> {
     let liquidity_borrowed = ctx.accounts.position.liquidity_borrowed;
     let loan_token_available = ctx.accounts.position.loan_token_available;
 
+    let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
+
     // Last param assumes that all loaned token was swapped to trade token in `open_trade_position`
     let update = loan_manager::calculate_modify_loan(
         &ctx.accounts.globalpool,
@@ -92,6 +125,7 @@ pub fn close_trade_position(ctx: Context<CloseTradePosition>) -> Result<()> {
         -(liquidity_borrowed as i128),
         -(loan_token_available as i64),
         false,
+        timestamp,
     )?;
 
     liquidity_manager::sync_modify_liquidity_values_for_loan(
@@ -99,7 +133,10 @@ pub fn close_trade_position(ctx: Context<CloseTradePosition>) -> Result<()> {
         &mut ctx.accounts.position,
         &ctx.accounts.tick_array_lower,
         &ctx.accounts.tick_array_upper,
+        &mut ctx.accounts.tick_interest_lower,
+        &mut ctx.accounts.tick_interest_upper,
         &update,
+        timestamp,
     )?;
 
     let is_borrow_a = ctx.accounts.position.is_borrow_a(&ctx.accounts.globalpool);
@@ -1,6 +1,7 @@
 use {
     crate::{
-        manager::liquidity_manager::calculate_fee_growths,
+        events::FeesCollected,
+        manager::liquidity_manager::calculate_fee_and_reward_growths,
         state::*,
         util::{to_timestamp_u64, transfer_from_vault_to_owner, verify_position_authority},
     },
@@ -10,6 +11,7 @@ use {
 
 #[derive(Accounts)]
 pub struct CollectFees<'info> {
+    #[account(mut)]
     pub globalpool: Box<Account<'info, Globalpool>>,
 
     pub position_authority: Signer<'info>,
@@ -50,15 +52,17 @@ pub fn collect_fees(ctx: Context<CollectFees>) -> Result<()> {
     let globalpool = &mut ctx.accounts.globalpool;
     // let position = &mut ctx.accounts.position;
 
-    // Update fee accrued for the position
-    let position_update = &calculate_fee_growths(
+    // Update fee and reward accrued for the position
+    let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
+    let (position_update, reward_infos) = calculate_fee_and_reward_growths(
         globalpool,
         &ctx.accounts.position,
         &ctx.accounts.tick_array_lower,
         &ctx.accounts.tick_array_upper,
-        to_timestamp_u64(Clock::get()?.unix_timestamp)?,
+        timestamp,
     )?;
-    (&mut ctx.accounts.position).update(position_update);
+    globalpool.update_rewards(reward_infos, timestamp);
+    (&mut ctx.accounts.position).update(&position_update);
 
     // Store the fees owed to use as transfer amounts, before resetting.
     let fee_owed_a = ctx.accounts.position.fee_owed_a;
@@ -83,5 +87,12 @@ pub fn collect_fees(ctx: Context<CollectFees>) -> Result<()> {
         fee_owed_b,
     )?;
 
+    emit!(FeesCollected {
+        globalpool: ctx.accounts.globalpool.key(),
+        position: ctx.accounts.position.key(),
+        fee_amount_a: fee_owed_a,
+        fee_amount_b: fee_owed_b,
+    });
+
     Ok(())
 }
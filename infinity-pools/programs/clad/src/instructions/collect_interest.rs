@@ -0,0 +1,102 @@
+use {
+    crate::{
+        events::InterestCollected,
+        manager::{
+            liquidity_manager::calculate_position_interest_growths,
+            position_manager::next_position_interest_update,
+        },
+        state::*,
+        util::{to_timestamp_u64, transfer_from_vault_to_owner, verify_position_authority},
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::token::{self, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct CollectInterest<'info> {
+    pub globalpool: Box<Account<'info, Globalpool>>,
+
+    pub position_authority: Signer<'info>,
+
+    #[account(mut, has_one = globalpool)]
+    pub position: Box<Account<'info, LiquidityPosition>>,
+    #[account(
+        constraint = position_token_account.mint == position.position_mint,
+        constraint = position_token_account.amount == 1
+    )]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Interest growths of the position's lower tick, created when a trade position borrows at it
+    #[account(
+        seeds = [b"tick_interest".as_ref(), globalpool.key().as_ref(), position.tick_lower_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub tick_interest_lower: UncheckedAccount<'info>,
+    /// CHECK: Interest growths of the position's upper tick, created when a trade position borrows at it
+    #[account(
+        seeds = [b"tick_interest".as_ref(), globalpool.key().as_ref(), position.tick_upper_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub tick_interest_upper: UncheckedAccount<'info>,
+
+    #[account(mut, constraint = token_owner_account_a.mint == globalpool.token_mint_a)]
+    pub token_owner_account_a: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = globalpool.token_vault_a)]
+    pub token_vault_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = token_owner_account_b.mint == globalpool.token_mint_b)]
+    pub token_owner_account_b: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = globalpool.token_vault_b)]
+    pub token_vault_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn collect_interest(ctx: Context<CollectInterest>) -> Result<()> {
+    verify_position_authority(
+        &ctx.accounts.position_token_account,
+        &ctx.accounts.position_authority,
+    )?;
+
+    // Update interest accrued for the position from its lower & upper ticks
+    let (interest_growth_a, interest_growth_b) = calculate_position_interest_growths(
+        &ctx.accounts.tick_interest_lower,
+        &ctx.accounts.tick_interest_upper,
+        to_timestamp_u64(Clock::get()?.unix_timestamp)?,
+    )?;
+    let interest_update =
+        next_position_interest_update(&ctx.accounts.position, interest_growth_a, interest_growth_b);
+    (&mut ctx.accounts.position).update_interest(&interest_update);
+
+    // Store the interest owed to use as transfer amounts, before resetting.
+    let interest_owed_a = ctx.accounts.position.interest_owed_a;
+    let interest_owed_b = ctx.accounts.position.interest_owed_b;
+
+    (&mut ctx.accounts.position).reset_interest_owed();
+
+    transfer_from_vault_to_owner(
+        &ctx.accounts.globalpool,
+        &ctx.accounts.token_vault_a,
+        &ctx.accounts.token_owner_account_a,
+        &ctx.accounts.token_program,
+        interest_owed_a,
+    )?;
+
+    transfer_from_vault_to_owner(
+        &ctx.accounts.globalpool,
+        &ctx.accounts.token_vault_b,
+        &ctx.accounts.token_owner_account_b,
+        &ctx.accounts.token_program,
+        interest_owed_b,
+    )?;
+
+    emit!(InterestCollected {
+        globalpool: ctx.accounts.globalpool.key(),
+        position: ctx.accounts.position.key(),
+        interest_amount_a: interest_owed_a,
+        interest_amount_b: interest_owed_b,
+    });
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::{state::*, util::transfer_from_vault_to_owner};
+use crate::{events::ProtocolFeesCollected, state::*, util::transfer_from_vault_to_owner};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount};
 
@@ -45,5 +45,11 @@ pub fn collect_protocol_fees(ctx: Context<CollectProtocolFees>) -> Result<()> {
         globalpool.protocol_fee_owed_b,
     )?;
 
+    emit!(ProtocolFeesCollected {
+        globalpool: globalpool.key(),
+        fee_amount_a: globalpool.protocol_fee_owed_a,
+        fee_amount_b: globalpool.protocol_fee_owed_b,
+    });
+
     Ok(ctx.accounts.globalpool.reset_protocol_fees_owed())
 }
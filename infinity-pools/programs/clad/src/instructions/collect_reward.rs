@@ -0,0 +1,96 @@
+use {
+    crate::{
+        events::RewardCollected,
+        manager::liquidity_manager::calculate_fee_and_reward_growths,
+        state::*,
+        util::{to_timestamp_u64, transfer_from_vault_to_owner, verify_position_authority},
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::token::{self, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+#[instruction(params: CollectRewardParams)]
+pub struct CollectReward<'info> {
+    #[account(mut)]
+    pub globalpool: Box<Account<'info, Globalpool>>,
+
+    pub position_authority: Signer<'info>,
+
+    #[account(mut, has_one = globalpool)]
+    pub position: Box<Account<'info, LiquidityPosition>>,
+    #[account(
+        constraint = position_token_account.mint == position.position_mint,
+        constraint = position_token_account.amount == 1
+    )]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(has_one = globalpool)]
+    pub tick_array_lower: AccountLoader<'info, TickArray>,
+    #[account(has_one = globalpool)]
+    pub tick_array_upper: AccountLoader<'info, TickArray>,
+
+    #[account(
+        mut,
+        constraint = reward_owner_account.mint == globalpool.reward_infos[params.reward_index as usize].mint
+    )]
+    pub reward_owner_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = globalpool.reward_infos[params.reward_index as usize].vault)]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CollectRewardParams {
+    pub reward_index: u8,
+}
+
+pub fn collect_reward(ctx: Context<CollectReward>, params: &CollectRewardParams) -> Result<()> {
+    verify_position_authority(
+        &ctx.accounts.position_token_account,
+        &ctx.accounts.position_authority,
+    )?;
+
+    let globalpool = &mut ctx.accounts.globalpool;
+    let reward_index = params.reward_index as usize;
+
+    // Update fee and reward accrued for the position. A position without liquidity accrues
+    // nothing, and its rewards owed were stored when its liquidity was withdrawn.
+    if ctx.accounts.position.liquidity > 0 {
+        let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
+        let (position_update, reward_infos) = calculate_fee_and_reward_growths(
+            globalpool,
+            &ctx.accounts.position,
+            &ctx.accounts.tick_array_lower,
+            &ctx.accounts.tick_array_upper,
+            timestamp,
+        )?;
+        globalpool.update_rewards(reward_infos, timestamp);
+        (&mut ctx.accounts.position).update(&position_update);
+    }
+
+    // Pay out what the vault holds, leaving the rest owed until the vault is refilled
+    let reward_owed = ctx.accounts.position.reward_infos[reward_index].amount_owed;
+    let reward_amount = reward_owed.min(ctx.accounts.reward_vault.amount);
+
+    (&mut ctx.accounts.position).update_reward_owed(reward_index, reward_owed - reward_amount);
+
+    transfer_from_vault_to_owner(
+        globalpool,
+        &ctx.accounts.reward_vault,
+        &ctx.accounts.reward_owner_account,
+        &ctx.accounts.token_program,
+        reward_amount,
+    )?;
+
+    emit!(RewardCollected {
+        globalpool: ctx.accounts.globalpool.key(),
+        position: ctx.accounts.position.key(),
+        reward_index: params.reward_index,
+        reward_amount,
+    });
+
+    Ok(())
+}
@@ -19,13 +19,24 @@ pub struct CreatePool<'info> {
     )]
     pub clad: Box<Account<'info, Clad>>,
 
+    #[account(
+        seeds = [
+            b"fee_tier".as_ref(),
+            clad.key().as_ref(),
+            params.tick_spacing.to_le_bytes().as_ref(),
+        ],
+        bump,
+        has_one = clad,
+    )]
+    pub fee_tier: Box<Account<'info, FeeTier>>,
+
     #[account(
         init,
         seeds = [
             b"globalpool".as_ref(),
             token_mint_a.key().as_ref(),
             token_mint_b.key().as_ref(),
-            params.fee_rate.to_le_bytes().as_ref(),
+            fee_tier.fee_rate_seed.to_le_bytes().as_ref(),
             params.tick_spacing.to_le_bytes().as_ref(),
         ],
         bump,
@@ -56,11 +67,6 @@ pub struct CreatePool<'info> {
     )]
     pub token_vault_b: Box<Account<'info, TokenAccount>>,
 
-    // Need to read from Pyth to calculate collateral amount. We read both Token A and B since 
-    // the prices are returned in USD and we calculate collateral denominated in either token A or B.
-    // pub token_price_feed_a: Account<'info, PriceFeed>,
-    // pub token_price_feed_b: Account<'info, PriceFeed>,
-
     #[account(address = token::ID)]
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -71,7 +77,6 @@ pub struct CreatePool<'info> {
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct CreatePoolParams {
-    pub fee_rate: u16,
     pub tick_spacing: u16,
     pub initial_sqrt_price: u128,
 }
@@ -89,14 +94,13 @@ pub fn create_pool(ctx: Context<CreatePool>, params: &CreatePoolParams) -> Resul
         globalpool_bump,
         params.tick_spacing,
         params.initial_sqrt_price,
-        params.fee_rate,
+        ctx.accounts.fee_tier.default_fee_rate,
+        ctx.accounts.fee_tier.fee_rate_seed,
         clad.protocol_fee_rate,
-        ctx.accounts.funder.key(),
+        clad.admin,
         ctx.accounts.token_mint_a.key(),
         ctx.accounts.token_vault_a.key(),
         ctx.accounts.token_mint_b.key(),
         ctx.accounts.token_vault_b.key(),
-        // ctx.accounts.token_price_feed_a.key(),
-        // ctx.accounts.token_price_feed_b.key(),
     )?)
 }
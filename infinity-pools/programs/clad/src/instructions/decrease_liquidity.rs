@@ -2,11 +2,16 @@ use {
     super::ModifyLiquidity,
     crate::{
         errors::ErrorCode,
+        events::{LiquidityDecreased, LiquidityWithdrawalQueued},
         manager::liquidity_manager::{
             calculate_liquidity_token_deltas, calculate_modify_liquidity,
+            calculate_partial_token_min, calculate_position_interest_growths,
+            calculate_withdrawable_liquidity, checkpoint_position_tick_interests,
             sync_modify_liquidity_values,
         },
+        manager::position_manager::next_position_interest_update,
         math::convert_to_liquidity_delta,
+        state::TickInterest,
         util::{to_timestamp_u64, transfer_from_vault_to_owner, verify_position_authority},
     },
     anchor_lang::prelude::*,
@@ -21,70 +26,205 @@ pub struct DecreaseLiquidityParams {
 
 /*
   Removes liquidity from an existing Globalpool Position.
+
+  Liquidity borrowed by trade positions at the position's ticks can't be withdrawn, so only the
+  idle liquidity is withdrawn right away. The rest is queued on the position for withdrawal, and
+  is redeemed with `redeem_queued_liquidity` once trade positions repay. Queued liquidity is
+  reserved at the ticks, which needs their TickInterest accounts.
 */
 pub fn decrease_liquidity(
     ctx: Context<ModifyLiquidity>,
     params: &DecreaseLiquidityParams,
 ) -> Result<()> {
+    require!(
+        ctx.accounts.clad.permissions.allow_remove_liquidity,
+        ErrorCode::RemoveLiquidityNotAllowed
+    );
+
     verify_position_authority(
         &ctx.accounts.position_token_account,
         &ctx.accounts.position_authority,
     )?;
 
-    let clock = Clock::get()?;
-
     if params.liquidity_amount == 0 {
         return Err(ErrorCode::LiquidityZero.into());
     }
-    let liquidity_delta = convert_to_liquidity_delta(params.liquidity_amount, false)?;
-    let timestamp = to_timestamp_u64(clock.unix_timestamp)?;
 
-    let update = calculate_modify_liquidity(
+    // Liquidity already queued for withdrawal can only be redeemed
+    let position = &ctx.accounts.position;
+    if params.liquidity_amount > position.liquidity - position.liquidity_queued {
+        return Err(ErrorCode::LiquidityUnderflow.into());
+    }
+
+    let liquidity_withdrawable = calculate_withdrawable_liquidity(
         &ctx.accounts.globalpool,
-        &ctx.accounts.position,
+        position,
         &ctx.accounts.tick_array_lower,
         &ctx.accounts.tick_array_upper,
+        &ctx.accounts.tick_interest_lower,
+        &ctx.accounts.tick_interest_upper,
+        &ctx.accounts.limit_order_array_lower,
+        &ctx.accounts.limit_order_array_upper,
+        false,
+    )?;
+    let liquidity_withdrawn = params.liquidity_amount.min(liquidity_withdrawable);
+    let liquidity_queued_delta = params.liquidity_amount - liquidity_withdrawn;
+
+    if liquidity_withdrawn > 0 {
+        let token_min_a = calculate_partial_token_min(
+            params.token_min_a,
+            liquidity_withdrawn,
+            params.liquidity_amount,
+        )?;
+        let token_min_b = calculate_partial_token_min(
+            params.token_min_b,
+            liquidity_withdrawn,
+            params.liquidity_amount,
+        )?;
+
+        withdraw_liquidity(ctx.accounts, liquidity_withdrawn, token_min_a, token_min_b)?;
+    }
+
+    if liquidity_queued_delta > 0 {
+        let liquidity_queued = ctx
+            .accounts
+            .position
+            .liquidity_queued
+            .checked_add(liquidity_queued_delta)
+            .ok_or(ErrorCode::LiquidityOverflow)?;
+        ctx.accounts
+            .position
+            .update_liquidity_queued(liquidity_queued)?;
+        update_ticks_liquidity_queued(
+            ctx.accounts,
+            convert_to_liquidity_delta(liquidity_queued_delta, true)?,
+        )?;
+
+        emit!(LiquidityWithdrawalQueued {
+            globalpool: ctx.accounts.globalpool.key(),
+            position: ctx.accounts.position.key(),
+            liquidity_amount: liquidity_queued_delta,
+            liquidity_queued,
+        });
+    }
+
+    Ok(())
+}
+
+///
+/// Withdraws `liquidity_amount` of the position's liquidity, which must be idle at its ticks, and
+/// transfers the tokens to the owner.
+///
+/// Callers must verify the position authority beforehand.
+///
+pub fn withdraw_liquidity(
+    accounts: &mut ModifyLiquidity,
+    liquidity_amount: u128,
+    token_min_a: u64,
+    token_min_b: u64,
+) -> Result<()> {
+    let liquidity_delta = convert_to_liquidity_delta(liquidity_amount, false)?;
+    let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
+
+    // Accrue interest owed at the position's current liquidity before modifying it
+    let (interest_growth_a, interest_growth_b) = calculate_position_interest_growths(
+        &accounts.tick_interest_lower,
+        &accounts.tick_interest_upper,
+        timestamp,
+    )?;
+    let interest_update =
+        next_position_interest_update(&accounts.position, interest_growth_a, interest_growth_b);
+    accounts.position.update_interest(&interest_update);
+
+    let update = calculate_modify_liquidity(
+        &accounts.globalpool,
+        &accounts.position,
+        &accounts.tick_array_lower,
+        &accounts.tick_array_upper,
         liquidity_delta,
         timestamp,
     )?;
 
     sync_modify_liquidity_values(
-        &mut ctx.accounts.globalpool,
-        &mut ctx.accounts.position,
-        &ctx.accounts.tick_array_lower,
-        &ctx.accounts.tick_array_upper,
+        &mut accounts.globalpool,
+        &mut accounts.position,
+        &accounts.tick_array_lower,
+        &accounts.tick_array_upper,
         update,
+        timestamp,
+    )?;
+
+    checkpoint_position_tick_interests(
+        &accounts.globalpool,
+        &accounts.position,
+        &accounts.tick_array_lower,
+        &accounts.tick_array_upper,
+        &accounts.tick_interest_lower,
+        &accounts.tick_interest_upper,
+        &accounts.limit_order_array_lower,
+        &accounts.limit_order_array_upper,
+        timestamp,
     )?;
 
     let (delta_a, delta_b) = calculate_liquidity_token_deltas(
-        ctx.accounts.position.tick_lower_index,
-        ctx.accounts.position.tick_upper_index,
-        ctx.accounts.globalpool.tick_current_index,
-        ctx.accounts.globalpool.sqrt_price,
+        accounts.position.tick_lower_index,
+        accounts.position.tick_upper_index,
+        accounts.globalpool.tick_current_index,
+        accounts.globalpool.sqrt_price,
         liquidity_delta,
     )?;
 
-    if delta_a < params.token_min_a {
+    if delta_a < token_min_a {
         return Err(ErrorCode::TokenMinSubceeded.into());
-    } else if delta_b < params.token_min_b {
+    } else if delta_b < token_min_b {
         return Err(ErrorCode::TokenMinSubceeded.into());
     }
 
     transfer_from_vault_to_owner(
-        &ctx.accounts.globalpool,
-        &ctx.accounts.token_vault_a,
-        &ctx.accounts.token_owner_account_a,
-        &ctx.accounts.token_program,
+        &accounts.globalpool,
+        &accounts.token_vault_a,
+        &accounts.token_owner_account_a,
+        &accounts.token_program,
         delta_a,
     )?;
 
     transfer_from_vault_to_owner(
-        &ctx.accounts.globalpool,
-        &ctx.accounts.token_vault_b,
-        &ctx.accounts.token_owner_account_b,
-        &ctx.accounts.token_program,
+        &accounts.globalpool,
+        &accounts.token_vault_b,
+        &accounts.token_owner_account_b,
+        &accounts.token_program,
         delta_b,
     )?;
 
+    emit!(LiquidityDecreased {
+        globalpool: accounts.globalpool.key(),
+        position: accounts.position.key(),
+        liquidity_amount,
+        token_amount_a: delta_a,
+        token_amount_b: delta_b,
+        sqrt_price: accounts.globalpool.sqrt_price,
+        tick_current_index: accounts.globalpool.tick_current_index,
+    });
+
+    Ok(())
+}
+
+///
+/// Reserves `liquidity_delta` more liquidity queued for withdrawal at the position's ticks, or
+/// releases it if negative. Both TickInterest accounts must be initialized.
+///
+pub fn update_ticks_liquidity_queued(
+    accounts: &ModifyLiquidity,
+    liquidity_delta: i128,
+) -> Result<()> {
+    for tick_interest in [&accounts.tick_interest_lower, &accounts.tick_interest_upper] {
+        if tick_interest.owner != &crate::ID || tick_interest.data_is_empty() {
+            return Err(ErrorCode::TickInterestNotInitialized.into());
+        }
+
+        let mut tick_interest = Account::<TickInterest>::try_from(tick_interest.as_ref())?;
+        tick_interest.update_liquidity_queued(liquidity_delta)?;
+        tick_interest.exit(&crate::ID)?;
+    }
     Ok(())
 }
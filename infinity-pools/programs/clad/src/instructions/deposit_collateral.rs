@@ -0,0 +1,99 @@
+use {
+    crate::{
+        errors::ErrorCode,
+        state::*,
+        util::{transfer_from_owner_to_vault, verify_position_authority},
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::token::{self, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct DepositCollateral<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub globalpool: Box<Account<'info, Globalpool>>,
+
+    #[account(mut, has_one = globalpool)]
+    pub position: Box<Account<'info, TradePosition>>,
+
+    #[account(
+        associated_token::mint = position.position_mint,
+        associated_token::authority = owner,
+	)]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = globalpool.token_mint_a)]
+    pub token_owner_account_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, address = globalpool.token_vault_a)]
+    pub token_vault_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = globalpool.token_mint_b)]
+    pub token_owner_account_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, address = globalpool.token_vault_b)]
+    pub token_vault_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DepositCollateralParams {
+    pub collateral_amount: u64,
+}
+
+pub fn deposit_collateral(
+    ctx: Context<DepositCollateral>,
+    params: &DepositCollateralParams,
+) -> Result<()> {
+    verify_position_authority(&ctx.accounts.position_token_account, &ctx.accounts.owner)?;
+
+    // Repaid positions only await closing
+    require!(
+        !TradePosition::is_position_empty(&ctx.accounts.position),
+        ErrorCode::InvalidLoanParameters
+    );
+
+    require!(
+        params.collateral_amount > 0,
+        ErrorCode::ZeroCollateralAmount
+    );
+
+    // borrow a => collateral is b (vice versa)
+    let (collateral_token_owner_account, collateral_token_vault) =
+        if ctx.accounts.position.is_borrow_a(&ctx.accounts.globalpool) {
+            (
+                &ctx.accounts.token_owner_account_b,
+                &ctx.accounts.token_vault_b,
+            )
+        } else {
+            (
+                &ctx.accounts.token_owner_account_a,
+                &ctx.accounts.token_vault_a,
+            )
+        };
+
+    transfer_from_owner_to_vault(
+        &ctx.accounts.owner,
+        collateral_token_owner_account,
+        collateral_token_vault,
+        &ctx.accounts.token_program,
+        params.collateral_amount,
+    )?;
+
+    let collateral_amount = ctx
+        .accounts
+        .position
+        .collateral_amount
+        .checked_add(params.collateral_amount)
+        .ok_or(ErrorCode::CollateralCalculationError)?;
+
+    ctx.accounts
+        .position
+        .update_collateral_amount(collateral_amount);
+
+    Ok(())
+}
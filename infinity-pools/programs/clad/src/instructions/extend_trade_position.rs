@@ -0,0 +1,196 @@
+use {
+    crate::{
+        errors::ErrorCode,
+        manager::loan_manager,
+        state::*,
+        util::{to_timestamp_u64, transfer_from_owner_to_vault, verify_position_authority},
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::token::{self, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct ExtendTradePosition<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [b"clad"], bump = clad.clad_bump)]
+    pub clad: Box<Account<'info, Clad>>,
+
+    #[account(mut)]
+    pub globalpool: Box<Account<'info, Globalpool>>,
+
+    #[account(mut, has_one = globalpool)]
+    pub position: Box<Account<'info, TradePosition>>,
+
+    #[account(
+        associated_token::mint = position.position_mint,
+        associated_token::authority = owner,
+	)]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = globalpool.token_mint_a)]
+    pub token_owner_account_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, address = globalpool.token_vault_a)]
+    pub token_vault_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = globalpool.token_mint_b)]
+    pub token_owner_account_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, address = globalpool.token_vault_b)]
+    pub token_vault_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(has_one = globalpool)]
+    pub tick_array_lower: AccountLoader<'info, TickArray>,
+
+    #[account(has_one = globalpool)]
+    pub tick_array_upper: AccountLoader<'info, TickArray>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"tick_interest".as_ref(),
+            globalpool.key().as_ref(),
+            position.tick_lower_index.to_le_bytes().as_ref()
+        ],
+        bump,
+    )]
+    pub tick_interest_lower: Box<Account<'info, TickInterest>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"tick_interest".as_ref(),
+            globalpool.key().as_ref(),
+            position.tick_upper_index.to_le_bytes().as_ref()
+        ],
+        bump,
+    )]
+    pub tick_interest_upper: Box<Account<'info, TickInterest>>,
+
+    /// CHECK: Limit orders of the lower tick array, created before the first order in the array
+    #[account(seeds = [b"limit_order_array", tick_array_lower.key().as_ref()], bump)]
+    pub limit_order_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Limit orders of the upper tick array, created before the first order in the array
+    #[account(seeds = [b"limit_order_array", tick_array_upper.key().as_ref()], bump)]
+    pub limit_order_array_upper: UncheckedAccount<'info>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ExtendTradePositionParams {
+    // Additional lifetime of loan, in seconds
+    pub additional_duration: u64,
+}
+
+pub fn extend_trade_position(
+    ctx: Context<ExtendTradePosition>,
+    params: &ExtendTradePositionParams,
+) -> Result<()> {
+    require!(
+        ctx.accounts.clad.permissions.allow_open_position,
+        ErrorCode::OpenPositionNotAllowed
+    );
+
+    verify_position_authority(&ctx.accounts.position_token_account, &ctx.accounts.owner)?;
+
+    // Repaid positions only await closing
+    require!(
+        !TradePosition::is_position_empty(&ctx.accounts.position),
+        ErrorCode::InvalidLoanParameters
+    );
+
+    ctx.accounts.position.extend_duration(
+        params.additional_duration,
+        ctx.accounts.globalpool.max_loan_duration,
+    )?;
+
+    // Positions that accrue interest pay for the extra time on repayment
+    if ctx.accounts.position.is_interest_accrued {
+        return Ok(());
+    }
+
+    //
+    // Re-price the interest at the current utilization of the borrowed ticks, and transfer the
+    // prorated interest for the extra time from trader to vault (in collateral token, like opening).
+    //
+
+    let is_borrow_a = ctx.accounts.position.is_borrow_a(&ctx.accounts.globalpool);
+
+    let loan_interest_annual_bps = loan_manager::calculate_position_interest_rate_annual(
+        &ctx.accounts.globalpool,
+        &ctx.accounts.position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+    )?;
+
+    // Interest is charged on the collateral required for the borrowed liquidity, like opening, not
+    // on the collateral deposited, which the trader can withdraw before extending.
+    let interest_fee_multiplier_amount = loan_manager::calculate_collateral(
+        ctx.accounts.position.liquidity_borrowed,
+        ctx.accounts.position.tick_lower_index,
+        ctx.accounts.position.tick_upper_index,
+        ctx.accounts.position.trade_token_amount,
+        is_borrow_a,
+    )?;
+
+    let prorated_interest_amount = loan_manager::calculate_prorated_interest_amount(
+        interest_fee_multiplier_amount,
+        loan_interest_annual_bps,
+        params.additional_duration,
+    )?;
+
+    msg!("loan_interest_annual_bps: {}", loan_interest_annual_bps);
+    msg!("prorated_interest_amount: {}", prorated_interest_amount);
+
+    let is_interest_fee_in_a = !is_borrow_a;
+
+    let (interest_fee_token_owner_account, interest_fee_token_vault) = if is_interest_fee_in_a {
+        (
+            &ctx.accounts.token_owner_account_a,
+            &ctx.accounts.token_vault_a,
+        )
+    } else {
+        (
+            &ctx.accounts.token_owner_account_b,
+            &ctx.accounts.token_vault_b,
+        )
+    };
+
+    if prorated_interest_amount > 0 {
+        transfer_from_owner_to_vault(
+            &ctx.accounts.owner,
+            interest_fee_token_owner_account,
+            interest_fee_token_vault,
+            &ctx.accounts.token_program,
+            prorated_interest_amount,
+        )?;
+    }
+
+    ctx.accounts
+        .position
+        .update_interest_rate(loan_interest_annual_bps as u32);
+
+    // Pay the interest fee to the liquidity positions lending at the position's ticks, released to
+    // them until the loan matures
+    loan_manager::pay_interest_to_ticks(
+        &mut ctx.accounts.globalpool,
+        &ctx.accounts.position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        &mut ctx.accounts.tick_interest_lower,
+        &mut ctx.accounts.tick_interest_upper,
+        &ctx.accounts.limit_order_array_lower,
+        &ctx.accounts.limit_order_array_upper,
+        prorated_interest_amount,
+        is_interest_fee_in_a,
+        ctx.accounts.position.open_time + ctx.accounts.position.duration,
+        to_timestamp_u64(Clock::get()?.unix_timestamp)?,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use {
+    crate::{errors::ErrorCode, state::*, util::transfer_from_vault_to_owner},
+    anchor_lang::{
+        prelude::*,
+        solana_program::sysvar::instructions::{
+            load_current_index_checked, load_instruction_at_checked,
+        },
+        Discriminator,
+    },
+    anchor_spl::token::{self, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct FlashBorrow<'info> {
+    pub borrower: Signer<'info>,
+
+    #[account(seeds = [b"clad"], bump = clad.clad_bump)]
+    pub clad: Box<Account<'info, Clad>>,
+
+    #[account(mut)]
+    pub globalpool: Box<Account<'info, Globalpool>>,
+
+    #[account(mut, constraint = token_owner_account_a.mint == globalpool.token_mint_a)]
+    pub token_owner_account_a: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = globalpool.token_vault_a)]
+    pub token_vault_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = token_owner_account_b.mint == globalpool.token_mint_b)]
+    pub token_owner_account_b: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = globalpool.token_vault_b)]
+    pub token_vault_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+
+    /// CHECK: instructions sysvar, to find the flash repay later in the transaction
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct FlashBorrowParams {
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
+
+pub fn flash_borrow(ctx: Context<FlashBorrow>, params: &FlashBorrowParams) -> Result<()> {
+    require!(
+        ctx.accounts.clad.permissions.allow_swap,
+        ErrorCode::SwapNotAllowed
+    );
+
+    verify_flash_repay(
+        &ctx.accounts.instructions_sysvar.to_account_info(),
+        ctx.accounts.globalpool.key(),
+    )?;
+
+    ctx.accounts
+        .globalpool
+        .start_flash_loan(params.amount_a, params.amount_b)?;
+
+    let globalpool = &ctx.accounts.globalpool;
+
+    if params.amount_a > 0 {
+        transfer_from_vault_to_owner(
+            globalpool,
+            &ctx.accounts.token_vault_a,
+            &ctx.accounts.token_owner_account_a,
+            &ctx.accounts.token_program,
+            params.amount_a,
+        )?;
+    }
+
+    if params.amount_b > 0 {
+        transfer_from_vault_to_owner(
+            globalpool,
+            &ctx.accounts.token_vault_b,
+            &ctx.accounts.token_owner_account_b,
+            &ctx.accounts.token_program,
+            params.amount_b,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Verify that this instruction is called directly (not through CPI) and that a flash repay of the
+/// same Globalpool follows it in the transaction.
+fn verify_flash_repay(instructions_sysvar: &AccountInfo, globalpool: Pubkey) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)? as usize;
+    let current_instruction = load_instruction_at_checked(current_index, instructions_sysvar)?;
+    require_keys_eq!(
+        current_instruction.program_id,
+        crate::ID,
+        ErrorCode::FlashLoanCpiNotAllowed
+    );
+
+    let mut index = current_index + 1;
+    while let Ok(instruction) = load_instruction_at_checked(index, instructions_sysvar) {
+        // `globalpool` is the second account of FlashRepay
+        if instruction.program_id == crate::ID
+            && instruction
+                .data
+                .starts_with(&crate::instruction::FlashRepay::DISCRIMINATOR)
+            && instruction
+                .accounts
+                .get(1)
+                .map_or(false, |account| account.pubkey == globalpool)
+        {
+            return Ok(());
+        }
+        index += 1;
+    }
+
+    Err(ErrorCode::FlashLoanNotRepaid.into())
+}
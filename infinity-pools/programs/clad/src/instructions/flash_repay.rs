@@ -0,0 +1,92 @@
+use {
+    crate::{errors::ErrorCode, state::*, util::transfer_from_owner_to_vault},
+    anchor_lang::{
+        prelude::*,
+        solana_program::sysvar::instructions::{
+            load_current_index_checked, load_instruction_at_checked,
+        },
+    },
+    anchor_spl::token::{self, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct FlashRepay<'info> {
+    pub borrower: Signer<'info>,
+
+    #[account(mut)]
+    pub globalpool: Box<Account<'info, Globalpool>>,
+
+    #[account(mut, constraint = token_owner_account_a.mint == globalpool.token_mint_a)]
+    pub token_owner_account_a: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = globalpool.token_vault_a)]
+    pub token_vault_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = token_owner_account_b.mint == globalpool.token_mint_b)]
+    pub token_owner_account_b: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = globalpool.token_vault_b)]
+    pub token_vault_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+
+    /// CHECK: instructions sysvar, to reject calls through CPI
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+pub fn flash_repay(ctx: Context<FlashRepay>) -> Result<()> {
+    let instructions_sysvar = &ctx.accounts.instructions_sysvar.to_account_info();
+    let current_index = load_current_index_checked(instructions_sysvar)? as usize;
+    let current_instruction = load_instruction_at_checked(current_index, instructions_sysvar)?;
+    require_keys_eq!(
+        current_instruction.program_id,
+        crate::ID,
+        ErrorCode::FlashLoanCpiNotAllowed
+    );
+
+    let globalpool = &ctx.accounts.globalpool;
+    require!(
+        globalpool.is_flash_loan_in_progress(),
+        ErrorCode::FlashLoanNotInProgress
+    );
+
+    let (amount_a, amount_b) = (
+        globalpool.flash_loan_amount_a,
+        globalpool.flash_loan_amount_b,
+    );
+    let fee_amount_a = globalpool.flash_loan_fee(amount_a)?;
+    let fee_amount_b = globalpool.flash_loan_fee(amount_b)?;
+
+    msg!("flash loan fees: a {}, b {}", fee_amount_a, fee_amount_b);
+
+    let repay_amount_a = amount_a
+        .checked_add(fee_amount_a)
+        .ok_or(ErrorCode::AmountCalcOverflow)?;
+    let repay_amount_b = amount_b
+        .checked_add(fee_amount_b)
+        .ok_or(ErrorCode::AmountCalcOverflow)?;
+
+    if repay_amount_a > 0 {
+        transfer_from_owner_to_vault(
+            &ctx.accounts.borrower,
+            &ctx.accounts.token_owner_account_a,
+            &ctx.accounts.token_vault_a,
+            &ctx.accounts.token_program,
+            repay_amount_a,
+        )?;
+    }
+
+    if repay_amount_b > 0 {
+        transfer_from_owner_to_vault(
+            &ctx.accounts.borrower,
+            &ctx.accounts.token_owner_account_b,
+            &ctx.accounts.token_vault_b,
+            &ctx.accounts.token_program,
+            repay_amount_b,
+        )?;
+    }
+
+    ctx.accounts
+        .globalpool
+        .update_after_flash_loan(fee_amount_a, fee_amount_b)
+}
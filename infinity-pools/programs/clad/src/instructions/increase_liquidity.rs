@@ -1,10 +1,13 @@
 use {
     crate::{
         errors::ErrorCode,
+        events::LiquidityIncreased,
         manager::liquidity_manager::{
             calculate_liquidity_token_deltas, calculate_modify_liquidity,
+            calculate_position_interest_growths, checkpoint_position_tick_interests,
             sync_modify_liquidity_values,
         },
+        manager::position_manager::next_position_interest_update,
         math::convert_to_liquidity_delta,
         state::*,
         util::{to_timestamp_u64, transfer_from_owner_to_vault, verify_position_authority},
@@ -17,6 +20,9 @@ use {
 pub struct ModifyLiquidity<'info> {
     pub position_authority: Signer<'info>,
 
+    #[account(seeds = [b"clad"], bump = clad.clad_bump)]
+    pub clad: Box<Account<'info, Clad>>,
+
     #[account(mut)]
     pub globalpool: Account<'info, Globalpool>,
 
@@ -47,6 +53,30 @@ pub struct ModifyLiquidity<'info> {
     #[account(mut, has_one = globalpool)]
     pub tick_array_upper: AccountLoader<'info, TickArray>,
 
+    /// CHECK: Loans of the position's lower tick, created when a trade position borrows at it
+    #[account(
+        mut,
+        seeds = [b"tick_interest".as_ref(), globalpool.key().as_ref(), position.tick_lower_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub tick_interest_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Loans of the position's upper tick, created when a trade position borrows at it
+    #[account(
+        mut,
+        seeds = [b"tick_interest".as_ref(), globalpool.key().as_ref(), position.tick_upper_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub tick_interest_upper: UncheckedAccount<'info>,
+
+    /// CHECK: Limit orders of the lower tick array, created before the first order in the array
+    #[account(seeds = [b"limit_order_array", tick_array_lower.key().as_ref()], bump)]
+    pub limit_order_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Limit orders of the upper tick array, created before the first order in the array
+    #[account(seeds = [b"limit_order_array", tick_array_upper.key().as_ref()], bump)]
+    pub limit_order_array_upper: UncheckedAccount<'info>,
+
     #[account(address = token::ID)]
     pub token_program: Program<'info, Token>,
 }
@@ -62,6 +92,11 @@ pub fn increase_liquidity(
     ctx: Context<ModifyLiquidity>,
     params: &IncreaseLiquidityParams,
 ) -> Result<()> {
+    require!(
+        ctx.accounts.clad.permissions.allow_add_liquidity,
+        ErrorCode::AddLiquidityNotAllowed
+    );
+
     verify_position_authority(
         &ctx.accounts.position_token_account,
         &ctx.accounts.position_authority,
@@ -73,6 +108,16 @@ pub fn increase_liquidity(
     let liquidity_delta = convert_to_liquidity_delta(params.liquidity_amount, true)?;
     let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
 
+    // Accrue interest owed at the position's current liquidity before modifying it
+    let (interest_growth_a, interest_growth_b) = calculate_position_interest_growths(
+        &ctx.accounts.tick_interest_lower,
+        &ctx.accounts.tick_interest_upper,
+        timestamp,
+    )?;
+    let interest_update =
+        next_position_interest_update(&ctx.accounts.position, interest_growth_a, interest_growth_b);
+    ctx.accounts.position.update_interest(&interest_update);
+
     let update = calculate_modify_liquidity(
         &ctx.accounts.globalpool,
         &ctx.accounts.position,
@@ -104,6 +149,21 @@ pub fn increase_liquidity(
         &ctx.accounts.tick_array_lower,
         &ctx.accounts.tick_array_upper,
         update,
+        timestamp,
+    )?;
+
+    // The liquidity lent at the position's ticks changed, so the interest released there since the
+    // last checkpoint goes to the liquidity lent before
+    checkpoint_position_tick_interests(
+        &ctx.accounts.globalpool,
+        &ctx.accounts.position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        &ctx.accounts.tick_interest_lower,
+        &ctx.accounts.tick_interest_upper,
+        &ctx.accounts.limit_order_array_lower,
+        &ctx.accounts.limit_order_array_upper,
+        timestamp,
     )?;
 
     let (delta_a, delta_b) = calculate_liquidity_token_deltas(
@@ -139,5 +199,15 @@ pub fn increase_liquidity(
         delta_b,
     )?;
 
+    emit!(LiquidityIncreased {
+        globalpool: ctx.accounts.globalpool.key(),
+        position: ctx.accounts.position.key(),
+        liquidity_amount: params.liquidity_amount,
+        token_amount_a: delta_a,
+        token_amount_b: delta_b,
+        sqrt_price: ctx.accounts.globalpool.sqrt_price,
+        tick_current_index: ctx.accounts.globalpool.tick_current_index,
+    });
+
     Ok(())
 }
@@ -39,6 +39,7 @@ pub fn initialize_clad(
         .ok_or(ProgramError::InvalidSeeds)?;
 
     Ok(clad.initialize(
+        ctx.accounts.admin.key(),
         params.permissions,
         params.protocol_fee_rate,
         clad_bump,
@@ -0,0 +1,47 @@
+use {crate::state::*, anchor_lang::prelude::*};
+
+#[derive(Accounts)]
+#[instruction(params: InitializeFeeTierParams)]
+pub struct InitializeFeeTier<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"clad"],
+        bump = clad.clad_bump,
+        has_one = admin,
+    )]
+    pub clad: Box<Account<'info, Clad>>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [
+            b"fee_tier".as_ref(),
+            clad.key().as_ref(),
+            params.tick_spacing.to_le_bytes().as_ref(),
+        ],
+        bump,
+        space = FeeTier::LEN
+    )]
+    pub fee_tier: Account<'info, FeeTier>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct InitializeFeeTierParams {
+    pub tick_spacing: u16,
+    pub default_fee_rate: u16,
+}
+
+pub fn initialize_fee_tier(
+    ctx: Context<InitializeFeeTier>,
+    params: &InitializeFeeTierParams,
+) -> Result<()> {
+    Ok(ctx.accounts.fee_tier.initialize(
+        ctx.accounts.clad.key(),
+        params.tick_spacing,
+        params.default_fee_rate,
+    )?)
+}
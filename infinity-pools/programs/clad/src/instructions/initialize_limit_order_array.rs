@@ -0,0 +1,29 @@
+use {crate::state::*, anchor_lang::prelude::*};
+
+#[derive(Accounts)]
+pub struct InitializeLimitOrderArray<'info> {
+    pub globalpool: Account<'info, Globalpool>,
+
+    #[account(has_one = globalpool)]
+    pub tick_array: AccountLoader<'info, TickArray>,
+
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    #[account(
+        init,
+        payer = funder,
+        seeds = [b"limit_order_array", tick_array.key().as_ref()],
+        bump,
+        space = LimitOrderArray::LEN
+    )]
+    pub limit_order_array: AccountLoader<'info, LimitOrderArray>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_limit_order_array(ctx: Context<InitializeLimitOrderArray>) -> Result<()> {
+    let mut limit_order_array = ctx.accounts.limit_order_array.load_init()?;
+    limit_order_array.initialize(&*ctx.accounts.tick_array.load()?, ctx.accounts.funder.key());
+    Ok(())
+}
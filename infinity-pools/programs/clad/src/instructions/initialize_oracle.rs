@@ -0,0 +1,33 @@
+use {
+    crate::{state::*, util::to_timestamp_u64},
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct InitializeOracle<'info> {
+    pub globalpool: Account<'info, Globalpool>,
+
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    #[account(
+        init,
+        payer = funder,
+        seeds = [b"oracle", globalpool.key().as_ref()],
+        bump,
+        space = Oracle::LEN
+    )]
+    pub oracle: AccountLoader<'info, Oracle>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_oracle(ctx: Context<InitializeOracle>) -> Result<()> {
+    let mut oracle = ctx.accounts.oracle.load_init()?;
+    oracle.initialize(
+        ctx.accounts.globalpool.key(),
+        to_timestamp_u64(Clock::get()?.unix_timestamp)?,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use {
+    crate::state::*,
+    anchor_lang::prelude::*,
+    anchor_spl::token::{self, Mint, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct InitializeReward<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    #[account(address = globalpool.fee_authority)]
+    pub fee_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub globalpool: Box<Account<'info, Globalpool>>,
+
+    pub reward_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = funder,
+        token::mint = reward_mint,
+        token::authority = globalpool
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct InitializeRewardParams {
+    pub reward_index: u8,
+}
+
+pub fn initialize_reward(
+    ctx: Context<InitializeReward>,
+    params: &InitializeRewardParams,
+) -> Result<()> {
+    // The fee authority starts as the reward authority, which sets the reward's emissions
+    Ok(ctx.accounts.globalpool.initialize_reward(
+        params.reward_index as usize,
+        ctx.accounts.reward_mint.key(),
+        ctx.accounts.reward_vault.key(),
+        ctx.accounts.fee_authority.key(),
+    )?)
+}
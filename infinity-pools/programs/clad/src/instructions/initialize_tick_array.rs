@@ -27,5 +27,9 @@ pub struct InitializeTickArrayParams {
 
 pub fn initialize_tick_array(ctx: Context<InitializeTickArray>, params: &InitializeTickArrayParams) -> Result<()> {
     let mut tick_array = ctx.accounts.tick_array.load_init()?;
-    Ok(tick_array.initialize(&ctx.accounts.globalpool, params.start_tick_index)?)
+    Ok(tick_array.initialize(
+        &ctx.accounts.globalpool,
+        params.start_tick_index,
+        ctx.accounts.funder.key(),
+    )?)
 }
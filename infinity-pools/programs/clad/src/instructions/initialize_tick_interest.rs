@@ -0,0 +1,40 @@
+use {crate::errors::ErrorCode, crate::state::*, anchor_lang::prelude::*};
+
+#[derive(Accounts)]
+#[instruction(params: InitializeTickInterestParams)]
+pub struct InitializeTickInterest<'info> {
+    pub globalpool: Account<'info, Globalpool>,
+
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    #[account(
+        init,
+        payer = funder,
+        seeds = [b"tick_interest".as_ref(), globalpool.key().as_ref(), params.tick_index.to_le_bytes().as_ref()],
+        bump,
+        space = TickInterest::LEN
+    )]
+    pub tick_interest: Account<'info, TickInterest>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct InitializeTickInterestParams {
+    tick_index: i32,
+}
+
+pub fn initialize_tick_interest(
+    ctx: Context<InitializeTickInterest>,
+    params: &InitializeTickInterestParams,
+) -> Result<()> {
+    if !Tick::check_is_usable_tick(params.tick_index, ctx.accounts.globalpool.tick_spacing) {
+        return Err(ErrorCode::InvalidTickIndex.into());
+    }
+
+    ctx.accounts
+        .tick_interest
+        .initialize(ctx.accounts.globalpool.key(), params.tick_index);
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use {
+    super::{settle_trade_position, RepayTradePosition, RepayTradePositionParams},
+    crate::{
+        errors::ErrorCode,
+        manager::{loan_manager, oracle_manager},
+        math::sqrt_price_from_tick_index,
+        state::Oracle,
+        util::{to_timestamp_u64, verify_position_token_held},
+    },
+    anchor_lang::prelude::*,
+};
+
+///
+/// Liquidates a trade position whose value (trade token & collateral) over its debt has fallen
+/// below the maintenance margin the pool had when the position was opened. Unlike
+/// `repay_trade_position`, the position doesn't have to be matured.
+///
+/// The position must be below the margin both at the current pool price and at the pool's
+/// time-weighted average price, if the pool has an oracle, so a price pushed for a single block
+/// can't liquidate healthy positions.
+///
+/// Liquidations unwind positions like repayments do, so they are paused along with them by the
+/// `allow_close_position` permission.
+///
+pub fn liquidate_trade_position(
+    ctx: Context<RepayTradePosition>,
+    params: &RepayTradePositionParams,
+) -> Result<()> {
+    require!(
+        ctx.accounts.clad.permissions.allow_close_position,
+        ErrorCode::ClosePositionNotAllowed
+    );
+
+    verify_position_token_held(&ctx.accounts.position_token_account)?;
+
+    let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
+    ctx.accounts.globalpool.update_borrow_index(timestamp)?;
+
+    let globalpool = &ctx.accounts.globalpool;
+    let position = &ctx.accounts.position;
+    let is_borrow_a = position.is_borrow_a(globalpool);
+
+    // Accrued interest is owed on top of the borrowed liquidity
+    let liquidity_owed = position
+        .liquidity_borrowed
+        .checked_add(position.interest_owed_liquidity(globalpool.borrow_index)?)
+        .ok_or(ErrorCode::LiquidityOverflow)?;
+
+    let health_at = |tick_index: i32, sqrt_price: u128| {
+        loan_manager::calculate_trade_position_health_bps(
+            liquidity_owed,
+            position.tick_lower_index,
+            position.tick_upper_index,
+            tick_index,
+            sqrt_price,
+            position.trade_token_amount,
+            position.collateral_amount,
+            is_borrow_a,
+        )
+    };
+
+    let health_bps = health_at(globalpool.tick_current_index, globalpool.sqrt_price)?;
+    msg!("health_bps: {}", health_bps);
+
+    require!(
+        health_bps < position.maintenance_margin_bps as u128,
+        ErrorCode::TradePositionNotLiquidatable
+    );
+
+    let twap_tick_index = oracle_manager::observe_twap_tick(
+        &ctx.accounts.oracle,
+        timestamp,
+        Oracle::LIQUIDATION_TWAP_SECONDS,
+        globalpool.tick_current_index,
+    )?;
+
+    if let Some(twap_tick_index) = twap_tick_index {
+        let twap_health_bps =
+            health_at(twap_tick_index, sqrt_price_from_tick_index(twap_tick_index))?;
+        msg!("twap_health_bps: {}", twap_health_bps);
+
+        require!(
+            twap_health_bps < position.maintenance_margin_bps as u128,
+            ErrorCode::TradePositionNotLiquidatable
+        );
+    }
+
+    let is_liquidating = ctx.accounts.liquidator.key != ctx.accounts.owner.key;
+
+    let repayment = ctx
+        .accounts
+        .position
+        .full_repayment(ctx.accounts.globalpool.borrow_index)?;
+
+    settle_trade_position(
+        ctx.accounts,
+        ctx.remaining_accounts,
+        params,
+        &repayment,
+        is_liquidating,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use {
+    crate::{
+        errors::ErrorCode,
+        events::LiquidityPositionsMerged,
+        manager::liquidity_manager::sync_position_growths,
+        state::*,
+        util::{burn_and_close_user_position_token, to_timestamp_u64, verify_position_authority},
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::token::{self, Mint, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct MergeLiquidityPositions<'info> {
+    pub position_authority: Signer<'info>,
+
+    /// CHECK: safe, for receiving rent only
+    #[account(mut)]
+    pub receiver: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub globalpool: Box<Account<'info, Globalpool>>,
+
+    #[account(mut, has_one = globalpool)]
+    pub position: Box<Account<'info, LiquidityPosition>>,
+
+    #[account(
+        constraint = position_token_account.mint == position.position_mint,
+        constraint = position_token_account.amount == 1
+    )]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = globalpool,
+        close = receiver,
+        constraint = merged_position.key() != position.key()
+    )]
+    pub merged_position: Box<Account<'info, LiquidityPosition>>,
+
+    #[account(mut, address = merged_position.position_mint)]
+    pub merged_position_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = merged_position_token_account.mint == merged_position.position_mint,
+        constraint = merged_position_token_account.amount == 1
+    )]
+    pub merged_position_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(has_one = globalpool)]
+    pub tick_array_lower: AccountLoader<'info, TickArray>,
+
+    #[account(has_one = globalpool)]
+    pub tick_array_upper: AccountLoader<'info, TickArray>,
+
+    /// CHECK: Interest growths of the positions' lower tick, created when a trade position borrows at it
+    #[account(
+        seeds = [b"tick_interest".as_ref(), globalpool.key().as_ref(), position.tick_lower_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub tick_interest_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Interest growths of the positions' upper tick, created when a trade position borrows at it
+    #[account(
+        seeds = [b"tick_interest".as_ref(), globalpool.key().as_ref(), position.tick_upper_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub tick_interest_upper: UncheckedAccount<'info>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+}
+
+/*
+  Merges a position into another position on the same range, moving its liquidity and the fees,
+  rewards and interest it is owed. The merged position is closed and its position token burned.
+
+  Both positions are first synced to the current growths of the range, so they share the same
+  checkpoints when merged.
+*/
+pub fn merge_liquidity_positions(ctx: Context<MergeLiquidityPositions>) -> Result<()> {
+    verify_position_authority(
+        &ctx.accounts.position_token_account,
+        &ctx.accounts.position_authority,
+    )?;
+    verify_position_authority(
+        &ctx.accounts.merged_position_token_account,
+        &ctx.accounts.position_authority,
+    )?;
+
+    let position = &ctx.accounts.position;
+    let merged_position = &ctx.accounts.merged_position;
+    if merged_position.tick_lower_index != position.tick_lower_index
+        || merged_position.tick_upper_index != position.tick_upper_index
+    {
+        return Err(ErrorCode::LiquidityPositionRangeMismatch.into());
+    }
+
+    let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
+
+    for position in [
+        &mut ctx.accounts.position,
+        &mut ctx.accounts.merged_position,
+    ] {
+        // Positions without liquidity have nothing left to accrue
+        if position.liquidity == 0 {
+            continue;
+        }
+        sync_position_growths(
+            &mut ctx.accounts.globalpool,
+            position,
+            &ctx.accounts.tick_array_lower,
+            &ctx.accounts.tick_array_upper,
+            &ctx.accounts.tick_interest_lower,
+            &ctx.accounts.tick_interest_upper,
+            timestamp,
+        )?;
+    }
+
+    ctx.accounts.position.merge(&ctx.accounts.merged_position)?;
+
+    burn_and_close_user_position_token(
+        &ctx.accounts.position_authority,
+        &ctx.accounts.receiver,
+        &ctx.accounts.merged_position_mint,
+        &ctx.accounts.merged_position_token_account,
+        &ctx.accounts.token_program,
+    )?;
+
+    emit!(LiquidityPositionsMerged {
+        globalpool: ctx.accounts.globalpool.key(),
+        position: ctx.accounts.position.key(),
+        merged_position: ctx.accounts.merged_position.key(),
+        liquidity_amount: ctx.accounts.merged_position.liquidity,
+    });
+
+    Ok(())
+}
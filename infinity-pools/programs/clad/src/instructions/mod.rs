@@ -1,20 +1,71 @@
+pub mod accept_fee_authority;
+pub mod admin_close_tick_array;
+pub mod cancel_limit_order;
+pub mod claim_limit_order;
 pub mod close_liquidity_position;
+pub mod close_tick_array;
 pub mod close_trade_position;
 pub mod collect_fees;
+pub mod collect_interest;
 pub mod collect_protocol_fees;
+pub mod collect_reward;
 pub mod create_pool;
 pub mod decrease_liquidity;
+pub mod deposit_collateral;
+pub mod extend_trade_position;
+pub mod flash_borrow;
+pub mod flash_repay;
 pub mod increase_liquidity;
 pub mod initialize_clad;
+pub mod initialize_fee_tier;
+pub mod initialize_limit_order_array;
+pub mod initialize_oracle;
+pub mod initialize_reward;
 pub mod initialize_tick_array;
+pub mod initialize_tick_interest;
+pub mod liquidate_trade_position;
+pub mod merge_liquidity_positions;
+pub mod open_limit_order;
 pub mod open_liquidity_position;
 pub mod open_trade_position;
+pub mod partial_repay_trade_position;
+pub mod rebalance_liquidity_position;
+pub mod redeem_queued_liquidity;
 pub mod repay_trade_position;
+pub mod set_default_fee_rate;
+pub mod set_dynamic_fee_config;
+pub mod set_fee_authority;
+pub mod set_fee_rate;
+pub mod set_flash_loan_fee_rate;
+pub mod set_interest_accrual;
+pub mod set_interest_rate_model;
+pub mod set_liquidation_config;
+pub mod set_max_loan_duration;
+pub mod set_permissions;
+pub mod set_price_feeds;
+pub mod set_protocol_fee_rate;
+pub mod set_reward_emissions;
+pub mod set_swap_router;
+pub mod split_liquidity_position;
 pub mod swap;
+pub mod two_hop_swap;
+pub mod withdraw_collateral;
 
 pub use {
-    close_liquidity_position::*, close_trade_position::*, collect_fees::*,
-    collect_protocol_fees::*, create_pool::*, decrease_liquidity::*, increase_liquidity::*,
-    initialize_clad::*, initialize_tick_array::*, open_liquidity_position::*,
-    open_trade_position::*, repay_trade_position::*, swap::*,
+    accept_fee_authority::*, admin_close_tick_array::*, cancel_limit_order::*,
+    claim_limit_order::*, close_liquidity_position::*, close_tick_array::*,
+    close_trade_position::*, collect_fees::*, collect_interest::*, collect_protocol_fees::*,
+    collect_reward::*, create_pool::*, decrease_liquidity::*, deposit_collateral::*,
+    extend_trade_position::*, flash_borrow::*, flash_repay::*, increase_liquidity::*,
+    initialize_clad::*, initialize_fee_tier::*, initialize_limit_order_array::*,
+    initialize_oracle::*, initialize_reward::*, initialize_tick_array::*,
+    initialize_tick_interest::*, liquidate_trade_position::*, merge_liquidity_positions::*,
+    open_limit_order::*, open_liquidity_position::*, open_trade_position::*,
+    partial_repay_trade_position::*, rebalance_liquidity_position::*, redeem_queued_liquidity::*,
+    repay_trade_position::*, set_default_fee_rate::*, set_dynamic_fee_config::*,
+    set_fee_authority::*, set_fee_rate::*, set_flash_loan_fee_rate::*, set_interest_accrual::*,
+    set_interest_rate_model::*, set_liquidation_config::*, set_max_loan_duration::*,
+    set_permissions::*, set_price_feeds::*, set_protocol_fee_rate::*, set_reward_emissions::*,
+    set_swap_router::*, split_liquidity_position::*, swap::*, two_hop_swap::*,
+    withdraw_collateral::*,
 };
@@ -0,0 +1,234 @@
+use {
+    crate::{
+        errors::ErrorCode,
+        events::LimitOrderOpened,
+        manager::{
+            globalpool_manager::next_globalpool_reward_infos,
+            limit_order_manager::calculate_limit_order_deposit,
+            tick_manager::{next_fee_growths_inside, next_tick_modify_liquidity_update},
+        },
+        math::convert_to_liquidity_delta,
+        state::*,
+        util::{to_timestamp_u64, transfer_from_owner_to_vault},
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::token::{self, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+#[instruction(params: OpenLimitOrderParams)]
+pub struct OpenLimitOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [b"clad"], bump = clad.clad_bump)]
+    pub clad: Box<Account<'info, Clad>>,
+
+    #[account(mut)]
+    pub globalpool: Box<Account<'info, Globalpool>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = LimitOrder::LEN,
+        seeds = [
+            b"limit_order".as_ref(),
+            globalpool.key().as_ref(),
+            owner.key().as_ref(),
+            params.tick_lower_index.to_le_bytes().as_ref()
+        ],
+        bump,
+    )]
+    pub limit_order: Box<Account<'info, LimitOrder>>,
+
+    #[account(mut, has_one = globalpool)]
+    pub tick_array: AccountLoader<'info, TickArray>,
+
+    #[account(mut, seeds = [b"limit_order_array", tick_array.key().as_ref()], bump)]
+    pub limit_order_array: AccountLoader<'info, LimitOrderArray>,
+
+    #[account(mut, constraint = token_owner_account_a.mint == globalpool.token_mint_a)]
+    pub token_owner_account_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = token_owner_account_b.mint == globalpool.token_mint_b)]
+    pub token_owner_account_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = token_vault_a.key() == globalpool.token_vault_a)]
+    pub token_vault_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = token_vault_b.key() == globalpool.token_vault_b)]
+    pub token_vault_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct OpenLimitOrderParams {
+    tick_lower_index: i32,
+    liquidity_amount: u128,
+    token_max: u64,
+}
+
+/*
+  Opens a limit order on the range [tick_lower_index, tick_lower_index + tick_spacing), which must
+  be entirely above the price (converting token A into B) or below it (converting token B into A).
+
+  The order's liquidity is added to the range's ticks, and is removed by the swap that crosses the
+  range, after which the order is claimed with `claim_limit_order`.
+*/
+pub fn open_limit_order(ctx: Context<OpenLimitOrder>, params: &OpenLimitOrderParams) -> Result<()> {
+    require!(
+        ctx.accounts.clad.permissions.allow_add_liquidity,
+        ErrorCode::AddLiquidityNotAllowed
+    );
+
+    if params.liquidity_amount == 0 {
+        return Err(ErrorCode::LiquidityZero.into());
+    }
+
+    let globalpool = &ctx.accounts.globalpool;
+    let tick_spacing = globalpool.tick_spacing;
+    let tick_lower_index = params.tick_lower_index;
+    let tick_upper_index = tick_lower_index + tick_spacing as i32;
+    let liquidity_delta = convert_to_liquidity_delta(params.liquidity_amount, true)?;
+    let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
+
+    let a_to_b = if globalpool.tick_current_index < tick_lower_index {
+        true
+    } else if globalpool.tick_current_index >= tick_upper_index {
+        false
+    } else {
+        return Err(ErrorCode::LimitOrderRangeInPrice.into());
+    };
+
+    let mut tick_array = ctx.accounts.tick_array.load_mut()?;
+    let mut limit_order_array = ctx.accounts.limit_order_array.load_mut()?;
+
+    if !Tick::check_is_usable_tick(tick_lower_index, tick_spacing)
+        || !Tick::check_is_usable_tick(tick_upper_index, tick_spacing)
+        || limit_order_array
+            .range_offset(tick_lower_index, tick_spacing)
+            .is_none()
+    {
+        return Err(ErrorCode::InvalidLimitOrderTickIndex.into());
+    }
+
+    let mut range = limit_order_array.get_range(tick_lower_index, tick_spacing)?;
+    if range.liquidity_unclaimed > 0 {
+        return Err(ErrorCode::LimitOrderRangeUnclaimed.into());
+    }
+    // Orders the swap couldn't fill (see next_limit_order_fill) are past the price
+    if range.has_pending_orders() && range.a_to_b != a_to_b {
+        return Err(ErrorCode::LimitOrderRangeInPrice.into());
+    }
+
+    let next_reward_infos = next_globalpool_reward_infos(globalpool, timestamp)?;
+
+    let tick_lower = *tick_array.get_tick(tick_lower_index, tick_spacing)?;
+    let tick_upper = *tick_array.get_tick(tick_upper_index, tick_spacing)?;
+
+    let (fee_growth_inside_a, fee_growth_inside_b) = next_fee_growths_inside(
+        globalpool.tick_current_index,
+        &tick_lower,
+        tick_lower_index,
+        &tick_upper,
+        tick_upper_index,
+        globalpool.fee_growth_global_a,
+        globalpool.fee_growth_global_b,
+    );
+
+    let tick_lower_update = next_tick_modify_liquidity_update(
+        &tick_lower,
+        tick_lower_index,
+        globalpool.tick_current_index,
+        globalpool.fee_growth_global_a,
+        globalpool.fee_growth_global_b,
+        &next_reward_infos,
+        liquidity_delta,
+        false,
+        false,
+    )?;
+
+    let tick_upper_update = next_tick_modify_liquidity_update(
+        &tick_upper,
+        tick_upper_index,
+        globalpool.tick_current_index,
+        globalpool.fee_growth_global_a,
+        globalpool.fee_growth_global_b,
+        &next_reward_infos,
+        liquidity_delta,
+        true,
+        false,
+    )?;
+
+    tick_array.update_tick(tick_lower_index, tick_spacing, &tick_lower_update)?;
+    tick_array.update_tick(tick_upper_index, tick_spacing, &tick_upper_update)?;
+
+    let had_pending_orders = range.has_pending_orders();
+    range.liquidity = range
+        .liquidity
+        .checked_add(params.liquidity_amount)
+        .ok_or(ErrorCode::LiquidityOverflow)?;
+    range.a_to_b = a_to_b;
+    limit_order_array.update_range(tick_lower_index, tick_spacing, &range)?;
+    tick_array.update_limit_order_range_count(had_pending_orders, true);
+
+    // The Globalpool's liquidity is unchanged, the range being out of price
+    ctx.accounts
+        .globalpool
+        .update_rewards(next_reward_infos, timestamp);
+
+    ctx.accounts.limit_order.open(
+        ctx.accounts.globalpool.key(),
+        ctx.accounts.owner.key(),
+        tick_lower_index,
+        a_to_b,
+        params.liquidity_amount,
+        range.epoch,
+        fee_growth_inside_a,
+        fee_growth_inside_b,
+    );
+
+    let token_amount = calculate_limit_order_deposit(
+        tick_lower_index,
+        tick_spacing,
+        params.liquidity_amount,
+        a_to_b,
+    )?;
+    if token_amount > params.token_max {
+        return Err(ErrorCode::TokenMaxExceeded.into());
+    }
+
+    let (token_owner_account, token_vault) = if a_to_b {
+        (
+            &ctx.accounts.token_owner_account_a,
+            &ctx.accounts.token_vault_a,
+        )
+    } else {
+        (
+            &ctx.accounts.token_owner_account_b,
+            &ctx.accounts.token_vault_b,
+        )
+    };
+    transfer_from_owner_to_vault(
+        &ctx.accounts.owner,
+        token_owner_account,
+        token_vault,
+        &ctx.accounts.token_program,
+        token_amount,
+    )?;
+
+    emit!(LimitOrderOpened {
+        globalpool: ctx.accounts.globalpool.key(),
+        limit_order: ctx.accounts.limit_order.key(),
+        owner: ctx.accounts.owner.key(),
+        tick_lower_index,
+        a_to_b,
+        liquidity_amount: params.liquidity_amount,
+        token_amount,
+    });
+
+    Ok(())
+}
@@ -1,14 +1,16 @@
 use {
     crate::{
         errors::ErrorCode,
+        events::TradePositionOpened,
         manager::{
-            liquidity_manager, loan_manager, swap_manager::execute_jupiter_swap_for_globalpool,
+            liquidity_manager, loan_manager, oracle_manager,
+            swap_manager::execute_swap_for_globalpool,
         },
         math::*,
         state::*,
         util::{
-            mint_position_token_and_remove_authority, sort_token_amount_for_loan,
-            transfer_from_owner_to_vault,
+            mint_position_token_and_remove_authority,
+            to_timestamp_u64, transfer_from_owner_to_vault,
         },
     },
     anchor_lang::prelude::*,
@@ -24,6 +26,9 @@ pub struct OpenTradePosition<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
+    #[account(seeds = [b"clad"], bump = clad.clad_bump)]
+    pub clad: Box<Account<'info, Clad>>,
+
     #[account(mut)]
     pub globalpool: Box<Account<'info, Globalpool>>,
 
@@ -73,19 +78,63 @@ pub struct OpenTradePosition<'info> {
     #[account(address = globalpool.token_mint_b)]
     pub token_mint_b: Box<Account<'info, Mint>>,
 
+    /// CHECK: Pyth feed of Token A, or the default pubkey if the Globalpool has no price feeds
+    #[account(address = globalpool.token_price_feed_a @ ErrorCode::InvalidOracleAccount)]
+    pub token_price_feed_a: UncheckedAccount<'info>,
+
+    /// CHECK: Pyth feed of Token B, or the default pubkey if the Globalpool has no price feeds
+    #[account(address = globalpool.token_price_feed_b @ ErrorCode::InvalidOracleAccount)]
+    pub token_price_feed_b: UncheckedAccount<'info>,
+
     #[account(mut, has_one = globalpool)]
     pub tick_array_lower: AccountLoader<'info, TickArray>,
 
     #[account(mut, has_one = globalpool)]
     pub tick_array_upper: AccountLoader<'info, TickArray>,
 
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = TickInterest::LEN,
+        seeds = [
+            b"tick_interest".as_ref(),
+            globalpool.key().as_ref(),
+            params.tick_lower_index.to_le_bytes().as_ref()
+        ],
+        bump,
+    )]
+    pub tick_interest_lower: Box<Account<'info, TickInterest>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = TickInterest::LEN,
+        seeds = [
+            b"tick_interest".as_ref(),
+            globalpool.key().as_ref(),
+            params.tick_upper_index.to_le_bytes().as_ref()
+        ],
+        bump,
+    )]
+    pub tick_interest_upper: Box<Account<'info, TickInterest>>,
+
+    /// CHECK: Limit orders of the lower tick array, created before the first order in the array
+    #[account(seeds = [b"limit_order_array", tick_array_lower.key().as_ref()], bump)]
+    pub limit_order_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Limit orders of the upper tick array, created before the first order in the array
+    #[account(seeds = [b"limit_order_array", tick_array_upper.key().as_ref()], bump)]
+    pub limit_order_array_upper: UncheckedAccount<'info>,
+
+    /// CHECK: Oracle of the Globalpool, written by Globalpool-routed swaps if it is initialized
+    #[account(mut, seeds = [b"oracle", globalpool.key().as_ref()], bump)]
+    pub oracle: UncheckedAccount<'info>,
+
     #[account(address = token::ID)]
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
-    // For pyth
-    // pub clock: Sysvar<'info, Clock>,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -111,6 +160,20 @@ pub fn open_trade_position<'info>(
     ctx: Context<OpenTradePosition>,
     params: &OpenTradePositionParams,
 ) -> Result<()> {
+    require!(
+        ctx.accounts.clad.permissions.allow_open_position,
+        ErrorCode::OpenPositionNotAllowed
+    );
+
+    oracle_manager::verify_oracle_price_band(
+        &ctx.accounts.globalpool,
+        &ctx.accounts.token_price_feed_a,
+        &ctx.accounts.token_price_feed_b,
+        ctx.accounts.token_mint_a.decimals,
+        ctx.accounts.token_mint_b.decimals,
+        Clock::get()?.unix_timestamp,
+    )?;
+
     let position_mint = &ctx.accounts.position_mint;
 
     let current_tick_index = ctx.accounts.globalpool.tick_current_index;
@@ -119,8 +182,10 @@ pub fn open_trade_position<'info>(
         return Err(ErrorCode::LiquidityZero.into());
     }
 
-    // Loan must be at least 1 hour long and at most 10 days
-    if params.loan_duration < 3600 || params.loan_duration > 864_000 {
+    // Loan must be at least 1 hour long and at most the pool's max loan duration (10 days by default)
+    if params.loan_duration < TradePosition::MIN_LOAN_DURATION
+        || params.loan_duration > ctx.accounts.globalpool.max_loan_duration
+    {
         return Err(ErrorCode::InvalidLoanDuration.into());
     }
 
@@ -152,8 +217,26 @@ pub fn open_trade_position<'info>(
 
     let liquidity_delta = convert_to_liquidity_delta(u128::from(params.liquidity_amount), true)?;
 
+    // Interest paid by trade positions is tracked per tick, from the first loan at the tick
+    let globalpool_key = ctx.accounts.globalpool.key();
+    if !ctx.accounts.tick_interest_lower.is_initialized() {
+        ctx.accounts
+            .tick_interest_lower
+            .initialize(globalpool_key, params.tick_lower_index);
+    }
+    if !ctx.accounts.tick_interest_upper.is_initialized() {
+        ctx.accounts
+            .tick_interest_upper
+            .initialize(globalpool_key, params.tick_upper_index);
+    }
+
     msg!("Requesting loan");
 
+    // Accrue interest at the current utilization before borrowing more liquidity
+    ctx.accounts
+        .globalpool
+        .update_borrow_index(to_timestamp_u64(Clock::get()?.unix_timestamp)?)?;
+
     //
     // 1. Initialize & mint the trade position
     //
@@ -168,6 +251,11 @@ pub fn open_trade_position<'info>(
         0, // to be updated later
     )?;
 
+    if ctx.accounts.globalpool.interest_accrual_enabled {
+        let borrow_index = ctx.accounts.globalpool.borrow_index;
+        ctx.accounts.position.enable_interest_accrual(borrow_index);
+    }
+
     mint_position_token_and_remove_authority(
         &ctx.accounts.globalpool,
         position_mint,
@@ -195,6 +283,29 @@ pub fn open_trade_position<'info>(
         ErrorCode::InvalidLoanParameters
     );
 
+    // Only the idle liquidity of liquidity positions can be borrowed, not that reserved for limit
+    // orders or queued withdrawals
+    let tick_lower_liquidity_borrowable = loan_manager::calculate_tick_liquidity_borrowable(
+        &ctx.accounts.globalpool,
+        &ctx.accounts.tick_array_lower,
+        params.tick_lower_index,
+        &ctx.accounts.tick_interest_lower,
+        &ctx.accounts.limit_order_array_lower,
+    )?;
+    let tick_upper_liquidity_borrowable = loan_manager::calculate_tick_liquidity_borrowable(
+        &ctx.accounts.globalpool,
+        &ctx.accounts.tick_array_upper,
+        params.tick_upper_index,
+        &ctx.accounts.tick_interest_upper,
+        &ctx.accounts.limit_order_array_upper,
+    )?;
+    loan_manager::verify_liquidity_borrowable(
+        u128::from(params.liquidity_amount),
+        tick_lower_liquidity_borrowable,
+        tick_upper_liquidity_borrowable,
+    )?;
+
+    let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
     let update = loan_manager::calculate_modify_loan(
         &ctx.accounts.globalpool,
         &ctx.accounts.position,
@@ -203,6 +314,7 @@ pub fn open_trade_position<'info>(
         liquidity_delta,
         token_borrow_amount as i64,
         true,
+        timestamp,
     )?;
 
     //
@@ -250,7 +362,10 @@ pub fn open_trade_position<'info>(
         &mut ctx.accounts.position,
         &ctx.accounts.tick_array_lower,
         &ctx.accounts.tick_array_upper,
+        &mut ctx.accounts.tick_interest_lower,
+        &mut ctx.accounts.tick_interest_upper,
         &update,
+        timestamp,
     )?;
 
     //
@@ -261,62 +376,40 @@ pub fn open_trade_position<'info>(
 
     msg!("Opening trade position from loan");
 
-    let (initial_loan_vault_balance, initial_swapped_vault_balance) = sort_token_amount_for_loan(
-        &ctx.accounts.token_vault_a,
-        &ctx.accounts.token_vault_b,
-        is_borrow_token_a,
-    );
-
     //
     // TODO: Validate that the receiver of the token swap is the globalpool's token vault
     //
 
-    execute_jupiter_swap_for_globalpool(
-        &ctx.accounts.globalpool,
-        &ctx.remaining_accounts,
+    // Swap all of the loan token to the swapped (trade) token
+    let loan_token_available = ctx.accounts.position.loan_token_available;
+
+    let swap_update = execute_swap_for_globalpool(
+        &mut ctx.accounts.globalpool,
+        &ctx.accounts.clad,
+        &ctx.accounts.oracle,
+        &mut ctx.accounts.token_vault_a,
+        &mut ctx.accounts.token_vault_b,
+        ctx.remaining_accounts,
         &params.swap_instruction_data,
+        loan_token_available,
+        true,
+        is_borrow_token_a,
     )?;
 
     //
     // Verify swap
     //
 
-    // Update token vault amounts
-    ctx.accounts.token_vault_a.reload()?;
-    ctx.accounts.token_vault_b.reload()?;
-
-    let (post_loan_vault_balance, post_swapped_vault_balance) = sort_token_amount_for_loan(
-        &ctx.accounts.token_vault_a,
-        &ctx.accounts.token_vault_b,
-        is_borrow_token_a,
-    );
-
-    // 1. Require that Loan (Borrowed) Token was the swapped to Swapped Token.
-    // => Loan Token balance should decrease
-    // => Swapped Token balance should increase
-    require!(
-        initial_loan_vault_balance > post_loan_vault_balance,
-        ErrorCode::InvalidLoanTradeSwapDirection
-    );
-    require!(
-        initial_swapped_vault_balance < post_swapped_vault_balance,
-        ErrorCode::InvalidLoanTradeSwapDirection
-    );
+    // 1. Loan (Borrowed) Token was swapped to Swapped Token, checked by `execute_swap_for_globalpool`.
 
     // 2. Require that the Loan Token amount was decreased by at most
     //    position.liquidity_available.
 
-    // This calculation should come after checking that the balances were modified legally (1).
-    let swapped_amount_in = initial_loan_vault_balance
-        .checked_sub(post_loan_vault_balance)
-        .unwrap();
-
-    let swapped_amount_out = post_swapped_vault_balance
-        .checked_sub(initial_swapped_vault_balance)
-        .unwrap();
+    let swapped_amount_in = swap_update.amount_in;
+    let swapped_amount_out = swap_update.amount_out;
 
     require!(
-        swapped_amount_in <= ctx.accounts.position.loan_token_available,
+        swapped_amount_in <= loan_token_available,
         ErrorCode::InvalidLoanTradeSwapResult
     );
 
@@ -389,6 +482,7 @@ pub fn open_trade_position<'info>(
     //
     // Calculate & transfer prorated interest from trader to vault (in collateral token).
     // NOTE: This must come after the collateral calculation because it uses the collateral amount
+    // NOTE: Positions that accrue interest pay it on repayment instead (see `repay_trade_position`)
     // TODO: Allow payment of interest in borrowed token as well.
     //
 
@@ -407,41 +501,74 @@ pub fn open_trade_position<'info>(
         interest_fee_token_vault = &ctx.accounts.token_vault_b;
     }
 
-    let annual_interest_amount = interest_fee_multiplier_amount
-        .checked_mul(update.loan_interest_annual_bps as u64)
-        .unwrap();
+    let prorated_interest_amount = if ctx.accounts.position.is_interest_accrued {
+        0
+    } else {
+        loan_manager::calculate_prorated_interest_amount(
+            interest_fee_multiplier_amount,
+            update.loan_interest_annual_bps,
+            params.loan_duration,
+        )?
+    };
 
-    let prorated_interest_amount = annual_interest_amount
-        .checked_mul(params.loan_duration as u64)
-        .unwrap()
-        .checked_div(3_153_600_000) // 31,536,000 sec per yr * 100 bps per 1% (ignore leap years)
-        .unwrap();
+    ctx.accounts
+        .position
+        .update_interest_rate(update.loan_interest_annual_bps as u32);
 
     msg!("collateral_amount: {}", collateral_amount);
     msg!(
         "loan_interst_annual_bps, {}",
         update.loan_interest_annual_bps
     );
-    msg!("annual_interest_amount: {}", annual_interest_amount);
     msg!("prorated_interest_amount: {}", prorated_interest_amount);
 
-    transfer_from_owner_to_vault(
-        &ctx.accounts.owner,
-        interest_fee_token_owner_account,
-        interest_fee_token_vault,
-        &ctx.accounts.token_program,
-        prorated_interest_amount,
-    )?;
+    if prorated_interest_amount > 0 {
+        transfer_from_owner_to_vault(
+            &ctx.accounts.owner,
+            interest_fee_token_owner_account,
+            interest_fee_token_vault,
+            &ctx.accounts.token_program,
+            prorated_interest_amount,
+        )?;
+    }
 
     //
-    // Add the interest fee to the pool fee growth (for LP payout)
+    // Pay the interest fee to the liquidity positions lending at the position's ticks, released to
+    // them until the loan matures
     //
 
-    ctx.accounts.globalpool.update_after_loan(
-        liquidity_delta,
+    loan_manager::pay_interest_to_ticks(
+        &mut ctx.accounts.globalpool,
+        &ctx.accounts.position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        &mut ctx.accounts.tick_interest_lower,
+        &mut ctx.accounts.tick_interest_upper,
+        &ctx.accounts.limit_order_array_lower,
+        &ctx.accounts.limit_order_array_upper,
         prorated_interest_amount,
         is_interest_fee_in_a,
-    );
+        ctx.accounts.position.open_time + ctx.accounts.position.duration,
+        timestamp,
+    )?;
+
+    ctx.accounts
+        .globalpool
+        .update_after_loan(liquidity_delta, 0, is_interest_fee_in_a)?;
+
+    emit!(TradePositionOpened {
+        globalpool: ctx.accounts.globalpool.key(),
+        position: ctx.accounts.position.key(),
+        owner: ctx.accounts.owner.key(),
+        is_borrow_a: is_borrow_token_a,
+        liquidity_borrowed_delta: liquidity_delta,
+        loan_token_swapped: swapped_amount_in,
+        trade_token_amount: swapped_amount_out,
+        collateral_amount,
+        interest_amount: prorated_interest_amount,
+        sqrt_price: ctx.accounts.globalpool.sqrt_price,
+        tick_current_index: ctx.accounts.globalpool.tick_current_index,
+    });
 
     Ok(())
 }
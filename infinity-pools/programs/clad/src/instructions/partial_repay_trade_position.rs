@@ -0,0 +1,126 @@
+use {
+    super::repay_trade_position::*,
+    crate::{
+        errors::ErrorCode,
+        manager::{liquidity_manager, loan_manager},
+        state::*,
+        util::{to_timestamp_u64, transfer_from_vault_to_owner, verify_position_authority},
+    },
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct PartialRepayTradePosition<'info> {
+    pub repay: RepayTradePosition<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PartialRepayTradePositionParams {
+    // Borrowed liquidity to repay, less than the position's
+    pub liquidity_amount: u128,
+    pub swap_instruction_data: Vec<u8>, // Jupiter swap data
+}
+
+pub fn partial_repay_trade_position(
+    ctx: Context<PartialRepayTradePosition>,
+    params: &PartialRepayTradePositionParams,
+) -> Result<()> {
+    let accounts = &mut ctx.accounts.repay;
+
+    require!(
+        accounts.clad.permissions.allow_close_position,
+        ErrorCode::ClosePositionNotAllowed
+    );
+
+    verify_position_authority(&accounts.position_token_account, &accounts.owner)?;
+
+    require!(
+        !TradePosition::is_position_empty(&accounts.position),
+        ErrorCode::InvalidRepaymentAmount
+    );
+
+    let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
+    accounts.globalpool.update_borrow_index(timestamp)?;
+
+    let repayment = accounts
+        .position
+        .partial_repayment(params.liquidity_amount, accounts.globalpool.borrow_index)?;
+
+    msg!("repayment: {:?}", repayment);
+
+    //
+    // 1. Repay the share of the loan, as `repay_trade_position` does for the whole position.
+    //
+
+    let collateral_to_return = settle_trade_position(
+        accounts,
+        ctx.remaining_accounts,
+        &RepayTradePositionParams {
+            swap_instruction_data: params.swap_instruction_data.clone(),
+        },
+        &repayment,
+        false,
+    )?;
+
+    //
+    // 2. Return the leftover collateral of the repaid share to the owner.
+    //
+
+    if collateral_to_return > 0 {
+        let is_borrow_a = accounts.position.is_borrow_a(&accounts.globalpool);
+
+        // borrow a => collateral is b (vice versa)
+        let (collateral_token_vault, collateral_token_owner_account) = if is_borrow_a {
+            (&accounts.token_vault_b, &accounts.token_owner_account_b)
+        } else {
+            (&accounts.token_vault_a, &accounts.token_owner_account_a)
+        };
+
+        transfer_from_vault_to_owner(
+            &accounts.globalpool,
+            collateral_token_vault,
+            collateral_token_owner_account,
+            &accounts.token_program,
+            collateral_to_return,
+        )?;
+
+        let collateral_amount = accounts.position.collateral_amount - collateral_to_return;
+        accounts
+            .position
+            .update_collateral_amount(collateral_amount);
+    }
+
+    //
+    // 3. Give the repaid liquidity back to the ticks, as `close_trade_position` does for the
+    //    whole position. The repaid loan token was made available on the position by step 1.
+    //
+
+    let update = loan_manager::calculate_modify_loan(
+        &accounts.globalpool,
+        &accounts.position,
+        &accounts.tick_array_lower,
+        &accounts.tick_array_upper,
+        -(repayment.liquidity as i128),
+        -(repayment.loan_token_swapped as i64),
+        false,
+        timestamp,
+    )?;
+
+    liquidity_manager::sync_modify_liquidity_values_for_loan(
+        &mut accounts.globalpool,
+        &mut accounts.position,
+        &accounts.tick_array_lower,
+        &accounts.tick_array_upper,
+        &mut accounts.tick_interest_lower,
+        &mut accounts.tick_interest_upper,
+        &update,
+        timestamp,
+    )?;
+
+    let liquidity_borrowed = accounts.position.liquidity_borrowed - repayment.liquidity;
+    accounts
+        .position
+        .update_liquidity_borrowed(liquidity_borrowed);
+
+    Ok(())
+}
@@ -0,0 +1,354 @@
+use {
+    crate::{
+        errors::ErrorCode,
+        events::LiquidityPositionRebalanced,
+        manager::liquidity_manager::{
+            calculate_liquidity_token_deltas, calculate_modify_liquidity,
+            calculate_position_interest_growths, calculate_withdrawable_liquidity,
+            checkpoint_position_tick_interests, sync_modify_liquidity_values,
+        },
+        manager::position_manager::next_position_interest_update,
+        math::convert_to_liquidity_delta,
+        state::*,
+        util::{
+            to_timestamp_u64, transfer_from_owner_to_vault, transfer_from_vault_to_owner,
+            verify_position_authority,
+        },
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::token::{self, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+#[instruction(params: RebalanceLiquidityPositionParams)]
+pub struct RebalanceLiquidityPosition<'info> {
+    pub position_authority: Signer<'info>,
+
+    #[account(seeds = [b"clad"], bump = clad.clad_bump)]
+    pub clad: Box<Account<'info, Clad>>,
+
+    #[account(mut)]
+    pub globalpool: Box<Account<'info, Globalpool>>,
+
+    #[account(mut, has_one = globalpool)]
+    pub position: Box<Account<'info, LiquidityPosition>>,
+
+    #[account(
+        constraint = position_token_account.mint == position.position_mint,
+        constraint = position_token_account.amount == 1
+    )]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = token_owner_account_a.mint == globalpool.token_mint_a)]
+    pub token_owner_account_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = token_owner_account_b.mint == globalpool.token_mint_b)]
+    pub token_owner_account_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = token_vault_a.key() == globalpool.token_vault_a)]
+    pub token_vault_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = token_vault_b.key() == globalpool.token_vault_b)]
+    pub token_vault_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, has_one = globalpool)]
+    pub tick_array_lower: AccountLoader<'info, TickArray>,
+
+    #[account(mut, has_one = globalpool)]
+    pub tick_array_upper: AccountLoader<'info, TickArray>,
+
+    /// CHECK: Loans of the position's lower tick, created when a trade position borrows at it
+    #[account(
+        mut,
+        seeds = [b"tick_interest".as_ref(), globalpool.key().as_ref(), position.tick_lower_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub tick_interest_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Loans of the position's upper tick, created when a trade position borrows at it
+    #[account(
+        mut,
+        seeds = [b"tick_interest".as_ref(), globalpool.key().as_ref(), position.tick_upper_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub tick_interest_upper: UncheckedAccount<'info>,
+
+    /// CHECK: Limit orders of the lower tick array, created before the first order in the array
+    #[account(seeds = [b"limit_order_array", tick_array_lower.key().as_ref()], bump)]
+    pub limit_order_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Limit orders of the upper tick array, created before the first order in the array
+    #[account(seeds = [b"limit_order_array", tick_array_upper.key().as_ref()], bump)]
+    pub limit_order_array_upper: UncheckedAccount<'info>,
+
+    #[account(mut, has_one = globalpool)]
+    pub new_tick_array_lower: AccountLoader<'info, TickArray>,
+
+    #[account(mut, has_one = globalpool)]
+    pub new_tick_array_upper: AccountLoader<'info, TickArray>,
+
+    /// CHECK: Interest growths of the new lower tick, created when a trade position borrows at it
+    #[account(
+        mut,
+        seeds = [b"tick_interest".as_ref(), globalpool.key().as_ref(), params.tick_lower_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub new_tick_interest_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Interest growths of the new upper tick, created when a trade position borrows at it
+    #[account(
+        mut,
+        seeds = [b"tick_interest".as_ref(), globalpool.key().as_ref(), params.tick_upper_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub new_tick_interest_upper: UncheckedAccount<'info>,
+
+    /// CHECK: Limit orders of the new lower tick array, created before the first order in the array
+    #[account(seeds = [b"limit_order_array", new_tick_array_lower.key().as_ref()], bump)]
+    pub new_limit_order_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Limit orders of the new upper tick array, created before the first order in the array
+    #[account(seeds = [b"limit_order_array", new_tick_array_upper.key().as_ref()], bump)]
+    pub new_limit_order_array_upper: UncheckedAccount<'info>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RebalanceLiquidityPositionParams {
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    liquidity_amount: u128,
+    token_max_a: u64,
+    token_max_b: u64,
+    token_min_a: u64,
+    token_min_b: u64,
+}
+
+/*
+  Moves all the liquidity of a position to a new range, depositing `liquidity_amount` there.
+
+  The tokens withdrawn from the current range fund the deposit, so only the difference is
+  transferred: the owner pays at most `token_max` of a token the deposit needs more of, and receives
+  at least `token_min` of a token left over. The fees, rewards and interest owed stay with the
+  position, which earns at the new range from now on.
+*/
+pub fn rebalance_liquidity_position(
+    ctx: Context<RebalanceLiquidityPosition>,
+    params: &RebalanceLiquidityPositionParams,
+) -> Result<()> {
+    require!(
+        ctx.accounts.clad.permissions.allow_add_liquidity,
+        ErrorCode::AddLiquidityNotAllowed
+    );
+    require!(
+        ctx.accounts.clad.permissions.allow_remove_liquidity,
+        ErrorCode::RemoveLiquidityNotAllowed
+    );
+
+    verify_position_authority(
+        &ctx.accounts.position_token_account,
+        &ctx.accounts.position_authority,
+    )?;
+
+    let position = &ctx.accounts.position;
+    if params.liquidity_amount == 0 || position.liquidity == 0 {
+        return Err(ErrorCode::LiquidityZero.into());
+    }
+    if position.liquidity_queued > 0 {
+        return Err(ErrorCode::LiquidityQueued.into());
+    }
+
+    // Liquidity borrowed by trade positions can't be moved until it's repaid
+    let liquidity_removed = position.liquidity;
+    let liquidity_withdrawable = calculate_withdrawable_liquidity(
+        &ctx.accounts.globalpool,
+        position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        &ctx.accounts.tick_interest_lower,
+        &ctx.accounts.tick_interest_upper,
+        &ctx.accounts.limit_order_array_lower,
+        &ctx.accounts.limit_order_array_upper,
+        false,
+    )?;
+    if liquidity_withdrawable < liquidity_removed {
+        return Err(ErrorCode::LiquidityBorrowed.into());
+    }
+
+    let liquidity_delta_removed = convert_to_liquidity_delta(liquidity_removed, false)?;
+    let liquidity_delta_added = convert_to_liquidity_delta(params.liquidity_amount, true)?;
+    let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
+
+    // Withdraw all the liquidity from the current range, accruing what the position earned there
+    let (interest_growth_a, interest_growth_b) = calculate_position_interest_growths(
+        &ctx.accounts.tick_interest_lower,
+        &ctx.accounts.tick_interest_upper,
+        timestamp,
+    )?;
+    let interest_update =
+        next_position_interest_update(&ctx.accounts.position, interest_growth_a, interest_growth_b);
+    ctx.accounts.position.update_interest(&interest_update);
+
+    let update = calculate_modify_liquidity(
+        &ctx.accounts.globalpool,
+        &ctx.accounts.position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        liquidity_delta_removed,
+        timestamp,
+    )?;
+    sync_modify_liquidity_values(
+        &mut ctx.accounts.globalpool,
+        &mut ctx.accounts.position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        update,
+        timestamp,
+    )?;
+    checkpoint_position_tick_interests(
+        &ctx.accounts.globalpool,
+        &ctx.accounts.position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        &ctx.accounts.tick_interest_lower,
+        &ctx.accounts.tick_interest_upper,
+        &ctx.accounts.limit_order_array_lower,
+        &ctx.accounts.limit_order_array_upper,
+        timestamp,
+    )?;
+
+    let (removed_a, removed_b) = calculate_liquidity_token_deltas(
+        ctx.accounts.position.tick_lower_index,
+        ctx.accounts.position.tick_upper_index,
+        ctx.accounts.globalpool.tick_current_index,
+        ctx.accounts.globalpool.sqrt_price,
+        liquidity_delta_removed,
+    )?;
+
+    // Deposit into the new range. Without liquidity, the position is only checkpointed at the
+    // growths of the new range.
+    ctx.accounts.position.reset_range(
+        ctx.accounts.globalpool.tick_spacing,
+        params.tick_lower_index,
+        params.tick_upper_index,
+    )?;
+
+    let (interest_growth_a, interest_growth_b) = calculate_position_interest_growths(
+        &ctx.accounts.new_tick_interest_lower,
+        &ctx.accounts.new_tick_interest_upper,
+        timestamp,
+    )?;
+    let interest_update =
+        next_position_interest_update(&ctx.accounts.position, interest_growth_a, interest_growth_b);
+    ctx.accounts.position.update_interest(&interest_update);
+
+    let update = calculate_modify_liquidity(
+        &ctx.accounts.globalpool,
+        &ctx.accounts.position,
+        &ctx.accounts.new_tick_array_lower,
+        &ctx.accounts.new_tick_array_upper,
+        liquidity_delta_added,
+        timestamp,
+    )?;
+    sync_modify_liquidity_values(
+        &mut ctx.accounts.globalpool,
+        &mut ctx.accounts.position,
+        &ctx.accounts.new_tick_array_lower,
+        &ctx.accounts.new_tick_array_upper,
+        update,
+        timestamp,
+    )?;
+    checkpoint_position_tick_interests(
+        &ctx.accounts.globalpool,
+        &ctx.accounts.position,
+        &ctx.accounts.new_tick_array_lower,
+        &ctx.accounts.new_tick_array_upper,
+        &ctx.accounts.new_tick_interest_lower,
+        &ctx.accounts.new_tick_interest_upper,
+        &ctx.accounts.new_limit_order_array_lower,
+        &ctx.accounts.new_limit_order_array_upper,
+        timestamp,
+    )?;
+
+    let (added_a, added_b) = calculate_liquidity_token_deltas(
+        params.tick_lower_index,
+        params.tick_upper_index,
+        ctx.accounts.globalpool.tick_current_index,
+        ctx.accounts.globalpool.sqrt_price,
+        liquidity_delta_added,
+    )?;
+
+    settle_rebalance(
+        &ctx.accounts,
+        &ctx.accounts.token_owner_account_a,
+        &ctx.accounts.token_vault_a,
+        removed_a,
+        added_a,
+        params.token_max_a,
+        params.token_min_a,
+    )?;
+    settle_rebalance(
+        &ctx.accounts,
+        &ctx.accounts.token_owner_account_b,
+        &ctx.accounts.token_vault_b,
+        removed_b,
+        added_b,
+        params.token_max_b,
+        params.token_min_b,
+    )?;
+
+    emit!(LiquidityPositionRebalanced {
+        globalpool: ctx.accounts.globalpool.key(),
+        position: ctx.accounts.position.key(),
+        tick_lower_index: params.tick_lower_index,
+        tick_upper_index: params.tick_upper_index,
+        liquidity_removed,
+        liquidity_added: params.liquidity_amount,
+        token_amount_a_removed: removed_a,
+        token_amount_b_removed: removed_b,
+        token_amount_a_added: added_a,
+        token_amount_b_added: added_b,
+    });
+
+    Ok(())
+}
+
+// Transfers the difference between the tokens withdrawn from the old range and deposited into the
+// new range, from the owner if the deposit needs more and to the owner otherwise.
+fn settle_rebalance<'info>(
+    accounts: &RebalanceLiquidityPosition<'info>,
+    token_owner_account: &Account<'info, TokenAccount>,
+    token_vault: &Account<'info, TokenAccount>,
+    amount_removed: u64,
+    amount_added: u64,
+    token_max: u64,
+    token_min: u64,
+) -> Result<()> {
+    if amount_added > amount_removed {
+        let amount = amount_added - amount_removed;
+        if amount > token_max {
+            return Err(ErrorCode::TokenMaxExceeded.into());
+        }
+        transfer_from_owner_to_vault(
+            &accounts.position_authority,
+            token_owner_account,
+            token_vault,
+            &accounts.token_program,
+            amount,
+        )
+    } else {
+        let amount = amount_removed - amount_added;
+        if amount < token_min {
+            return Err(ErrorCode::TokenMinSubceeded.into());
+        }
+        transfer_from_vault_to_owner(
+            &accounts.globalpool,
+            token_vault,
+            token_owner_account,
+            &accounts.token_program,
+            amount,
+        )
+    }
+}
@@ -0,0 +1,74 @@
+use {
+    super::{update_ticks_liquidity_queued, withdraw_liquidity, ModifyLiquidity},
+    crate::{
+        errors::ErrorCode,
+        manager::liquidity_manager::{
+            calculate_partial_token_min, calculate_withdrawable_liquidity,
+        },
+        math::convert_to_liquidity_delta,
+        util::verify_position_authority,
+    },
+    anchor_lang::prelude::*,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RedeemQueuedLiquidityParams {
+    // Minimums for redeeming all of the queued liquidity, scaled down to the share redeemed
+    pub token_min_a: u64,
+    pub token_min_b: u64,
+}
+
+/*
+  Withdraws the position's liquidity queued by `decrease_liquidity`, as much as trade positions
+  have repaid at the position's ticks. Positions with queued liquidity have the first claim on what
+  is repaid, and share it first-come.
+*/
+pub fn redeem_queued_liquidity(
+    ctx: Context<ModifyLiquidity>,
+    params: &RedeemQueuedLiquidityParams,
+) -> Result<()> {
+    require!(
+        ctx.accounts.clad.permissions.allow_remove_liquidity,
+        ErrorCode::RemoveLiquidityNotAllowed
+    );
+
+    verify_position_authority(
+        &ctx.accounts.position_token_account,
+        &ctx.accounts.position_authority,
+    )?;
+
+    let liquidity_queued = ctx.accounts.position.liquidity_queued;
+    let liquidity_withdrawable = calculate_withdrawable_liquidity(
+        &ctx.accounts.globalpool,
+        &ctx.accounts.position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        &ctx.accounts.tick_interest_lower,
+        &ctx.accounts.tick_interest_upper,
+        &ctx.accounts.limit_order_array_lower,
+        &ctx.accounts.limit_order_array_upper,
+        true,
+    )?;
+    let liquidity_redeemed = liquidity_queued.min(liquidity_withdrawable);
+
+    if liquidity_redeemed == 0 {
+        return Err(ErrorCode::NoRedeemableLiquidity.into());
+    }
+
+    let token_min_a =
+        calculate_partial_token_min(params.token_min_a, liquidity_redeemed, liquidity_queued)?;
+    let token_min_b =
+        calculate_partial_token_min(params.token_min_b, liquidity_redeemed, liquidity_queued)?;
+
+    withdraw_liquidity(ctx.accounts, liquidity_redeemed, token_min_a, token_min_b)?;
+
+    ctx.accounts
+        .position
+        .update_liquidity_queued(liquidity_queued - liquidity_redeemed)?;
+    update_ticks_liquidity_queued(
+        ctx.accounts,
+        convert_to_liquidity_delta(liquidity_redeemed, false)?,
+    )?;
+
+    Ok(())
+}
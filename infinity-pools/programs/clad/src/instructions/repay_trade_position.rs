@@ -1,12 +1,16 @@
 use {
     crate::{
         errors,
+        events::{TradePositionLiquidated, TradePositionRepaid},
         manager::{
-            liquidity_manager::calculate_liquidity_token_deltas,
-            swap_manager::execute_jupiter_swap_for_globalpool,
+            liquidity_manager::calculate_liquidity_token_deltas, loan_manager,
+            swap_manager::execute_swap_for_globalpool,
         },
         state::*,
-        util::{to_timestamp_u64, transfer_from_vault_to_owner, verify_position_authority},
+        util::{
+            to_timestamp_u64, transfer_from_vault_to_owner, verify_position_authority,
+            verify_position_token_held,
+        },
     },
     anchor_lang::prelude::*,
     anchor_spl::{
@@ -16,7 +20,6 @@ use {
 };
 
 #[derive(Accounts)]
-#[instruction(params: RepayTradePositionParams)]
 pub struct RepayTradePosition<'info> {
     #[account(mut)]
     pub liquidator: Signer<'info>,
@@ -24,6 +27,9 @@ pub struct RepayTradePosition<'info> {
     /// CHECK: owner is the position's authority
     pub owner: AccountInfo<'info>,
 
+    #[account(seeds = [b"clad"], bump = clad.clad_bump)]
+    pub clad: Box<Account<'info, Clad>>,
+
     #[account(mut)]
     pub globalpool: Box<Account<'info, Globalpool>>,
 
@@ -76,6 +82,46 @@ pub struct RepayTradePosition<'info> {
     #[account(address = globalpool.token_mint_b)]
     pub token_mint_b: Box<Account<'info, Mint>>,
 
+    #[account(mut, has_one = globalpool)]
+    pub tick_array_lower: AccountLoader<'info, TickArray>,
+
+    #[account(mut, has_one = globalpool)]
+    pub tick_array_upper: AccountLoader<'info, TickArray>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"tick_interest".as_ref(),
+            globalpool.key().as_ref(),
+            position.tick_lower_index.to_le_bytes().as_ref()
+        ],
+        bump,
+    )]
+    pub tick_interest_lower: Box<Account<'info, TickInterest>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"tick_interest".as_ref(),
+            globalpool.key().as_ref(),
+            position.tick_upper_index.to_le_bytes().as_ref()
+        ],
+        bump,
+    )]
+    pub tick_interest_upper: Box<Account<'info, TickInterest>>,
+
+    /// CHECK: Limit orders of the lower tick array, created before the first order in the array
+    #[account(seeds = [b"limit_order_array", tick_array_lower.key().as_ref()], bump)]
+    pub limit_order_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Limit orders of the upper tick array, created before the first order in the array
+    #[account(seeds = [b"limit_order_array", tick_array_upper.key().as_ref()], bump)]
+    pub limit_order_array_upper: UncheckedAccount<'info>,
+
+    /// CHECK: Oracle of the Globalpool, written by Globalpool-routed swaps if it is initialized
+    #[account(mut, seeds = [b"oracle", globalpool.key().as_ref()], bump)]
+    pub oracle: UncheckedAccount<'info>,
+
     #[account(address = token::ID)]
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -91,11 +137,17 @@ pub fn repay_trade_position(
     ctx: Context<RepayTradePosition>,
     params: &RepayTradePositionParams,
 ) -> Result<()> {
+    require!(
+        ctx.accounts.clad.permissions.allow_close_position,
+        errors::ErrorCode::ClosePositionNotAllowed
+    );
+
     let is_liquidating = ctx.accounts.liquidator.key != ctx.accounts.owner.key;
-    verify_position_authority(&ctx.accounts.position_token_account, &ctx.accounts.owner)?;
 
     // if is_liquidating, then make sure the position has matured
     if is_liquidating {
+        verify_position_token_held(&ctx.accounts.position_token_account)?;
+
         let current_timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp).unwrap();
         let position_maturity_timestamp = ctx
             .accounts
@@ -107,8 +159,48 @@ pub fn repay_trade_position(
             current_timestamp >= position_maturity_timestamp,
             errors::ErrorCode::LoanNotMatured
         );
+    } else {
+        verify_position_authority(&ctx.accounts.position_token_account, &ctx.accounts.owner)?;
     }
 
+    ctx.accounts
+        .globalpool
+        .update_borrow_index(to_timestamp_u64(Clock::get()?.unix_timestamp)?)?;
+
+    let repayment = ctx
+        .accounts
+        .position
+        .full_repayment(ctx.accounts.globalpool.borrow_index)?;
+
+    settle_trade_position(
+        ctx.accounts,
+        ctx.remaining_accounts,
+        params,
+        &repayment,
+        is_liquidating,
+    )?;
+
+    Ok(())
+}
+
+///
+/// Repays the `repayment` share of a trade position's loan by swapping back the trade token (and
+/// collateral, if needed). Profit is paid out to the owner, and the leftover collateral is kept on
+/// the position until it's closed. When liquidating, the liquidator receives `liquidation_bonus_bps`
+/// of the leftover collateral.
+///
+/// Callers must verify the position authority (or the liquidation condition) and update the
+/// Globalpool's borrow index beforehand.
+///
+/// Returns the leftover collateral of the repaid share.
+///
+pub fn settle_trade_position(
+    accounts: &mut RepayTradePosition,
+    remaining_accounts: &[AccountInfo],
+    params: &RepayTradePositionParams,
+    repayment: &TradePositionRepayment,
+    is_liquidating: bool,
+) -> Result<u64> {
     //
     // WARNING:
     //
@@ -161,16 +253,17 @@ pub fn repay_trade_position(
     // i. Repay 1 SOL + all of SOL collateral.
     //
 
-    let is_borrow_a = ctx.accounts.position.is_borrow_a(&ctx.accounts.globalpool);
+    let is_borrow_a = accounts.position.is_borrow_a(&accounts.globalpool);
 
-    let liquidity_borrowed = ctx.accounts.position.liquidity_borrowed;
-    let collateral_amount = ctx.accounts.position.collateral_amount;
-    let trade_token_amount = ctx.accounts.position.trade_token_amount;
-    let loan_token_swapped = ctx.accounts.position.loan_token_swapped;
-    let tick_lower_index = ctx.accounts.position.tick_lower_index;
-    let tick_upper_index = ctx.accounts.position.tick_upper_index;
-    let tick_current_index = ctx.accounts.globalpool.tick_current_index;
-    let sqrt_current_price = ctx.accounts.globalpool.sqrt_price;
+    let liquidity_borrowed = repayment.liquidity;
+    let collateral_amount = repayment.collateral_amount;
+    let trade_token_amount = repayment.trade_token_amount;
+    let loan_token_swapped = repayment.loan_token_swapped;
+    let tick_lower_index = accounts.position.tick_lower_index;
+    let tick_upper_index = accounts.position.tick_upper_index;
+    let tick_current_index = accounts.globalpool.tick_current_index;
+    let sqrt_current_price = accounts.globalpool.sqrt_price;
+    let borrow_index = accounts.globalpool.borrow_index;
 
     let (repay_delta_a, repay_delta_b) = calculate_liquidity_token_deltas(
         tick_lower_index,
@@ -180,6 +273,26 @@ pub fn repay_trade_position(
         liquidity_borrowed as i128,
     )?;
 
+    // Interest accrued since opening (if not paid upfront) is repaid as additional liquidity
+    let interest_owed_liquidity = repayment.interest_owed_liquidity;
+    let (interest_delta_a, interest_delta_b) = if interest_owed_liquidity > 0 {
+        calculate_liquidity_token_deltas(
+            tick_lower_index,
+            tick_upper_index,
+            tick_current_index,
+            sqrt_current_price,
+            interest_owed_liquidity as i128,
+        )?
+    } else {
+        (0, 0)
+    };
+
+    msg!("interest_delta_a: {}", interest_delta_a);
+    msg!("interest_delta_b: {}", interest_delta_b);
+
+    let repay_delta_a = repay_delta_a + interest_delta_a;
+    let repay_delta_b = repay_delta_b + interest_delta_b;
+
     // This assumes the position has swapped all of loan token to the opposite (trade) token.
     let (borrowed_delta_a, borrowed_delta_b) = if is_borrow_a {
         (loan_token_swapped, 0)
@@ -219,109 +332,51 @@ pub fn repay_trade_position(
         return Err(errors::ErrorCode::InvalidRepaymentAmount.into());
     }
 
-    let mut swap_in_before_balance: u64 = 0;
-    let mut swap_out_before_balance: u64 = 0;
-    let mut swap_in_after_balance: u64 = 0;
-    let mut swap_out_after_balance: u64 = 0;
-    let mut swap_out_needed: u64 = 0;
-
     // Should swap exact out if one of the above conditions are met
     if swap_needed_delta_a > 0 || swap_needed_delta_b > 0 {
         // Trade token as in the token that was swapped to from loaned token
         // e.g. loaned USDC, swapped to SOL for long position => SOL is trade token.
         // Trade token is also the collateral token, due to the way AMM converts token for LPs.
-        let trade_token_before_balance = if is_borrow_a {
-            ctx.accounts.token_vault_b.amount
+
+        // Need more A => swap from token B to token A (and vice versa)
+        let (swap_out_needed, a_to_b) = if swap_needed_delta_a > 0 {
+            (swap_needed_delta_a, false)
         } else {
-            ctx.accounts.token_vault_a.amount
+            (swap_needed_delta_b, true)
         };
 
-        if swap_needed_delta_a > 0 {
-            // Need more A. Swap from token B to token A
-            swap_in_before_balance = ctx.accounts.token_vault_b.amount;
-            swap_out_before_balance = ctx.accounts.token_vault_a.amount;
-            swap_out_needed = swap_needed_delta_a;
-        } else if swap_needed_delta_b > 0 {
-            // Need more B. Swap from token A to token B
-            swap_in_before_balance = ctx.accounts.token_vault_a.amount;
-            swap_out_before_balance = ctx.accounts.token_vault_b.amount;
-            swap_out_needed = swap_needed_delta_b;
-        }
-
-        // 1. Swap
-        execute_jupiter_swap_for_globalpool(
-            &ctx.accounts.globalpool,
-            &ctx.remaining_accounts,
+        // 1. Swap (direction is checked by the vault balance deltas)
+        let swap_update = execute_swap_for_globalpool(
+            &mut accounts.globalpool,
+            &accounts.clad,
+            &accounts.oracle,
+            &mut accounts.token_vault_a,
+            &mut accounts.token_vault_b,
+            remaining_accounts,
             &params.swap_instruction_data,
+            swap_out_needed,
+            false,
+            a_to_b,
         )?;
 
         // 2. Validate output
-        // (i) swap_out_token_vault must increase by `swap_needed_delta`
-        // (ii) NEED more check on swap_in_token_vault...
-
-        // Reload vaults for get updated token balances
-        ctx.accounts.token_vault_a.reload()?;
-        ctx.accounts.token_vault_b.reload()?;
-
-        let trade_token_after_balance = if is_borrow_a {
-            ctx.accounts.token_vault_b.amount
-        } else {
-            ctx.accounts.token_vault_a.amount
-        };
-
-        if swap_needed_delta_a > 0 {
-            // Need more A. Swap from token B to token A
-            swap_in_after_balance = ctx.accounts.token_vault_b.amount;
-            swap_out_after_balance = ctx.accounts.token_vault_a.amount;
-        } else if swap_needed_delta_b > 0 {
-            // Need more B. Swap from token A to token B
-            swap_in_after_balance = ctx.accounts.token_vault_a.amount;
-            swap_out_after_balance = ctx.accounts.token_vault_b.amount;
-        }
-
         msg!("out_needed:      {}", swap_out_needed);
-        msg!("swap_in before:  {}", swap_in_before_balance);
-        msg!("swap_in after:   {}", swap_in_after_balance);
-        msg!(
-            "diff:            {}",
-            swap_in_before_balance - swap_in_after_balance
-        );
-        msg!("swap_out before: {}", swap_out_before_balance);
-        msg!("swap_out after:  {}", swap_out_after_balance);
-        msg!(
-            "diff:            {}",
-            swap_out_after_balance - swap_out_before_balance
-        );
-
-        // Balances should increase & decrease correctly in direction (magnitude is not checked here)
-        require!(
-            (swap_in_after_balance < swap_in_before_balance)
-                && (swap_out_after_balance > swap_out_before_balance),
-            errors::ErrorCode::InvalidLoanTradeSwapDirection
-        );
+        msg!("swap_update:     {:?}", swap_update);
 
-        // Swap out balance should increase exactly by `swap_out_needed` (swap_needed_delta_a or swap_needed_delta_b)
+        // Swap out amount should be exactly `swap_out_needed` (swap_needed_delta_a or swap_needed_delta_b)
         require!(
-            swap_out_after_balance == swap_out_before_balance + swap_out_needed,
+            swap_update.amount_out == swap_out_needed,
             errors::ErrorCode::InvalidLoanTradeSwapResult
         );
 
-        // TODO: swap in precise token amount change requirement
-        // require!( // swap result in (not a really good check, need more specific number)
-        //     swap_in_after_balance < swap_in_before_balance,
-        //     errors::ErrorCode::InvalidLoanTradeSwapResult
-        // );
-
         // Trade token should decrease by at most `trade_token_amount + collateral_amount` (if used for swap)
         // which is also used above in `available_delta_a` or `available_delta_b`
         require!(
-            // collateral vault should decrease by at most
-            trade_token_after_balance + trade_token_amount + collateral_amount
-                >= trade_token_before_balance,
+            swap_update.amount_in <= trade_token_amount + collateral_amount,
             errors::ErrorCode::InvalidLoanTradeSwapResult
         );
 
-        let swap_in_amount = swap_in_before_balance - swap_in_after_balance;
+        let swap_in_amount = swap_update.amount_in;
 
         // Update available token amounts to reflect the swap_in sent & swap_out received
         if is_borrow_a {
@@ -354,15 +409,44 @@ pub fn repay_trade_position(
     msg!("repay_delta_b:     {}", repay_delta_b);
     msg!("left_over_token_b: {}", leftover_token_b);
 
-    ctx.accounts
+    accounts
         .position
         .update_liquidity_swapped(-(loan_token_swapped as i64), -(trade_token_amount as i64))?;
 
-    ctx.accounts.globalpool.update_after_loan(
+    accounts.globalpool.update_after_loan(
         -(liquidity_borrowed as i128),
         0,
         false, // doesn't matter since interest_amount = 0 (repaying, not borrowing)
-    );
+    )?;
+
+    // Pay the accrued interest to the liquidity positions lending at the position's ticks, released
+    // to them over as long as it accrued, so that liquidity added right before the repayment earns
+    // little of it
+    let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
+    let release_end = timestamp + timestamp.saturating_sub(accounts.position.open_time);
+    for (interest_amount, is_token_a) in [(interest_delta_a, true), (interest_delta_b, false)] {
+        loan_manager::pay_interest_to_ticks(
+            &mut accounts.globalpool,
+            &accounts.position,
+            &accounts.tick_array_lower,
+            &accounts.tick_array_upper,
+            &mut accounts.tick_interest_lower,
+            &mut accounts.tick_interest_upper,
+            &accounts.limit_order_array_lower,
+            &accounts.limit_order_array_upper,
+            interest_amount,
+            is_token_a,
+            release_end,
+            timestamp,
+        )?;
+    }
+
+    // Remaining liquidity of a partially repaid position keeps accruing from the same checkpoint
+    if repayment.liquidity == accounts.position.liquidity_borrowed {
+        accounts
+            .position
+            .update_borrow_index_checkpoint(borrow_index);
+    }
 
     //
     // Logic for calculating the amount of collateral to return to the trader & liquidator, if any,
@@ -371,7 +455,8 @@ pub fn repay_trade_position(
     // Best case: trade position is in profit, and we give back the whole collateral.
     // Worst case: trade position is in total loss, and we give back no collateral.
     //
-    // Note: if liquidator is liquidating, then give 10% of the leftover collateral to the liquidator.
+    // Note: if liquidator is liquidating, then give the pool's liquidation bonus share of the leftover
+    //       collateral to the liquidator.
 
     let mut collateral_to_return;
     let mut collateral_to_liquidator = 0;
@@ -386,31 +471,39 @@ pub fn repay_trade_position(
     }
 
     if is_liquidating {
-        collateral_to_liquidator = collateral_to_return / 10;
+        collateral_to_liquidator = (collateral_to_return as u128
+            * accounts.globalpool.liquidation_bonus_bps as u128
+            / Clad::BPS_POWER) as u64;
         collateral_to_return -= collateral_to_liquidator;
     }
 
-    ctx.accounts
+    let position_collateral_amount = accounts
         .position
-        .update_collateral_amount(collateral_to_return);
+        .collateral_amount
+        .checked_sub(repayment.collateral_amount)
+        .ok_or(errors::ErrorCode::CollateralCalculationError)?
+        + collateral_to_return;
+    accounts
+        .position
+        .update_collateral_amount(position_collateral_amount);
 
     if collateral_to_liquidator > 0 {
         let token_vault;
         let liquidator_token_account;
 
         if is_borrow_a {
-            token_vault = &ctx.accounts.token_vault_b;
-            liquidator_token_account = &ctx.accounts.token_liquidator_account_b;
+            token_vault = &accounts.token_vault_b;
+            liquidator_token_account = &accounts.token_liquidator_account_b;
         } else {
-            token_vault = &ctx.accounts.token_vault_a;
-            liquidator_token_account = &ctx.accounts.token_liquidator_account_a;
+            token_vault = &accounts.token_vault_a;
+            liquidator_token_account = &accounts.token_liquidator_account_a;
         }
 
         transfer_from_vault_to_owner(
-            &ctx.accounts.globalpool,
+            &accounts.globalpool,
             token_vault,
             liquidator_token_account,
-            &ctx.accounts.token_program,
+            &accounts.token_program,
             collateral_to_liquidator,
         )?;
     }
@@ -420,21 +513,50 @@ pub fn repay_trade_position(
         let owner_token_account;
 
         if is_borrow_a {
-            token_vault = &ctx.accounts.token_vault_a;
-            owner_token_account = &ctx.accounts.token_owner_account_a;
+            token_vault = &accounts.token_vault_a;
+            owner_token_account = &accounts.token_owner_account_a;
         } else {
-            token_vault = &ctx.accounts.token_vault_b;
-            owner_token_account = &ctx.accounts.token_owner_account_b;
+            token_vault = &accounts.token_vault_b;
+            owner_token_account = &accounts.token_owner_account_b;
         }
 
         transfer_from_vault_to_owner(
-            &ctx.accounts.globalpool,
+            &accounts.globalpool,
             token_vault,
             owner_token_account,
-            &ctx.accounts.token_program,
+            &accounts.token_program,
             profit_to_return,
         )?;
     }
 
-    Ok(())
+    if is_liquidating {
+        emit!(TradePositionLiquidated {
+            globalpool: accounts.globalpool.key(),
+            position: accounts.position.key(),
+            liquidator: accounts.liquidator.key(),
+            liquidity_borrowed_delta: -(liquidity_borrowed as i128),
+            interest_owed_liquidity,
+            repay_amount_a: repay_delta_a,
+            repay_amount_b: repay_delta_b,
+            collateral_amount: collateral_to_return,
+            liquidation_bonus_amount: collateral_to_liquidator,
+            sqrt_price: accounts.globalpool.sqrt_price,
+            tick_current_index: accounts.globalpool.tick_current_index,
+        });
+    } else {
+        emit!(TradePositionRepaid {
+            globalpool: accounts.globalpool.key(),
+            position: accounts.position.key(),
+            liquidity_borrowed_delta: -(liquidity_borrowed as i128),
+            interest_owed_liquidity,
+            repay_amount_a: repay_delta_a,
+            repay_amount_b: repay_delta_b,
+            collateral_amount: collateral_to_return,
+            profit_amount: profit_to_return,
+            sqrt_price: accounts.globalpool.sqrt_price,
+            tick_current_index: accounts.globalpool.tick_current_index,
+        });
+    }
+
+    Ok(collateral_to_return)
 }
@@ -0,0 +1,31 @@
+use {crate::state::*, anchor_lang::prelude::*};
+
+#[derive(Accounts)]
+pub struct SetDefaultFeeRate<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"clad"],
+        bump = clad.clad_bump,
+        has_one = admin,
+    )]
+    pub clad: Box<Account<'info, Clad>>,
+
+    #[account(mut, has_one = clad)]
+    pub fee_tier: Account<'info, FeeTier>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetDefaultFeeRateParams {
+    pub default_fee_rate: u16,
+}
+
+pub fn set_default_fee_rate(
+    ctx: Context<SetDefaultFeeRate>,
+    params: &SetDefaultFeeRateParams,
+) -> Result<()> {
+    Ok(ctx
+        .accounts
+        .fee_tier
+        .update_default_fee_rate(params.default_fee_rate)?)
+}
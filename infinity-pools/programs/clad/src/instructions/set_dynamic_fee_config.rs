@@ -0,0 +1,32 @@
+use {crate::state::*, anchor_lang::prelude::*};
+
+#[derive(Accounts)]
+pub struct SetDynamicFeeConfig<'info> {
+    #[account(mut)]
+    pub globalpool: Account<'info, Globalpool>,
+
+    #[account(address = globalpool.fee_authority)]
+    pub fee_authority: Signer<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetDynamicFeeConfigParams {
+    pub variable_fee_control: u32,
+    pub volatility_filter_period: u16,
+    pub volatility_decay_period: u16,
+    pub volatility_reduction_factor_bps: u16,
+    pub max_volatility_accumulator: u32,
+}
+
+pub fn set_dynamic_fee_config(
+    ctx: Context<SetDynamicFeeConfig>,
+    params: &SetDynamicFeeConfigParams,
+) -> Result<()> {
+    Ok(ctx.accounts.globalpool.update_dynamic_fee_config(
+        params.variable_fee_control,
+        params.volatility_filter_period,
+        params.volatility_decay_period,
+        params.volatility_reduction_factor_bps,
+        params.max_volatility_accumulator,
+    )?)
+}
@@ -0,0 +1,24 @@
+use {crate::state::*, anchor_lang::prelude::*};
+
+#[derive(Accounts)]
+pub struct SetFeeAuthority<'info> {
+    #[account(mut)]
+    pub globalpool: Account<'info, Globalpool>,
+
+    #[account(address = globalpool.fee_authority)]
+    pub fee_authority: Signer<'info>,
+
+    /// CHECK: safe, the new fee authority must sign `accept_fee_authority` to take over
+    pub new_fee_authority: UncheckedAccount<'info>,
+}
+
+///
+/// Proposes a new fee authority. The change only takes effect once the new authority
+/// calls `accept_fee_authority`.
+///
+pub fn set_fee_authority(ctx: Context<SetFeeAuthority>) -> Result<()> {
+    Ok(ctx
+        .accounts
+        .globalpool
+        .update_pending_fee_authority(ctx.accounts.new_fee_authority.key()))
+}
@@ -0,0 +1,19 @@
+use {crate::state::*, anchor_lang::prelude::*};
+
+#[derive(Accounts)]
+pub struct SetFeeRate<'info> {
+    #[account(mut)]
+    pub globalpool: Account<'info, Globalpool>,
+
+    #[account(address = globalpool.fee_authority)]
+    pub fee_authority: Signer<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetFeeRateParams {
+    pub fee_rate: u16,
+}
+
+pub fn set_fee_rate(ctx: Context<SetFeeRate>, params: &SetFeeRateParams) -> Result<()> {
+    Ok(ctx.accounts.globalpool.update_fee_rate(params.fee_rate)?)
+}
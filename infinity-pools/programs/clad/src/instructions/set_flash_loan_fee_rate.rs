@@ -0,0 +1,25 @@
+use {crate::state::*, anchor_lang::prelude::*};
+
+#[derive(Accounts)]
+pub struct SetFlashLoanFeeRate<'info> {
+    #[account(mut)]
+    pub globalpool: Account<'info, Globalpool>,
+
+    #[account(address = globalpool.fee_authority)]
+    pub fee_authority: Signer<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetFlashLoanFeeRateParams {
+    pub flash_loan_fee_rate: u16,
+}
+
+pub fn set_flash_loan_fee_rate(
+    ctx: Context<SetFlashLoanFeeRate>,
+    params: &SetFlashLoanFeeRateParams,
+) -> Result<()> {
+    Ok(ctx
+        .accounts
+        .globalpool
+        .update_flash_loan_fee_rate(params.flash_loan_fee_rate)?)
+}
@@ -0,0 +1,34 @@
+use {
+    crate::{state::*, util::to_timestamp_u64},
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct SetInterestAccrual<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"clad"],
+        bump = clad.clad_bump,
+        has_one = admin,
+    )]
+    pub clad: Box<Account<'info, Clad>>,
+
+    #[account(mut)]
+    pub globalpool: Box<Account<'info, Globalpool>>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetInterestAccrualParams {
+    pub interest_accrual_enabled: bool,
+}
+
+pub fn set_interest_accrual(
+    ctx: Context<SetInterestAccrual>,
+    params: &SetInterestAccrualParams,
+) -> Result<()> {
+    let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
+    ctx.accounts
+        .globalpool
+        .update_interest_accrual_enabled(params.interest_accrual_enabled, timestamp)
+}
@@ -0,0 +1,41 @@
+use {
+    crate::{state::*, util::to_timestamp_u64},
+    anchor_lang::prelude::*,
+};
+
+#[derive(Accounts)]
+pub struct SetInterestRateModel<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"clad"],
+        bump = clad.clad_bump,
+        has_one = admin,
+    )]
+    pub clad: Box<Account<'info, Clad>>,
+
+    #[account(mut)]
+    pub globalpool: Box<Account<'info, Globalpool>>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetInterestRateModelParams {
+    pub base_rate_bps: u16,
+    pub slope1_bps: u16,
+    pub optimal_utilization_bps: u16,
+    pub slope2_bps: u16,
+}
+
+pub fn set_interest_rate_model(
+    ctx: Context<SetInterestRateModel>,
+    params: &SetInterestRateModelParams,
+) -> Result<()> {
+    let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
+    Ok(ctx.accounts.globalpool.update_interest_rate_model(
+        params.base_rate_bps,
+        params.slope1_bps,
+        params.optimal_utilization_bps,
+        params.slope2_bps,
+        timestamp,
+    )?)
+}
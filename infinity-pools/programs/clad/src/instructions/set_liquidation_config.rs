@@ -0,0 +1,32 @@
+use {crate::state::*, anchor_lang::prelude::*};
+
+#[derive(Accounts)]
+pub struct SetLiquidationConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"clad"],
+        bump = clad.clad_bump,
+        has_one = admin,
+    )]
+    pub clad: Box<Account<'info, Clad>>,
+
+    #[account(mut)]
+    pub globalpool: Box<Account<'info, Globalpool>>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetLiquidationConfigParams {
+    pub maintenance_margin_bps: u16,
+    pub liquidation_bonus_bps: u16,
+}
+
+pub fn set_liquidation_config(
+    ctx: Context<SetLiquidationConfig>,
+    params: &SetLiquidationConfigParams,
+) -> Result<()> {
+    Ok(ctx
+        .accounts
+        .globalpool
+        .update_liquidation_config(params.maintenance_margin_bps, params.liquidation_bonus_bps)?)
+}
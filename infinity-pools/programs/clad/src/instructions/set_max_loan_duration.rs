@@ -0,0 +1,31 @@
+use {crate::state::*, anchor_lang::prelude::*};
+
+#[derive(Accounts)]
+pub struct SetMaxLoanDuration<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"clad"],
+        bump = clad.clad_bump,
+        has_one = admin,
+    )]
+    pub clad: Box<Account<'info, Clad>>,
+
+    #[account(mut)]
+    pub globalpool: Box<Account<'info, Globalpool>>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetMaxLoanDurationParams {
+    pub max_loan_duration: u64,
+}
+
+pub fn set_max_loan_duration(
+    ctx: Context<SetMaxLoanDuration>,
+    params: &SetMaxLoanDurationParams,
+) -> Result<()> {
+    Ok(ctx
+        .accounts
+        .globalpool
+        .update_max_loan_duration(params.max_loan_duration)?)
+}
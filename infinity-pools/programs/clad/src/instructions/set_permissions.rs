@@ -0,0 +1,23 @@
+use {crate::state::*, anchor_lang::prelude::*};
+
+#[derive(Accounts)]
+pub struct SetPermissions<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"clad"],
+        bump = clad.clad_bump,
+        has_one = admin,
+    )]
+    pub clad: Account<'info, Clad>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetPermissionsParams {
+    pub permissions: Permissions,
+}
+
+pub fn set_permissions(ctx: Context<SetPermissions>, params: &SetPermissionsParams) -> Result<()> {
+    Ok(ctx.accounts.clad.update_permissions(params.permissions))
+}
@@ -0,0 +1,35 @@
+use {crate::state::*, anchor_lang::prelude::*};
+
+#[derive(Accounts)]
+pub struct SetPriceFeeds<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"clad"],
+        bump = clad.clad_bump,
+        has_one = admin,
+    )]
+    pub clad: Box<Account<'info, Clad>>,
+
+    #[account(mut)]
+    pub globalpool: Box<Account<'info, Globalpool>>,
+
+    // Pyth feeds of Token A and B, both quoted in USD
+    pub token_price_feed_a: Account<'info, PriceFeed>,
+    pub token_price_feed_b: Account<'info, PriceFeed>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetPriceFeedsParams {
+    pub oracle_stale_seconds: u64,
+    pub oracle_price_band_bps: u16,
+}
+
+pub fn set_price_feeds(ctx: Context<SetPriceFeeds>, params: &SetPriceFeedsParams) -> Result<()> {
+    Ok(ctx.accounts.globalpool.update_price_feeds(
+        ctx.accounts.token_price_feed_a.key(),
+        ctx.accounts.token_price_feed_b.key(),
+        params.oracle_stale_seconds,
+        params.oracle_price_band_bps,
+    )?)
+}
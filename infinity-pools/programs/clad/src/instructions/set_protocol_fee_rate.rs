@@ -0,0 +1,25 @@
+use {crate::state::*, anchor_lang::prelude::*};
+
+#[derive(Accounts)]
+pub struct SetProtocolFeeRate<'info> {
+    #[account(mut)]
+    pub globalpool: Account<'info, Globalpool>,
+
+    #[account(address = globalpool.fee_authority)]
+    pub fee_authority: Signer<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetProtocolFeeRateParams {
+    pub protocol_fee_rate: u16,
+}
+
+pub fn set_protocol_fee_rate(
+    ctx: Context<SetProtocolFeeRate>,
+    params: &SetProtocolFeeRateParams,
+) -> Result<()> {
+    Ok(ctx
+        .accounts
+        .globalpool
+        .update_protocol_fee_rate(params.protocol_fee_rate)?)
+}
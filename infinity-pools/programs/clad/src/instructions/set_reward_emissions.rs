@@ -0,0 +1,55 @@
+use {
+    crate::{
+        errors::ErrorCode, manager::globalpool_manager::next_globalpool_reward_infos,
+        math::checked_mul_shift_right, state::*, util::to_timestamp_u64,
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::token::TokenAccount,
+};
+
+const DAY_IN_SECONDS: u128 = 60 * 60 * 24;
+
+#[derive(Accounts)]
+#[instruction(params: SetRewardEmissionsParams)]
+pub struct SetRewardEmissions<'info> {
+    #[account(mut)]
+    pub globalpool: Account<'info, Globalpool>,
+
+    #[account(address = globalpool.reward_infos[params.reward_index as usize].authority)]
+    pub reward_authority: Signer<'info>,
+
+    #[account(address = globalpool.reward_infos[params.reward_index as usize].vault)]
+    pub reward_vault: Account<'info, TokenAccount>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetRewardEmissionsParams {
+    pub reward_index: u8,
+    pub emissions_per_second_x64: u128,
+}
+
+pub fn set_reward_emissions(
+    ctx: Context<SetRewardEmissions>,
+    params: &SetRewardEmissionsParams,
+) -> Result<()> {
+    let globalpool = &ctx.accounts.globalpool;
+    let reward_index = params.reward_index as usize;
+
+    // The vault must hold at least a day of the new emissions
+    let emissions_per_day =
+        checked_mul_shift_right(DAY_IN_SECONDS, params.emissions_per_second_x64)?;
+    if ctx.accounts.reward_vault.amount < emissions_per_day {
+        return Err(ErrorCode::RewardVaultAmountInsufficient.into());
+    }
+
+    // Accrue the reward growths at the previous emissions before changing them
+    let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
+    let next_reward_infos = next_globalpool_reward_infos(globalpool, timestamp)?;
+
+    Ok(ctx.accounts.globalpool.update_emissions(
+        reward_index,
+        next_reward_infos,
+        timestamp,
+        params.emissions_per_second_x64,
+    )?)
+}
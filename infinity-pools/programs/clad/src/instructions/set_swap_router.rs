@@ -0,0 +1,28 @@
+use {crate::state::*, anchor_lang::prelude::*};
+
+#[derive(Accounts)]
+pub struct SetSwapRouter<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"clad"],
+        bump = clad.clad_bump,
+        has_one = admin,
+    )]
+    pub clad: Box<Account<'info, Clad>>,
+
+    #[account(mut)]
+    pub globalpool: Box<Account<'info, Globalpool>>,
+
+    /// CHECK: any executable program, verified by vault balance deltas on each swap
+    #[account(executable)]
+    pub swap_router: UncheckedAccount<'info>,
+}
+
+pub fn set_swap_router(ctx: Context<SetSwapRouter>) -> Result<()> {
+    ctx.accounts
+        .globalpool
+        .update_swap_router(ctx.accounts.swap_router.key());
+
+    Ok(())
+}
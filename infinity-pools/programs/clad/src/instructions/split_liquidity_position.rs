@@ -0,0 +1,149 @@
+use {
+    crate::{
+        events::LiquidityPositionSplit,
+        manager::liquidity_manager::sync_position_growths,
+        state::*,
+        util::{
+            mint_position_token_and_remove_authority, to_timestamp_u64, verify_position_authority,
+        },
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::{
+        associated_token::AssociatedToken,
+        token::{self, Mint, Token, TokenAccount},
+    },
+};
+
+#[derive(Accounts)]
+pub struct SplitLiquidityPosition<'info> {
+    #[account(mut)]
+    pub position_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub globalpool: Box<Account<'info, Globalpool>>,
+
+    #[account(mut, has_one = globalpool)]
+    pub position: Box<Account<'info, LiquidityPosition>>,
+
+    #[account(
+        constraint = position_token_account.mint == position.position_mint,
+        constraint = position_token_account.amount == 1
+    )]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = position_authority,
+        space = LiquidityPosition::LEN,
+        seeds = [
+            b"liquidity_position".as_ref(),
+            new_position_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub new_position: Box<Account<'info, LiquidityPosition>>,
+
+    #[account(
+        init,
+        payer = position_authority,
+        mint::authority = globalpool,
+        mint::decimals = 0,
+    )]
+    pub new_position_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = position_authority,
+        associated_token::mint = new_position_mint,
+        associated_token::authority = position_authority,
+    )]
+    pub new_position_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(has_one = globalpool)]
+    pub tick_array_lower: AccountLoader<'info, TickArray>,
+
+    #[account(has_one = globalpool)]
+    pub tick_array_upper: AccountLoader<'info, TickArray>,
+
+    /// CHECK: Interest growths of the position's lower tick, created when a trade position borrows at it
+    #[account(
+        seeds = [b"tick_interest".as_ref(), globalpool.key().as_ref(), position.tick_lower_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub tick_interest_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Interest growths of the position's upper tick, created when a trade position borrows at it
+    #[account(
+        seeds = [b"tick_interest".as_ref(), globalpool.key().as_ref(), position.tick_upper_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub tick_interest_upper: UncheckedAccount<'info>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SplitLiquidityPositionParams {
+    liquidity_amount: u128,
+}
+
+/*
+  Splits `liquidity_amount` of a position into a new position on the same range, represented by a
+  newly minted position token. The liquidity stays in the Globalpool, so no tokens are transferred.
+
+  The position is first synced to the current growths of its range, so that the new position earns
+  from now on. The fees, rewards and interest owed stay with the split position.
+*/
+pub fn split_liquidity_position(
+    ctx: Context<SplitLiquidityPosition>,
+    params: &SplitLiquidityPositionParams,
+) -> Result<()> {
+    verify_position_authority(
+        &ctx.accounts.position_token_account,
+        &ctx.accounts.position_authority,
+    )?;
+
+    let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
+
+    sync_position_growths(
+        &mut ctx.accounts.globalpool,
+        &mut ctx.accounts.position,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        &ctx.accounts.tick_interest_lower,
+        &ctx.accounts.tick_interest_upper,
+        timestamp,
+    )?;
+
+    let tick_lower_index = ctx.accounts.position.tick_lower_index;
+    let tick_upper_index = ctx.accounts.position.tick_upper_index;
+    ctx.accounts.new_position.open_position(
+        &ctx.accounts.globalpool,
+        ctx.accounts.new_position_mint.key(),
+        tick_lower_index,
+        tick_upper_index,
+    )?;
+    ctx.accounts
+        .position
+        .split(&mut ctx.accounts.new_position, params.liquidity_amount)?;
+
+    mint_position_token_and_remove_authority(
+        &ctx.accounts.globalpool,
+        &ctx.accounts.new_position_mint,
+        &ctx.accounts.new_position_token_account,
+        &ctx.accounts.token_program,
+    )?;
+
+    emit!(LiquidityPositionSplit {
+        globalpool: ctx.accounts.globalpool.key(),
+        position: ctx.accounts.position.key(),
+        new_position: ctx.accounts.new_position.key(),
+        liquidity_amount: params.liquidity_amount,
+    });
+
+    Ok(())
+}
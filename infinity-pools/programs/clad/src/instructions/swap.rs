@@ -1,9 +1,13 @@
 use {
     crate::{
         errors::ErrorCode,
-        manager::swap_manager,
-        state::{Globalpool, TickArray},
-        util::{update_and_swap_globalpool, to_timestamp_u64, TickSequence},
+        events::Swapped,
+        manager::{oracle_manager::write_oracle_observation, swap_manager},
+        state::{Clad, Globalpool, TickArray},
+        util::{
+            load_limit_order_arrays, load_tick_sequence, to_timestamp_u64,
+            update_and_swap_globalpool,
+        },
     },
     anchor_lang::prelude::*,
     anchor_spl::token::{self, Token, TokenAccount},
@@ -16,6 +20,9 @@ pub struct Swap<'info> {
 
     pub token_authority: Signer<'info>,
 
+    #[account(seeds = [b"clad"], bump = clad.clad_bump)]
+    pub clad: Box<Account<'info, Clad>>,
+
     #[account(mut)]
     pub globalpool: Box<Account<'info, Globalpool>>,
 
@@ -37,6 +44,10 @@ pub struct Swap<'info> {
 
     #[account(mut, has_one = globalpool)]
     pub tick_array_2: AccountLoader<'info, TickArray>,
+
+    /// CHECK: Oracle of the Globalpool, written if it is initialized
+    #[account(mut, seeds = [b"oracle", globalpool.key().as_ref()], bump)]
+    pub oracle: UncheckedAccount<'info>,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -49,14 +60,23 @@ pub struct SwapParams {
 }
 
 pub fn swap(ctx: Context<Swap>, params: &SwapParams) -> Result<()> {
+    require!(
+        ctx.accounts.clad.permissions.allow_swap,
+        ErrorCode::SwapNotAllowed
+    );
+
     let globalpool = &mut ctx.accounts.globalpool;
 
     let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
-    let mut swap_tick_sequence = TickSequence::new(
-        ctx.accounts.tick_array_0.load_mut().unwrap(),
-        ctx.accounts.tick_array_1.load_mut().ok(),
-        ctx.accounts.tick_array_2.load_mut().ok(),
-    );
+    let limit_order_arrays = load_limit_order_arrays(ctx.remaining_accounts)?;
+    let mut swap_tick_sequence = load_tick_sequence(
+        &ctx.accounts.tick_array_0,
+        Some(&ctx.accounts.tick_array_1),
+        Some(&ctx.accounts.tick_array_2),
+    )?;
+    for limit_order_array in limit_order_arrays.iter() {
+        swap_tick_sequence.add_limit_order_array(limit_order_array.load_mut()?)?;
+    }
 
     let SwapParams { amount_specified_is_input, a_to_b, other_amount_threshold, .. } = *params;
 
@@ -85,6 +105,19 @@ pub fn swap(ctx: Context<Swap>, params: &SwapParams) -> Result<()> {
     }
     msg!("swap_update: {:?}", swap_update);
 
+    // Observe the tick before the swap moves it
+    write_oracle_observation(
+        &ctx.accounts.oracle,
+        timestamp,
+        globalpool.tick_current_index,
+    )?;
+
+    let (amount_a, amount_b, protocol_fee) = (
+        swap_update.amount_a,
+        swap_update.amount_b,
+        swap_update.next_protocol_fee,
+    );
+
     update_and_swap_globalpool(
         globalpool,
         &ctx.accounts.token_authority,
@@ -95,5 +128,19 @@ pub fn swap(ctx: Context<Swap>, params: &SwapParams) -> Result<()> {
         &ctx.accounts.token_program,
         swap_update,
         a_to_b,
-    )
+        timestamp,
+    )?;
+
+    emit!(Swapped {
+        globalpool: globalpool.key(),
+        token_authority: ctx.accounts.token_authority.key(),
+        a_to_b,
+        amount_a,
+        amount_b,
+        protocol_fee,
+        sqrt_price: globalpool.sqrt_price,
+        tick_current_index: globalpool.tick_current_index,
+    });
+
+    Ok(())
 }
@@ -0,0 +1,317 @@
+use {
+    crate::{
+        errors::ErrorCode,
+        events::Swapped,
+        manager::{oracle_manager::write_oracle_observation, swap_manager},
+        state::{Clad, Globalpool, TickArray},
+        util::{
+            load_limit_order_arrays, load_tick_sequence, to_timestamp_u64,
+            update_and_swap_globalpool,
+        },
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::token::{self, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct TwoHopSwap<'info> {
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+
+    pub token_authority: Signer<'info>,
+
+    #[account(seeds = [b"clad"], bump = clad.clad_bump)]
+    pub clad: Box<Account<'info, Clad>>,
+
+    #[account(mut)]
+    pub globalpool_one: Box<Account<'info, Globalpool>>,
+
+    #[account(mut)]
+    pub globalpool_two: Box<Account<'info, Globalpool>>,
+
+    #[account(mut, constraint = token_owner_account_one_a.mint == globalpool_one.token_mint_a)]
+    pub token_owner_account_one_a: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = globalpool_one.token_vault_a)]
+    pub token_vault_one_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = token_owner_account_one_b.mint == globalpool_one.token_mint_b)]
+    pub token_owner_account_one_b: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = globalpool_one.token_vault_b)]
+    pub token_vault_one_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = token_owner_account_two_a.mint == globalpool_two.token_mint_a)]
+    pub token_owner_account_two_a: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = globalpool_two.token_vault_a)]
+    pub token_vault_two_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = token_owner_account_two_b.mint == globalpool_two.token_mint_b)]
+    pub token_owner_account_two_b: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = globalpool_two.token_vault_b)]
+    pub token_vault_two_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, constraint = tick_array_one_0.load()?.globalpool == globalpool_one.key())]
+    pub tick_array_one_0: AccountLoader<'info, TickArray>,
+
+    #[account(mut, constraint = tick_array_one_1.load()?.globalpool == globalpool_one.key())]
+    pub tick_array_one_1: AccountLoader<'info, TickArray>,
+
+    #[account(mut, constraint = tick_array_one_2.load()?.globalpool == globalpool_one.key())]
+    pub tick_array_one_2: AccountLoader<'info, TickArray>,
+
+    #[account(mut, constraint = tick_array_two_0.load()?.globalpool == globalpool_two.key())]
+    pub tick_array_two_0: AccountLoader<'info, TickArray>,
+
+    #[account(mut, constraint = tick_array_two_1.load()?.globalpool == globalpool_two.key())]
+    pub tick_array_two_1: AccountLoader<'info, TickArray>,
+
+    #[account(mut, constraint = tick_array_two_2.load()?.globalpool == globalpool_two.key())]
+    pub tick_array_two_2: AccountLoader<'info, TickArray>,
+
+    /// CHECK: Oracle of Globalpool one, written if it is initialized
+    #[account(mut, seeds = [b"oracle", globalpool_one.key().as_ref()], bump)]
+    pub oracle_one: UncheckedAccount<'info>,
+
+    /// CHECK: Oracle of Globalpool two, written if it is initialized
+    #[account(mut, seeds = [b"oracle", globalpool_two.key().as_ref()], bump)]
+    pub oracle_two: UncheckedAccount<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TwoHopSwapParams {
+    amount: u64,
+    other_amount_threshold: u64,
+    amount_specified_is_input: bool,
+    a_to_b_one: bool,
+    a_to_b_two: bool,
+    sqrt_price_limit_one: u128,
+    sqrt_price_limit_two: u128,
+}
+
+pub fn two_hop_swap(ctx: Context<TwoHopSwap>, params: &TwoHopSwapParams) -> Result<()> {
+    require!(
+        ctx.accounts.clad.permissions.allow_swap,
+        ErrorCode::SwapNotAllowed
+    );
+
+    let globalpool_one = &ctx.accounts.globalpool_one;
+    let globalpool_two = &ctx.accounts.globalpool_two;
+
+    require!(
+        globalpool_one.key() != globalpool_two.key(),
+        ErrorCode::DuplicateTwoHopPool
+    );
+
+    let TwoHopSwapParams {
+        amount,
+        other_amount_threshold,
+        amount_specified_is_input,
+        a_to_b_one,
+        a_to_b_two,
+        sqrt_price_limit_one,
+        sqrt_price_limit_two,
+    } = *params;
+
+    // Output token of the first swap is the input token of the second swap
+    let intermediary_mint_one = if a_to_b_one {
+        globalpool_one.token_mint_b
+    } else {
+        globalpool_one.token_mint_a
+    };
+    let intermediary_mint_two = if a_to_b_two {
+        globalpool_two.token_mint_a
+    } else {
+        globalpool_two.token_mint_b
+    };
+    require!(
+        intermediary_mint_one == intermediary_mint_two,
+        ErrorCode::InvalidIntermediaryMint
+    );
+
+    let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
+
+    let limit_order_arrays = load_limit_order_arrays(ctx.remaining_accounts)?;
+    let mut swap_tick_sequence_one = load_tick_sequence(
+        &ctx.accounts.tick_array_one_0,
+        Some(&ctx.accounts.tick_array_one_1),
+        Some(&ctx.accounts.tick_array_one_2),
+    )?;
+
+    let mut swap_tick_sequence_two = load_tick_sequence(
+        &ctx.accounts.tick_array_two_0,
+        Some(&ctx.accounts.tick_array_two_1),
+        Some(&ctx.accounts.tick_array_two_2),
+    )?;
+
+    for limit_order_array in limit_order_arrays.iter() {
+        if limit_order_array.load()?.globalpool == globalpool_one.key() {
+            swap_tick_sequence_one.add_limit_order_array(limit_order_array.load_mut()?)?;
+        } else {
+            swap_tick_sequence_two.add_limit_order_array(limit_order_array.load_mut()?)?;
+        }
+    }
+
+    // Exact input swaps forward from the first pool, exact output swaps backward from the second
+    let (swap_update_one, swap_update_two) = if amount_specified_is_input {
+        let swap_update_one = swap_manager::swap(
+            globalpool_one,
+            &mut swap_tick_sequence_one,
+            amount,
+            sqrt_price_limit_one,
+            amount_specified_is_input,
+            a_to_b_one,
+            timestamp,
+        )?;
+
+        let swap_two_input = if a_to_b_one {
+            swap_update_one.amount_b
+        } else {
+            swap_update_one.amount_a
+        };
+
+        let swap_update_two = swap_manager::swap(
+            globalpool_two,
+            &mut swap_tick_sequence_two,
+            swap_two_input,
+            sqrt_price_limit_two,
+            amount_specified_is_input,
+            a_to_b_two,
+            timestamp,
+        )?;
+
+        (swap_update_one, swap_update_two)
+    } else {
+        let swap_update_two = swap_manager::swap(
+            globalpool_two,
+            &mut swap_tick_sequence_two,
+            amount,
+            sqrt_price_limit_two,
+            amount_specified_is_input,
+            a_to_b_two,
+            timestamp,
+        )?;
+
+        let swap_one_output = if a_to_b_two {
+            swap_update_two.amount_a
+        } else {
+            swap_update_two.amount_b
+        };
+
+        let swap_update_one = swap_manager::swap(
+            globalpool_one,
+            &mut swap_tick_sequence_one,
+            swap_one_output,
+            sqrt_price_limit_one,
+            amount_specified_is_input,
+            a_to_b_one,
+            timestamp,
+        )?;
+
+        (swap_update_one, swap_update_two)
+    };
+
+    // A swap stopped early by its sqrt_price_limit leaves the intermediary token unbalanced
+    let (swap_one_output, swap_one_input) = if a_to_b_one {
+        (swap_update_one.amount_b, swap_update_one.amount_a)
+    } else {
+        (swap_update_one.amount_a, swap_update_one.amount_b)
+    };
+    let (swap_two_input, swap_two_output) = if a_to_b_two {
+        (swap_update_two.amount_a, swap_update_two.amount_b)
+    } else {
+        (swap_update_two.amount_b, swap_update_two.amount_a)
+    };
+    require!(
+        swap_one_output == swap_two_input,
+        ErrorCode::IntermediateTokenAmountMismatch
+    );
+
+    if amount_specified_is_input {
+        if other_amount_threshold > swap_two_output {
+            return Err(ErrorCode::AmountOutBelowMinimum.into());
+        }
+    } else {
+        if other_amount_threshold < swap_one_input {
+            return Err(ErrorCode::AmountInAboveMaximum.into());
+        }
+    }
+    msg!("swap_update_one: {:?}", swap_update_one);
+    msg!("swap_update_two: {:?}", swap_update_two);
+
+    drop(swap_tick_sequence_one);
+    drop(swap_tick_sequence_two);
+
+    // Observe the ticks before the swaps move them
+    write_oracle_observation(
+        &ctx.accounts.oracle_one,
+        timestamp,
+        globalpool_one.tick_current_index,
+    )?;
+    write_oracle_observation(
+        &ctx.accounts.oracle_two,
+        timestamp,
+        globalpool_two.tick_current_index,
+    )?;
+
+    let (amount_one_a, amount_one_b, protocol_fee_one) = (
+        swap_update_one.amount_a,
+        swap_update_one.amount_b,
+        swap_update_one.next_protocol_fee,
+    );
+    let (amount_two_a, amount_two_b, protocol_fee_two) = (
+        swap_update_two.amount_a,
+        swap_update_two.amount_b,
+        swap_update_two.next_protocol_fee,
+    );
+
+    update_and_swap_globalpool(
+        &mut ctx.accounts.globalpool_one,
+        &ctx.accounts.token_authority,
+        &ctx.accounts.token_owner_account_one_a,
+        &ctx.accounts.token_owner_account_one_b,
+        &ctx.accounts.token_vault_one_a,
+        &ctx.accounts.token_vault_one_b,
+        &ctx.accounts.token_program,
+        swap_update_one,
+        a_to_b_one,
+        timestamp,
+    )?;
+
+    update_and_swap_globalpool(
+        &mut ctx.accounts.globalpool_two,
+        &ctx.accounts.token_authority,
+        &ctx.accounts.token_owner_account_two_a,
+        &ctx.accounts.token_owner_account_two_b,
+        &ctx.accounts.token_vault_two_a,
+        &ctx.accounts.token_vault_two_b,
+        &ctx.accounts.token_program,
+        swap_update_two,
+        a_to_b_two,
+        timestamp,
+    )?;
+
+    let globalpool_one = &ctx.accounts.globalpool_one;
+    emit!(Swapped {
+        globalpool: globalpool_one.key(),
+        token_authority: ctx.accounts.token_authority.key(),
+        a_to_b: a_to_b_one,
+        amount_a: amount_one_a,
+        amount_b: amount_one_b,
+        protocol_fee: protocol_fee_one,
+        sqrt_price: globalpool_one.sqrt_price,
+        tick_current_index: globalpool_one.tick_current_index,
+    });
+
+    let globalpool_two = &ctx.accounts.globalpool_two;
+    emit!(Swapped {
+        globalpool: globalpool_two.key(),
+        token_authority: ctx.accounts.token_authority.key(),
+        a_to_b: a_to_b_two,
+        amount_a: amount_two_a,
+        amount_b: amount_two_b,
+        protocol_fee: protocol_fee_two,
+        sqrt_price: globalpool_two.sqrt_price,
+        tick_current_index: globalpool_two.tick_current_index,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use {
+    crate::{
+        errors::ErrorCode,
+        manager::{loan_manager, oracle_manager},
+        state::*,
+        util::{to_timestamp_u64, transfer_from_vault_to_owner, verify_position_authority},
+    },
+    anchor_lang::prelude::*,
+    anchor_spl::token::{self, Mint, Token, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct WithdrawCollateral<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [b"clad"], bump = clad.clad_bump)]
+    pub clad: Box<Account<'info, Clad>>,
+
+    #[account(mut)]
+    pub globalpool: Box<Account<'info, Globalpool>>,
+
+    #[account(mut, has_one = globalpool)]
+    pub position: Box<Account<'info, TradePosition>>,
+
+    #[account(
+        associated_token::mint = position.position_mint,
+        associated_token::authority = owner,
+	)]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = globalpool.token_mint_a)]
+    pub token_owner_account_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, address = globalpool.token_vault_a)]
+    pub token_vault_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = globalpool.token_mint_a)]
+    pub token_mint_a: Box<Account<'info, Mint>>,
+
+    #[account(mut, token::mint = globalpool.token_mint_b)]
+    pub token_owner_account_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, address = globalpool.token_vault_b)]
+    pub token_vault_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = globalpool.token_mint_b)]
+    pub token_mint_b: Box<Account<'info, Mint>>,
+
+    /// CHECK: Pyth feed of Token A, or the default pubkey if the Globalpool has no price feeds
+    #[account(address = globalpool.token_price_feed_a @ ErrorCode::InvalidOracleAccount)]
+    pub token_price_feed_a: UncheckedAccount<'info>,
+
+    /// CHECK: Pyth feed of Token B, or the default pubkey if the Globalpool has no price feeds
+    #[account(address = globalpool.token_price_feed_b @ ErrorCode::InvalidOracleAccount)]
+    pub token_price_feed_b: UncheckedAccount<'info>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct WithdrawCollateralParams {
+    pub collateral_amount: u64,
+}
+
+pub fn withdraw_collateral(
+    ctx: Context<WithdrawCollateral>,
+    params: &WithdrawCollateralParams,
+) -> Result<()> {
+    require!(
+        ctx.accounts.clad.permissions.allow_close_position,
+        ErrorCode::ClosePositionNotAllowed
+    );
+
+    verify_position_authority(&ctx.accounts.position_token_account, &ctx.accounts.owner)?;
+
+    // Leftover collateral of repaid positions is returned on closing
+    require!(
+        !TradePosition::is_position_empty(&ctx.accounts.position),
+        ErrorCode::InvalidLoanParameters
+    );
+
+    require!(
+        params.collateral_amount > 0,
+        ErrorCode::ZeroCollateralAmount
+    );
+
+    // Prevent withdrawing against a manipulated pool price
+    oracle_manager::verify_oracle_price_band(
+        &ctx.accounts.globalpool,
+        &ctx.accounts.token_price_feed_a,
+        &ctx.accounts.token_price_feed_b,
+        ctx.accounts.token_mint_a.decimals,
+        ctx.accounts.token_mint_b.decimals,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    ctx.accounts
+        .globalpool
+        .update_borrow_index(to_timestamp_u64(Clock::get()?.unix_timestamp)?)?;
+
+    let globalpool = &ctx.accounts.globalpool;
+    let position = &ctx.accounts.position;
+    let is_borrow_a = position.is_borrow_a(globalpool);
+
+    // Accrued interest is owed on top of the borrowed liquidity
+    let liquidity_owed = position
+        .liquidity_borrowed
+        .checked_add(position.interest_owed_liquidity(globalpool.borrow_index)?)
+        .ok_or(ErrorCode::LiquidityOverflow)?;
+
+    // Position must stay fully collateralized after the withdrawal
+    let excess_collateral = loan_manager::calculate_excess_collateral(
+        liquidity_owed,
+        position.tick_lower_index,
+        position.tick_upper_index,
+        position.trade_token_amount,
+        position.collateral_amount,
+        is_borrow_a,
+    )?;
+
+    msg!("excess_collateral: {}", excess_collateral);
+
+    require!(
+        params.collateral_amount <= excess_collateral,
+        ErrorCode::InsufficientCollateral
+    );
+
+    // ...and keep a buffer above its maintenance margin, so it isn't left on the verge of liquidation
+    loan_manager::verify_health_after_collateral_withdrawal(
+        position,
+        liquidity_owed,
+        globalpool.tick_current_index,
+        globalpool.sqrt_price,
+        params.collateral_amount,
+        is_borrow_a,
+    )?;
+
+    // borrow a => collateral is b (vice versa)
+    let (collateral_token_owner_account, collateral_token_vault) = if is_borrow_a {
+        (
+            &ctx.accounts.token_owner_account_b,
+            &ctx.accounts.token_vault_b,
+        )
+    } else {
+        (
+            &ctx.accounts.token_owner_account_a,
+            &ctx.accounts.token_vault_a,
+        )
+    };
+
+    transfer_from_vault_to_owner(
+        globalpool,
+        collateral_token_vault,
+        collateral_token_owner_account,
+        &ctx.accounts.token_program,
+        params.collateral_amount,
+    )?;
+
+    let collateral_amount = position.collateral_amount - params.collateral_amount;
+
+    ctx.accounts
+        .position
+        .update_collateral_amount(collateral_amount);
+
+    Ok(())
+}
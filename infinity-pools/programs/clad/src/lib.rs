@@ -4,6 +4,7 @@ declare_id!("GH4aPZ5bXQr3MhN6MrejxKLTj6gUdyrGZvieagrfA3ke");
 
 #[doc(hidden)]
 pub mod errors;
+pub mod events;
 #[doc(hidden)]
 pub mod instructions;
 #[doc(hidden)]
@@ -40,6 +41,59 @@ pub mod clad {
         return instructions::initialize_clad(ctx, &params);
     }
 
+    /// Set the permissions that gate swaps, liquidity changes and trade positions across all
+    /// Globalpools. Lets operators pause parts of the protocol without redeploying.
+    ///
+    /// ### Authority
+    /// - `admin` - The admin that initialized the Clad account.
+    ///
+    /// ### Parameters
+    /// - `permissions` - The new set of permissions.
+    pub fn set_permissions(
+        ctx: Context<SetPermissions>,
+        params: SetPermissionsParams,
+    ) -> Result<()> {
+        return instructions::set_permissions(ctx, &params);
+    }
+
+    /// Registers a fee tier, the tick spacing and default fee rate that Globalpools can be created
+    /// with.
+    ///
+    /// ### Authority
+    /// - `admin` - The admin of the Clad account.
+    ///
+    /// ### Parameters
+    /// - `tick_spacing` - The tick spacing of the fee tier.
+    /// - `default_fee_rate` - The fee rate of Globalpools created with this tier, in hundredths of a basis point.
+    ///
+    /// #### Special Errors
+    /// - `InvalidTickSpacing` - The tick spacing is zero.
+    /// - `FeeRateMaxExceeded` - If the provided default_fee_rate exceeds MAX_FEE_RATE.
+    pub fn initialize_fee_tier(
+        ctx: Context<InitializeFeeTier>,
+        params: InitializeFeeTierParams,
+    ) -> Result<()> {
+        return instructions::initialize_fee_tier(ctx, &params);
+    }
+
+    /// Set the default fee rate of a fee tier. Only Globalpools created afterwards use it, and their
+    /// addresses are still seeded by the tier's initial default fee rate.
+    ///
+    /// ### Authority
+    /// - `admin` - The admin of the Clad account.
+    ///
+    /// ### Parameters
+    /// - `default_fee_rate` - The new default fee rate, in hundredths of a basis point.
+    ///
+    /// #### Special Errors
+    /// - `FeeRateMaxExceeded` - If the provided default_fee_rate exceeds MAX_FEE_RATE.
+    pub fn set_default_fee_rate(
+        ctx: Context<SetDefaultFeeRate>,
+        params: SetDefaultFeeRateParams,
+    ) -> Result<()> {
+        return instructions::set_default_fee_rate(ctx, &params);
+    }
+
     /// Initializes a tick_array account to represent a tick-range in a Globalpool.
     ///
     /// ### Parameters
@@ -56,8 +110,73 @@ pub mod clad {
         return instructions::initialize_tick_array(ctx, &params);
     }
 
+    /// Closes a tick_array account without liquidity left at any of its ticks, refunding its rent
+    /// to the funder who initialized it. Its limit_order_array is closed along with it if created,
+    /// refunding its rent to its own funder.
+    ///
+    /// ### Authority
+    /// - `funder` - The funder who initialized the tick_array.
+    ///
+    /// #### Special Errors
+    /// - `CloseTickArrayNotEmpty` - If a tick of the array is initialized or has liquidity, or the
+    ///                              array has pending or unclaimed limit orders.
+    /// - `InvalidLimitOrderArrayFunder` - The limit_order_array_funder is not the funder of the limit_order_array.
+    pub fn close_tick_array(ctx: Context<CloseTickArray>) -> Result<()> {
+        return instructions::close_tick_array(ctx);
+    }
+
+    /// Closes a tick_array account without liquidity left at any of its ticks that has no funder,
+    /// ie. initialized before tick arrays recorded their funder. Its rent goes to the admin. Its
+    /// limit_order_array is closed along with it if created, refunding its rent to its own funder.
+    ///
+    /// ### Authority
+    /// - `admin` - The admin of the Clad.
+    ///
+    /// #### Special Errors
+    /// - `TickArrayFunderSet` - The tick_array has a funder, who closes it with `close_tick_array`.
+    /// - `CloseTickArrayNotEmpty` - If a tick of the array is initialized or has liquidity, or the
+    ///                              array has pending or unclaimed limit orders.
+    /// - `InvalidLimitOrderArrayFunder` - The limit_order_array_funder is not the funder of the limit_order_array.
+    pub fn admin_close_tick_array(ctx: Context<AdminCloseTickArray>) -> Result<()> {
+        return instructions::admin_close_tick_array(ctx);
+    }
+
+    /// Initializes the limit_order_array account of a tick_array, holding the limit orders on its
+    /// ranges. Required before opening limit orders in the tick-array.
+    pub fn initialize_limit_order_array(ctx: Context<InitializeLimitOrderArray>) -> Result<()> {
+        return instructions::initialize_limit_order_array(ctx);
+    }
+
+    /// Initializes the tick_interest account of a tick, tracking the loans of trade positions at it.
+    /// Created by `open_trade_position` on the first loan at the tick, and required before
+    /// `decrease_liquidity` can queue the liquidity of a position bounded by the tick.
+    ///
+    /// ### Parameters
+    /// - `tick_index` - The tick index, a multiple of the tick spacing of this pool.
+    ///
+    /// #### Special Errors
+    /// - `InvalidTickIndex` - If the tick is out of bounds or not a multiple of the tick spacing.
+    pub fn initialize_tick_interest(
+        ctx: Context<InitializeTickInterest>,
+        params: InitializeTickInterestParams,
+    ) -> Result<()> {
+        return instructions::initialize_tick_interest(ctx, &params);
+    }
+
+    /// Initializes the oracle account of a Globalpool, a ring buffer of tick observations written
+    /// on swaps. Optional: until it is initialized, swaps skip the observations and liquidations
+    /// value positions at the spot price only.
+    pub fn initialize_oracle(ctx: Context<InitializeOracle>) -> Result<()> {
+        return instructions::initialize_oracle(ctx);
+    }
+
     /// Initializes a Globalpool account.
-    /// Fee rate is set to the default values on the config and supplied fee_tier.
+    /// Fee rate is set to the default values on the config and supplied fee_tier. The Clad admin
+    /// becomes the fee authority of the Globalpool, and can hand it over with `set_fee_authority`.
+    /// The Globalpool address is seeded by the fee tier's initial default fee rate, so there is one
+    /// Globalpool per token pair and tick spacing.
+    /// Only SPL Token mints are supported. Token-2022 mints are rejected, as the vaults and transfers
+    /// of this program are SPL Token only.
     ///
     /// ### Parameters
     /// - `tick_spacing` - The desired tick spacing for this pool.
@@ -66,6 +185,8 @@ pub mod clad {
     /// #### Special Errors
     /// `InvalidTokenMintOrder` - The order of mints have to be ordered by
     /// `SqrtPriceOutOfBounds` - provided initial_sqrt_price is not between 2^-64 to 2^64
+    /// `AccountNotInitialized` - No fee tier is registered for the tick spacing
+    /// `AccountOwnedByWrongProgram` - A mint is not an SPL Token mint, eg. a Token-2022 mint
     ///
     pub fn create_pool(ctx: Context<CreatePool>, params: CreatePoolParams) -> Result<()> {
         return instructions::create_pool(ctx, &params);
@@ -106,6 +227,14 @@ pub mod clad {
         return instructions::open_trade_position(ctx, &params);
     }
 
+    /// Repay a matured trade position's loan in full, swapping back the trade token (and
+    /// collateral, if needed) and returning the leftover collateral to the owner.
+    ///
+    /// #### Special Errors
+    /// - `LoanNotMatured` - A non-owner repays before the loan matured.
+    /// - `ClosePositionNotAllowed` - Closing positions is disabled in the Clad permissions.
+    /// - `SwapNotAllowed` - The Globalpool routes swaps through itself and swaps are disabled in
+    ///   the Clad permissions.
     pub fn repay_trade_position(
         ctx: Context<RepayTradePosition>,
         params: RepayTradePositionParams,
@@ -113,11 +242,113 @@ pub mod clad {
         return instructions::repay_trade_position(ctx, &params);
     }
 
+    /// Liquidate a trade position whose value (trade token & collateral) over its debt is below the
+    /// maintenance margin it was opened with, valued both at the current pool price and, if the
+    /// Globalpool has an oracle, at its time-weighted average price. The liquidator receives the
+    /// Globalpool's liquidation bonus share of the leftover collateral.
+    ///
+    /// ### Parameters
+    /// - `swap_instruction_data` - The swap instruction data to swap back the trade token.
+    ///
+    /// #### Special Errors
+    /// - `TradePositionNotLiquidatable` - The position is at or above the maintenance margin.
+    /// - `InvalidPositionTokenAmount` - The owner no longer holds the position token.
+    /// - `ClosePositionNotAllowed` - Closing positions (and thus liquidating) is disabled in the
+    ///   Clad permissions.
+    pub fn liquidate_trade_position(
+        ctx: Context<RepayTradePosition>,
+        params: RepayTradePositionParams,
+    ) -> Result<()> {
+        return instructions::liquidate_trade_position(ctx, &params);
+    }
+
+    /// Repay a share of a trade position's loan, swapping back the proportional trade token (and
+    /// collateral, if needed). The repaid liquidity is returned to the position's ticks and the
+    /// leftover collateral of the share is returned to the owner.
+    ///
+    /// ### Authority
+    /// - "position_authority" - The authority that owns the position token.
+    ///
+    /// ### Parameters
+    /// - `liquidity_amount` - The borrowed liquidity to repay, less than the position's.
+    /// - `swap_instruction_data` - The swap instruction data to swap back the trade token.
+    ///
+    /// #### Special Errors
+    /// - `InvalidRepaymentAmount` - The liquidity amount is zero or not less than the position's.
+    /// - `ClosePositionNotAllowed` - Closing positions is disabled in the Clad permissions.
+    pub fn partial_repay_trade_position(
+        ctx: Context<PartialRepayTradePosition>,
+        params: PartialRepayTradePositionParams,
+    ) -> Result<()> {
+        return instructions::partial_repay_trade_position(ctx, &params);
+    }
+
+    /// Deposit additional collateral to a trade position, eg. to top up a position that is drifting
+    /// toward liquidation.
+    ///
+    /// ### Authority
+    /// - "position_authority" - The authority that owns the position token.
+    ///
+    /// ### Parameters
+    /// - `collateral_amount` - The amount of collateral token to deposit.
+    ///
+    /// #### Special Errors
+    /// - `ZeroCollateralAmount` - The collateral amount is zero.
+    pub fn deposit_collateral(
+        ctx: Context<DepositCollateral>,
+        params: DepositCollateralParams,
+    ) -> Result<()> {
+        return instructions::deposit_collateral(ctx, &params);
+    }
+
+    /// Withdraw collateral from a trade position, as long as the position stays fully collateralized
+    /// against the debt (including accrued interest) entirely in the collateral token, and its health
+    /// stays 5% above its maintenance margin.
+    ///
+    /// ### Authority
+    /// - "position_authority" - The authority that owns the position token.
+    ///
+    /// ### Parameters
+    /// - `collateral_amount` - The amount of collateral token to withdraw.
+    ///
+    /// #### Special Errors
+    /// - `ZeroCollateralAmount` - The collateral amount is zero.
+    /// - `InsufficientCollateral` - The withdrawal leaves the position undercollateralized, or too
+    ///   close to its maintenance margin.
+    /// - `OraclePriceOutOfBand` - The pool price is outside the oracle price band of the Globalpool.
+    pub fn withdraw_collateral(
+        ctx: Context<WithdrawCollateral>,
+        params: WithdrawCollateralParams,
+    ) -> Result<()> {
+        return instructions::withdraw_collateral(ctx, &params);
+    }
+
+    /// Extend the loan duration of a trade position. Unless the position accrues interest, the
+    /// interest for the extra time is re-priced at the current utilization of the borrowed ticks
+    /// and paid upfront in the collateral token. Like opening, it is charged on the collateral
+    /// required for the borrowed liquidity, regardless of the collateral deposited.
+    ///
+    /// ### Authority
+    /// - `owner` - The owner of the trade position token.
+    ///
+    /// ### Parameters
+    /// - `additional_duration` - Extra time added to the loan, in seconds.
+    ///
+    /// #### Special Errors
+    /// - `InvalidLoanDuration` - The extended duration exceeds the Globalpool's max loan duration.
+    /// - `OpenPositionNotAllowed` - Opening positions is disabled in the Clad permissions.
+    pub fn extend_trade_position(
+        ctx: Context<ExtendTradePosition>,
+        params: ExtendTradePositionParams,
+    ) -> Result<()> {
+        return instructions::extend_trade_position(ctx, &params);
+    }
+
     pub fn close_trade_position(ctx: Context<CloseTradePosition>) -> Result<()> {
         return instructions::close_trade_position(ctx);
     }
 
-    /// Add liquidity to a position in the Globalpool. This call also updates the position's accrued fees.
+    /// Add liquidity to a position in the Globalpool. This call also updates the position's accrued fees and rewards.
     ///
     /// ### Authority
     /// - `position_authority` - authority that owns the token corresponding to this desired position.
@@ -131,6 +362,7 @@ pub mod clad {
     /// - `LiquidityZero` - Provided liquidity amount is zero.
     /// - `LiquidityTooHigh` - Provided liquidity exceeds u128::max.
     /// - `TokenMaxExceeded` - The required token to perform this operation exceeds the user defined amount.
+    /// - `AddLiquidityNotAllowed` - Adding liquidity is disabled in the Clad permissions.
     pub fn increase_liquidity(
         ctx: Context<ModifyLiquidity>,
         params: IncreaseLiquidityParams,
@@ -138,20 +370,27 @@ pub mod clad {
         return instructions::increase_liquidity(ctx, &params);
     }
 
-    /// Withdraw liquidity from a position in the Globalpool. This call also updates the position's accrued fees.
+    /// Withdraw liquidity from a position in the Globalpool. This call also updates the position's accrued fees and rewards.
+    /// Liquidity borrowed by trade positions at the position's ticks is queued for withdrawal instead,
+    /// redeemable with `redeem_queued_liquidity` once the trade positions repay. Liquidity of limit
+    /// orders at the ticks is never lent, and isn't withdrawable by liquidity positions either.
+    /// Queued liquidity is reserved at the ticks, so it isn't borrowed or withdrawn by others.
     ///
     /// ### Authority
     /// - `position_authority` - authority that owns the token corresponding to this desired position.
     ///
     /// ### Parameters
     /// - `liquidity_amount` - The total amount of Liquidity the user desires to withdraw.
-    /// - `token_min_a` - The minimum amount of tokenA the user is willing to withdraw.
-    /// - `token_min_b` - The minimum amount of tokenB the user is willing to withdraw.
+    /// - `token_min_a` - The minimum amount of tokenA the user is willing to withdraw, scaled down if partially queued.
+    /// - `token_min_b` - The minimum amount of tokenB the user is willing to withdraw, scaled down if partially queued.
     ///
     /// #### Special Errors
     /// - `LiquidityZero` - Provided liquidity amount is zero.
     /// - `LiquidityTooHigh` - Provided liquidity exceeds u128::max.
+    /// - `LiquidityUnderflow` - Provided liquidity exceeds the position's liquidity not yet queued for withdrawal.
+    /// - `TickInterestNotInitialized` - Liquidity is queued, but a tick_interest account of the position's ticks is not initialized.
     /// - `TokenMinSubceeded` - The required token to perform this operation subceeds the user defined amount.
+    /// - `RemoveLiquidityNotAllowed` - Removing liquidity is disabled in the Clad permissions.
     pub fn decrease_liquidity(
         ctx: Context<ModifyLiquidity>,
         params: DecreaseLiquidityParams,
@@ -159,6 +398,89 @@ pub mod clad {
         return instructions::decrease_liquidity(ctx, &params);
     }
 
+    /// Withdraw the liquidity queued by `decrease_liquidity`, as much of it as trade positions have
+    /// repaid at the position's ticks.
+    ///
+    /// ### Authority
+    /// - `position_authority` - authority that owns the token corresponding to this desired position.
+    ///
+    /// ### Parameters
+    /// - `token_min_a` - The minimum amount of tokenA for all of the queued liquidity, scaled down to the share redeemed.
+    /// - `token_min_b` - The minimum amount of tokenB for all of the queued liquidity, scaled down to the share redeemed.
+    ///
+    /// #### Special Errors
+    /// - `NoRedeemableLiquidity` - No liquidity is queued, or none of it is repaid yet.
+    /// - `TokenMinSubceeded` - The required token to perform this operation subceeds the user defined amount.
+    /// - `RemoveLiquidityNotAllowed` - Removing liquidity is disabled in the Clad permissions.
+    pub fn redeem_queued_liquidity(
+        ctx: Context<ModifyLiquidity>,
+        params: RedeemQueuedLiquidityParams,
+    ) -> Result<()> {
+        return instructions::redeem_queued_liquidity(ctx, &params);
+    }
+
+    /// Split part of a position's liquidity into a new position on the same range. A unique token
+    /// is minted to represent the new position in the users wallet. The new position carries over
+    /// the fee, reward and interest checkpoints, while the amounts owed stay with the position.
+    ///
+    /// ### Authority
+    /// - `position_authority` - authority that owns the token corresponding to this desired position.
+    ///
+    /// ### Parameters
+    /// - `liquidity_amount` - The amount of Liquidity moved to the new position.
+    ///
+    /// #### Special Errors
+    /// - `LiquidityZero` - Provided liquidity amount is zero.
+    /// - `LiquidityUnderflow` - Provided liquidity exceeds the position's liquidity not yet queued for withdrawal.
+    pub fn split_liquidity_position(
+        ctx: Context<SplitLiquidityPosition>,
+        params: SplitLiquidityPositionParams,
+    ) -> Result<()> {
+        return instructions::split_liquidity_position(ctx, &params);
+    }
+
+    /// Merge a position into another position on the same range, moving its liquidity and the fees,
+    /// rewards and interest it is owed. Burns the merged position token in the owner's wallet.
+    ///
+    /// ### Authority
+    /// - `position_authority` - authority that owns the tokens corresponding to both positions.
+    ///
+    /// #### Special Errors
+    /// - `LiquidityPositionRangeMismatch` - The positions are not on the same range.
+    pub fn merge_liquidity_positions(ctx: Context<MergeLiquidityPositions>) -> Result<()> {
+        return instructions::merge_liquidity_positions(ctx);
+    }
+
+    /// Move all the liquidity of a position to a new range. The tokens withdrawn from the current
+    /// range are deposited into the new range, so only the difference is transferred.
+    ///
+    /// ### Authority
+    /// - `position_authority` - authority that owns the token corresponding to this desired position.
+    ///
+    /// ### Parameters
+    /// - `tick_lower_index` - The tick specifying the lower end of the new range.
+    /// - `tick_upper_index` - The tick specifying the upper end of the new range.
+    /// - `liquidity_amount` - The amount of Liquidity deposited into the new range.
+    /// - `token_max_a` - The maximum amount of tokenA the user is willing to add to the withdrawn tokens.
+    /// - `token_max_b` - The maximum amount of tokenB the user is willing to add to the withdrawn tokens.
+    /// - `token_min_a` - The minimum amount of tokenA the user is willing to receive back.
+    /// - `token_min_b` - The minimum amount of tokenB the user is willing to receive back.
+    ///
+    /// #### Special Errors
+    /// - `LiquidityZero` - Provided liquidity amount or the position's liquidity is zero.
+    /// - `LiquidityQueued` - The position has liquidity queued for withdrawal.
+    /// - `LiquidityBorrowed` - The position's liquidity is borrowed by trade positions.
+    /// - `InvalidTickIndex` - If a provided tick is out of bounds, out of order or not a multiple of
+    ///                        the tick-spacing in this pool.
+    /// - `TokenMaxExceeded` - The tokens to add exceed the user defined amount.
+    /// - `TokenMinSubceeded` - The tokens received back subceed the user defined amount.
+    pub fn rebalance_liquidity_position(
+        ctx: Context<RebalanceLiquidityPosition>,
+        params: RebalanceLiquidityPositionParams,
+    ) -> Result<()> {
+        return instructions::rebalance_liquidity_position(ctx, &params);
+    }
+
     /// Collect fees accrued for this position.
     ///
     /// ### Authority
@@ -171,6 +493,129 @@ pub mod clad {
         return instructions::collect_fees(ctx);
     }
 
+    /// Collect interest paid by trade positions to this position, for lending liquidity at its
+    /// lower & upper ticks. Interest paid to a tick is released to the liquidity lent there by the
+    /// second, until the end of the time it pays for: the loan's maturity when paid upfront, or as
+    /// long again as it accrued when paid on repayment.
+    ///
+    /// ### Authority
+    /// - `position_authority` - authority that owns the token corresponding to this desired position.
+    pub fn collect_interest(ctx: Context<CollectInterest>) -> Result<()> {
+        return instructions::collect_interest(ctx);
+    }
+
+    /// Open a limit order on a single tick-spacing range of the Globalpool, entirely above the
+    /// price (converting token A into B) or below it (converting token B into A). The order is
+    /// filled by the swap that crosses the range, after which it is claimed with
+    /// `claim_limit_order`. Order liquidity is not lent to trade positions.
+    ///
+    /// ### Authority
+    /// - `owner` - The owner of the limit order, depositing the tokens.
+    ///
+    /// ### Parameters
+    /// - `tick_lower_index` - The lower tick of the range, which can't be the last tick of its tick array.
+    /// - `liquidity_amount` - The liquidity of the order.
+    /// - `token_max` - The maximum amount of the token sold the user is willing to deposit.
+    ///
+    /// #### Special Errors
+    /// - `LiquidityZero` - Provided liquidity amount is zero.
+    /// - `InvalidLimitOrderTickIndex` - The range is not a usable tick-spacing range of the tick array.
+    /// - `LimitOrderRangeInPrice` - The current price is in the range.
+    /// - `LimitOrderRangeUnclaimed` - Filled orders of the range are not claimed yet.
+    /// - `TokenMaxExceeded` - The required token to perform this operation exceeds the user defined amount.
+    /// - `AddLiquidityNotAllowed` - Adding liquidity is disabled in the Clad permissions.
+    pub fn open_limit_order(
+        ctx: Context<OpenLimitOrder>,
+        params: OpenLimitOrderParams,
+    ) -> Result<()> {
+        return instructions::open_limit_order(ctx, &params);
+    }
+
+    /// Cancel a limit order that is not filled yet, withdrawing its tokens and the fees it earned.
+    ///
+    /// ### Authority
+    /// - `owner` - The owner of the limit order.
+    ///
+    /// ### Parameters
+    /// - `token_min_a` - The minimum amount of tokenA the user is willing to withdraw.
+    /// - `token_min_b` - The minimum amount of tokenB the user is willing to withdraw.
+    ///
+    /// #### Special Errors
+    /// - `LimitOrderFilled` - The order is filled, and can only be claimed.
+    /// - `TokenMinSubceeded` - The required token to perform this operation subceeds the user defined amount.
+    /// - `RemoveLiquidityNotAllowed` - Removing liquidity is disabled in the Clad permissions.
+    pub fn cancel_limit_order(
+        ctx: Context<CancelLimitOrder>,
+        params: CancelLimitOrderParams,
+    ) -> Result<()> {
+        return instructions::cancel_limit_order(ctx, &params);
+    }
+
+    /// Claim the proceeds of a filled limit order for its owner, ie. the converted tokens and the
+    /// fees earned until the fill.
+    ///
+    /// ### Authority
+    /// - `authority` - Anyone, eg. the owner or a keeper. The proceeds always go to the owner.
+    ///
+    /// #### Special Errors
+    /// - `LimitOrderNotFilled` - The order is not filled yet.
+    /// - `RemoveLiquidityNotAllowed` - Removing liquidity is disabled in the Clad permissions.
+    pub fn claim_limit_order(ctx: Context<ClaimLimitOrder>) -> Result<()> {
+        return instructions::claim_limit_order(ctx);
+    }
+
+    /// Initialize the reward at `reward_index` of a Globalpool, with a new vault for its mint.
+    /// Rewards are initialized in order, and emit nothing until `set_reward_emissions` is called.
+    ///
+    /// ### Authority
+    /// - `fee_authority` - the Globalpool's fee authority, which becomes the reward authority.
+    ///
+    /// ### Parameters
+    /// - `reward_index` - The lowest uninitialized reward index, below NUM_REWARDS.
+    ///
+    /// #### Special Errors
+    /// - `InvalidRewardIndex` - The reward index is out of bounds or not the lowest uninitialized one.
+    pub fn initialize_reward(
+        ctx: Context<InitializeReward>,
+        params: InitializeRewardParams,
+    ) -> Result<()> {
+        return instructions::initialize_reward(ctx, &params);
+    }
+
+    /// Set the emissions of the reward at `reward_index`, after accruing the reward growths at
+    /// the previous emissions.
+    ///
+    /// ### Authority
+    /// - `reward_authority` - authority of the reward at `reward_index`.
+    ///
+    /// ### Parameters
+    /// - `reward_index` - The reward to set the emissions of.
+    /// - `emissions_per_second_x64` - Reward tokens emitted per second, as a Q64.64 number.
+    ///
+    /// #### Special Errors
+    /// - `RewardVaultAmountInsufficient` - The reward vault holds less than a day of the emissions.
+    pub fn set_reward_emissions(
+        ctx: Context<SetRewardEmissions>,
+        params: SetRewardEmissionsParams,
+    ) -> Result<()> {
+        return instructions::set_reward_emissions(ctx, &params);
+    }
+
+    /// Collect the reward at `reward_index` accrued for this position. Rewards owed beyond the
+    /// reward vault's balance stay owed to the position.
+    ///
+    /// ### Authority
+    /// - `position_authority` - authority that owns the token corresponding to this desired position.
+    ///
+    /// ### Parameters
+    /// - `reward_index` - The reward to collect.
+    ///
+    /// #### Special Errors
+    /// - `TickNotFound` - Provided tick array account does not contain the tick for this position.
+    pub fn collect_reward(ctx: Context<CollectReward>, params: CollectRewardParams) -> Result<()> {
+        return instructions::collect_reward(ctx, &params);
+    }
+
     /// Collect the protocol fees accrued in this Globalpool
     ///
     /// ### Authority
@@ -179,7 +624,207 @@ pub mod clad {
         return instructions::collect_protocol_fees(ctx);
     }
 
-    /// Perform a swap in this Globalpool
+    /// Set the fee rate of this Globalpool. The Globalpool address keeps the fee rate it was created with.
+    ///
+    /// ### Authority
+    /// - `fee_authority` - The fee authority of the Globalpool.
+    ///
+    /// ### Parameters
+    /// - `fee_rate` - The new fee rate, in hundredths of a basis point.
+    ///
+    /// #### Special Errors
+    /// - `FeeRateMaxExceeded` - If the provided fee_rate exceeds MAX_FEE_RATE.
+    pub fn set_fee_rate(ctx: Context<SetFeeRate>, params: SetFeeRateParams) -> Result<()> {
+        return instructions::set_fee_rate(ctx, &params);
+    }
+
+    /// Set the dynamic fee config of this Globalpool. With the dynamic fee, swaps pay a variable
+    /// fee on top of the base `fee_rate`, growing with the square of the volatility accumulated from
+    /// tick movement since recent swaps. Each tick spacing a swap moves through is charged for the
+    /// volatility up to its start. The total fee rate is capped at MAX_FEE_RATE.
+    ///
+    /// ### Authority
+    /// - `fee_authority` - The fee authority of the Globalpool.
+    ///
+    /// ### Parameters
+    /// - `variable_fee_control` - Variable fee per squared tick of volatility, in hundredths of a
+    ///                            basis point scaled by 10^5. Zero disables the dynamic fee.
+    /// - `volatility_filter_period` - Seconds since the last swap within which volatility keeps
+    ///                                accumulating from the same reference.
+    /// - `volatility_decay_period` - Seconds since the last swap after which volatility resets.
+    /// - `volatility_reduction_factor_bps` - Portion of the volatility kept between the filter and
+    ///                                       decay periods, in bps.
+    /// - `max_volatility_accumulator` - Cap of the volatility, in tick spacings scaled by 10^4.
+    ///
+    /// #### Special Errors
+    /// - `InvalidDynamicFeeConfig` - If the dynamic fee is enabled with a filter period that isn't
+    ///                               shorter than the decay period, or a reduction factor over 100%.
+    pub fn set_dynamic_fee_config(
+        ctx: Context<SetDynamicFeeConfig>,
+        params: SetDynamicFeeConfigParams,
+    ) -> Result<()> {
+        return instructions::set_dynamic_fee_config(ctx, &params);
+    }
+
+    /// Set the protocol fee rate of this Globalpool.
+    ///
+    /// ### Authority
+    /// - `fee_authority` - The fee authority of the Globalpool.
+    ///
+    /// ### Parameters
+    /// - `protocol_fee_rate` - The new protocol fee rate, in basis points of the fee rate.
+    ///
+    /// #### Special Errors
+    /// - `ProtocolFeeRateMaxExceeded` - If the provided protocol_fee_rate exceeds MAX_PROTOCOL_FEE_RATE.
+    pub fn set_protocol_fee_rate(
+        ctx: Context<SetProtocolFeeRate>,
+        params: SetProtocolFeeRateParams,
+    ) -> Result<()> {
+        return instructions::set_protocol_fee_rate(ctx, &params);
+    }
+
+    /// Set the flash loan fee rate of this Globalpool.
+    ///
+    /// ### Authority
+    /// - `fee_authority` - The fee authority of the Globalpool.
+    ///
+    /// ### Parameters
+    /// - `flash_loan_fee_rate` - The new flash loan fee rate, in hundredths of a basis point.
+    ///
+    /// #### Special Errors
+    /// - `FeeRateMaxExceeded` - If the provided flash_loan_fee_rate exceeds MAX_FEE_RATE.
+    pub fn set_flash_loan_fee_rate(
+        ctx: Context<SetFlashLoanFeeRate>,
+        params: SetFlashLoanFeeRateParams,
+    ) -> Result<()> {
+        return instructions::set_flash_loan_fee_rate(ctx, &params);
+    }
+
+    /// Propose a new fee authority for this Globalpool. The proposed authority takes over
+    /// only after calling `accept_fee_authority`.
+    ///
+    /// ### Authority
+    /// - `fee_authority` - The current fee authority of the Globalpool.
+    pub fn set_fee_authority(ctx: Context<SetFeeAuthority>) -> Result<()> {
+        return instructions::set_fee_authority(ctx);
+    }
+
+    /// Accept the fee authority of this Globalpool proposed by `set_fee_authority`.
+    ///
+    /// ### Authority
+    /// - `pending_fee_authority` - The fee authority proposed by the current fee authority.
+    pub fn accept_fee_authority(ctx: Context<AcceptFeeAuthority>) -> Result<()> {
+        return instructions::accept_fee_authority(ctx);
+    }
+
+    /// Set the maintenance margin under which trade positions of this Globalpool can be liquidated,
+    /// and the share of the leftover collateral paid to liquidators. Trade positions keep the
+    /// maintenance margin they were opened with.
+    ///
+    /// ### Authority
+    /// - `admin` - The admin of the Clad account.
+    ///
+    /// ### Parameters
+    /// - `maintenance_margin_bps` - Minimum value of a trade position over its debt, in bps.
+    /// - `liquidation_bonus_bps` - Share of the leftover collateral paid to the liquidator, in bps.
+    ///
+    /// #### Special Errors
+    /// - `InvalidMaintenanceMargin` - If the provided maintenance_margin_bps is below 100% or above 200%.
+    /// - `LiquidationBonusMaxExceeded` - If the provided liquidation_bonus_bps exceeds 100%.
+    pub fn set_liquidation_config(
+        ctx: Context<SetLiquidationConfig>,
+        params: SetLiquidationConfigParams,
+    ) -> Result<()> {
+        return instructions::set_liquidation_config(ctx, &params);
+    }
+
+    /// Set the interest rate model of loans in this Globalpool. The annual rate rises linearly by
+    /// `slope1_bps` up to the optimal utilization, then by `slope2_bps` up to full utilization.
+    /// Interest accrued on the borrow index until now is kept at the previous rate.
+    ///
+    /// ### Authority
+    /// - `admin` - The admin of the Clad account.
+    ///
+    /// ### Parameters
+    /// - `base_rate_bps` - Annual interest rate at zero utilization, in bps.
+    /// - `slope1_bps` - Rate increase from zero to optimal utilization, in bps.
+    /// - `optimal_utilization_bps` - Utilization at which the rate curve kinks, in bps.
+    /// - `slope2_bps` - Rate increase from optimal to full utilization, in bps.
+    ///
+    /// #### Special Errors
+    /// - `InvalidInterestRateModel` - If the optimal utilization is zero or exceeds 100%.
+    pub fn set_interest_rate_model(
+        ctx: Context<SetInterestRateModel>,
+        params: SetInterestRateModelParams,
+    ) -> Result<()> {
+        return instructions::set_interest_rate_model(ctx, &params);
+    }
+
+    /// Set whether new trade positions in this Globalpool accrue interest by the second on their
+    /// borrowed liquidity (paid on repayment) instead of paying interest for the full loan duration
+    /// upfront. Existing positions keep the mode they were opened with. Interest accrued on the
+    /// borrow index until now is kept at the current rate.
+    ///
+    /// ### Authority
+    /// - `admin` - The admin of the Clad account.
+    ///
+    /// ### Parameters
+    /// - `interest_accrual_enabled` - Whether new trade positions accrue interest.
+    pub fn set_interest_accrual(
+        ctx: Context<SetInterestAccrual>,
+        params: SetInterestAccrualParams,
+    ) -> Result<()> {
+        return instructions::set_interest_accrual(ctx, &params);
+    }
+
+    /// Set the max duration of trade position loans in this Globalpool, including extensions.
+    ///
+    /// ### Authority
+    /// - `admin` - The admin of the Clad account.
+    ///
+    /// ### Parameters
+    /// - `max_loan_duration` - Max loan duration since opening, in seconds.
+    ///
+    /// #### Special Errors
+    /// - `InvalidLoanDuration` - If the provided max_loan_duration is shorter than the min loan duration.
+    pub fn set_max_loan_duration(
+        ctx: Context<SetMaxLoanDuration>,
+        params: SetMaxLoanDurationParams,
+    ) -> Result<()> {
+        return instructions::set_max_loan_duration(ctx, &params);
+    }
+
+    /// Register the Pyth price feeds of this Globalpool's tokens, used to check the pool price when
+    /// opening trade positions and withdrawing their collateral. Until feeds are registered, these
+    /// instructions take the default pubkey for both feeds and skip the oracle price band check.
+    ///
+    /// ### Authority
+    /// - `admin` - The admin of the Clad account.
+    ///
+    /// ### Parameters
+    /// - `oracle_stale_seconds` - Max age of the oracle prices, in seconds.
+    /// - `oracle_price_band_bps` - Max deviation of the pool price from the oracle price, in bps.
+    ///
+    /// #### Special Errors
+    /// - `OraclePriceBandMaxExceeded` - If the provided oracle_price_band_bps exceeds 100%.
+    pub fn set_price_feeds(ctx: Context<SetPriceFeeds>, params: SetPriceFeedsParams) -> Result<()> {
+        return instructions::set_price_feeds(ctx, &params);
+    }
+
+    /// Set the program that this Globalpool's trade position swaps are routed through. Setting it
+    /// to this program routes the swaps through the Globalpool itself, with the tick arrays to
+    /// traverse as remaining accounts, followed by the limit_order_arrays of those with pending
+    /// orders. Swaps through any router are verified by the vault balance deltas.
+    ///
+    /// ### Authority
+    /// - `admin` - The admin of the Clad account.
+    pub fn set_swap_router(ctx: Context<SetSwapRouter>) -> Result<()> {
+        return instructions::set_swap_router(ctx);
+    }
+
+    /// Perform a swap in this Globalpool. Limit orders on the ranges the swap crosses are filled,
+    /// so the limit_order_arrays of the tick-arrays with pending orders must be passed as remaining
+    /// accounts.
     ///
     /// ### Authority
     /// - "token_authority" - The authority to withdraw tokens from the input token account.
@@ -200,7 +845,68 @@ pub mod clad {
     /// - `TickArrayIndexOutofBounds` - The swap loop attempted to access an invalid array index during tick crossing.
     /// - `LiquidityOverflow` - Liquidity value overflowed 128bits during tick crossing.
     /// - `InvalidTickSpacing` - The swap pool was initialized with tick-spacing of 0.
+    /// - `LimitOrderArrayMissing` - The swap reached a tick-array with pending limit orders, but its limit_order_array was not passed.
+    /// - `SwapNotAllowed` - Swapping is disabled in the Clad permissions.
     pub fn swap(ctx: Context<Swap>, params: SwapParams) -> Result<()> {
         return instructions::swap(ctx, &params);
     }
+
+    /// Perform a swap across two Globalpools, where the output token of the first swap is the
+    /// input token of the second swap. The slippage threshold applies end to end.
+    ///
+    /// ### Authority
+    /// - "token_authority" - The authority to withdraw tokens from the input token account.
+    ///
+    /// ### Parameters
+    /// - `amount` - The amount of input (first swap) or output (second swap) token to swap from (depending on amount_specified_is_input).
+    /// - `other_amount_threshold` - The maximum input of the first swap or minimum output of the second swap (depending on amount_specified_is_input).
+    /// - `amount_specified_is_input` - Specifies the token the parameter `amount`represents. If true, the amount represents the input token of the first swap.
+    /// - `a_to_b_one` - The direction of the first swap. True if swapping from A to B.
+    /// - `a_to_b_two` - The direction of the second swap. True if swapping from A to B.
+    /// - `sqrt_price_limit_one` - The maximum/minimum price the first swap will swap to.
+    /// - `sqrt_price_limit_two` - The maximum/minimum price the second swap will swap to.
+    ///
+    /// #### Special Errors
+    /// - `DuplicateTwoHopPool` - Both swaps are in the same Globalpool.
+    /// - `InvalidIntermediaryMint` - The output mint of the first swap is not the input mint of the second swap.
+    /// - `IntermediateTokenAmountMismatch` - A swap stopped at its sqrt_price_limit, so the output of the first swap is not the input of the second swap.
+    /// - `AmountOutBelowMinimum` / `AmountInAboveMaximum` - The route exceeds `other_amount_threshold`.
+    /// - `LimitOrderArrayMissing` - A swap reached a tick-array with pending limit orders, but its limit_order_array was not passed.
+    /// - `SwapNotAllowed` - Swapping is disabled in the Clad permissions.
+    pub fn two_hop_swap(ctx: Context<TwoHopSwap>, params: TwoHopSwapParams) -> Result<()> {
+        return instructions::two_hop_swap(ctx, &params);
+    }
+
+    /// Borrow tokens from the vaults of this Globalpool, to be repaid with `flash_repay` later in
+    /// the same transaction. Only one flash loan per Globalpool can be in progress.
+    ///
+    /// ### Authority
+    /// - "borrower" - The authority of the token accounts receiving the loan.
+    ///
+    /// ### Parameters
+    /// - `amount_a` - The amount of token A to borrow.
+    /// - `amount_b` - The amount of token B to borrow.
+    ///
+    /// #### Special Errors
+    /// - `FlashLoanNotRepaid` - No `flash_repay` of this Globalpool follows in the transaction.
+    /// - `FlashLoanCpiNotAllowed` - Called through CPI.
+    /// - `FlashLoanInProgress` - A flash loan of this Globalpool is already in progress.
+    /// - `ZeroBorrowableAmount` - Both amounts are zero.
+    /// - `SwapNotAllowed` - Swapping is disabled in the Clad permissions.
+    pub fn flash_borrow(ctx: Context<FlashBorrow>, params: FlashBorrowParams) -> Result<()> {
+        return instructions::flash_borrow(ctx, &params);
+    }
+
+    /// Repay the flash loan in progress of this Globalpool, with the flash loan fee. The fee is
+    /// split between the protocol and LPs like swap fees.
+    ///
+    /// ### Authority
+    /// - "borrower" - The authority to withdraw tokens from the repaying token accounts.
+    ///
+    /// #### Special Errors
+    /// - `FlashLoanNotInProgress` - No flash loan of this Globalpool is in progress.
+    /// - `FlashLoanCpiNotAllowed` - Called through CPI.
+    pub fn flash_repay(ctx: Context<FlashRepay>) -> Result<()> {
+        return instructions::flash_repay(ctx);
+    }
 }
@@ -1,4 +1,94 @@
-use crate::{errors::ErrorCode, math::add_liquidity_delta, state::*};
+use crate::{
+    errors::ErrorCode,
+    math::{
+        add_liquidity_delta, checked_mul_div, get_tick_group_index,
+        MAX_VOLATILITY_REDUCTION_FACTOR_BPS,
+    },
+    state::*,
+};
+
+// Calculates the next global reward growth variables based on the given timestamp.
+// The provided timestamp must be greater than or equal to the last updated timestamp.
+pub fn next_globalpool_reward_infos(
+    globalpool: &Globalpool,
+    next_timestamp: u64,
+) -> Result<[GlobalpoolRewardInfo; NUM_REWARDS], ErrorCode> {
+    let curr_timestamp = globalpool.reward_last_updated_timestamp;
+    if next_timestamp < curr_timestamp {
+        return Err(ErrorCode::InvalidTimestamp);
+    }
+
+    // Limit orders in range earn no rewards, so they're left out of the liquidity emitted to
+    let liquidity = globalpool
+        .liquidity_available
+        .saturating_sub(globalpool.liquidity_limit_orders);
+
+    // No-op if no liquidity or no change in timestamp
+    if liquidity == 0 || next_timestamp == curr_timestamp {
+        return Ok(globalpool.reward_infos);
+    }
+
+    // Calculate new global reward growth
+    let mut next_reward_infos = globalpool.reward_infos;
+    let time_delta = u128::from(next_timestamp - curr_timestamp);
+    for reward_info in next_reward_infos.iter_mut() {
+        if !reward_info.initialized() {
+            continue;
+        }
+
+        // Calculate the new reward growth delta.
+        // If the calculation overflows, set the delta value to zero.
+        // This will halt reward distributions for this reward.
+        let reward_growth_delta =
+            checked_mul_div(time_delta, reward_info.emissions_per_second_x64, liquidity)
+                .unwrap_or(0);
+
+        // Add the reward growth delta to the global reward growth.
+        reward_info.growth_global_x64 = reward_info
+            .growth_global_x64
+            .wrapping_add(reward_growth_delta);
+    }
+
+    Ok(next_reward_infos)
+}
+
+// Calculates the volatility info a swap at the given timestamp starts from. If the filter period
+// has passed since the last swap, the volatility is measured from the current tick group again,
+// with a reference that keeps a portion of the accumulated volatility until the decay period.
+// The provided timestamp must be greater than or equal to the last swap timestamp.
+pub fn next_globalpool_volatility_info(
+    globalpool: &Globalpool,
+    next_timestamp: u64,
+) -> Result<GlobalpoolVolatilityInfo, ErrorCode> {
+    let mut next_volatility_info = globalpool.volatility_info;
+
+    // No-op if the dynamic fee is disabled
+    if !globalpool.is_dynamic_fee_enabled() {
+        return Ok(next_volatility_info);
+    }
+
+    let curr_timestamp = next_volatility_info.last_swap_timestamp;
+    if next_timestamp < curr_timestamp {
+        return Err(ErrorCode::InvalidTimestamp);
+    }
+
+    let time_delta = next_timestamp - curr_timestamp;
+    if time_delta >= globalpool.volatility_filter_period as u64 {
+        next_volatility_info.tick_group_index_reference =
+            get_tick_group_index(globalpool.tick_current_index, globalpool.tick_spacing);
+        next_volatility_info.volatility_reference =
+            if time_delta < globalpool.volatility_decay_period as u64 {
+                (next_volatility_info.volatility_accumulator as u64
+                    * globalpool.volatility_reduction_factor_bps as u64
+                    / MAX_VOLATILITY_REDUCTION_FACTOR_BPS as u64) as u32
+            } else {
+                0
+            };
+    }
+    next_volatility_info.last_swap_timestamp = next_timestamp;
+
+    Ok(next_volatility_info)
+}
 
 // Calculates the next global liquidity for a globalpool depending on its position relative
 // to the lower and upper tick indexes and the liquidity_delta.
@@ -16,3 +106,137 @@ pub fn next_globalpool_liquidity(
         Ok(globalpool.liquidity_available)
     }
 }
+
+#[cfg(test)]
+mod globalpool_manager_tests {
+    use {
+        super::*,
+        crate::{math::Q64_RESOLUTION, state::globalpool_builder::GlobalpoolBuilder},
+        anchor_lang::prelude::Pubkey,
+    };
+
+    fn globalpool_with_reward(liquidity: u128, emissions_per_second_x64: u128) -> Globalpool {
+        let mut globalpool = GlobalpoolBuilder::new().liquidity(liquidity).build();
+        globalpool.reward_last_updated_timestamp = 100;
+        globalpool
+            .initialize_reward(
+                0,
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+            )
+            .unwrap();
+        globalpool.reward_infos[0].emissions_per_second_x64 = emissions_per_second_x64;
+        globalpool
+    }
+
+    #[test]
+    fn test_next_reward_infos_accrues_per_liquidity() {
+        let globalpool = globalpool_with_reward(1_000, 10 << Q64_RESOLUTION);
+
+        let reward_infos = next_globalpool_reward_infos(&globalpool, 200).unwrap();
+        // 10 tokens per second for 100 seconds, over 1000 liquidity
+        assert_eq!(reward_infos[0].growth_global_x64, 1 << Q64_RESOLUTION);
+        assert_eq!(reward_infos[1], GlobalpoolRewardInfo::default());
+    }
+
+    #[test]
+    fn test_next_reward_infos_excludes_limit_orders() {
+        let mut globalpool = globalpool_with_reward(1_500, 10 << Q64_RESOLUTION);
+        globalpool.liquidity_limit_orders = 500;
+
+        let reward_infos = next_globalpool_reward_infos(&globalpool, 200).unwrap();
+        assert_eq!(reward_infos[0].growth_global_x64, 1 << Q64_RESOLUTION);
+
+        // Only limit orders in range
+        globalpool.liquidity_limit_orders = 1_500;
+        let reward_infos = next_globalpool_reward_infos(&globalpool, 200).unwrap();
+        assert_eq!(reward_infos[0].growth_global_x64, 0);
+    }
+
+    #[test]
+    fn test_next_reward_infos_without_liquidity() {
+        let globalpool = globalpool_with_reward(0, 10 << Q64_RESOLUTION);
+
+        let reward_infos = next_globalpool_reward_infos(&globalpool, 200).unwrap();
+        assert_eq!(reward_infos[0].growth_global_x64, 0);
+    }
+
+    #[test]
+    fn test_next_reward_infos_invalid_timestamp() {
+        let globalpool = globalpool_with_reward(1_000, 10 << Q64_RESOLUTION);
+
+        let result = next_globalpool_reward_infos(&globalpool, 99);
+        assert_eq!(result.unwrap_err(), ErrorCode::InvalidTimestamp);
+    }
+
+    fn globalpool_with_volatility(volatility_accumulator: u32) -> Globalpool {
+        let mut globalpool = GlobalpoolBuilder::new()
+            .tick_spacing(64)
+            .tick_current_index(640)
+            .build();
+        globalpool
+            .update_dynamic_fee_config(10_000, 30, 600, 5_000, 350_000)
+            .unwrap();
+        globalpool.volatility_info = GlobalpoolVolatilityInfo {
+            volatility_accumulator,
+            volatility_reference: 10_000,
+            tick_group_index_reference: 5,
+            last_swap_timestamp: 100,
+        };
+        globalpool
+    }
+
+    #[test]
+    fn test_next_volatility_info_within_filter_period() {
+        let globalpool = globalpool_with_volatility(40_000);
+
+        // Keeps accumulating from the same reference
+        let volatility_info = next_globalpool_volatility_info(&globalpool, 129).unwrap();
+        assert_eq!(
+            volatility_info,
+            GlobalpoolVolatilityInfo {
+                last_swap_timestamp: 129,
+                ..globalpool.volatility_info
+            }
+        );
+    }
+
+    #[test]
+    fn test_next_volatility_info_within_decay_period() {
+        let globalpool = globalpool_with_volatility(40_000);
+
+        // Measured from the current tick group, keeping half of the accumulated volatility
+        let volatility_info = next_globalpool_volatility_info(&globalpool, 130).unwrap();
+        assert_eq!(volatility_info.volatility_accumulator, 40_000);
+        assert_eq!(volatility_info.volatility_reference, 20_000);
+        assert_eq!(volatility_info.tick_group_index_reference, 10);
+        assert_eq!(volatility_info.last_swap_timestamp, 130);
+    }
+
+    #[test]
+    fn test_next_volatility_info_after_decay_period() {
+        let globalpool = globalpool_with_volatility(40_000);
+
+        let volatility_info = next_globalpool_volatility_info(&globalpool, 700).unwrap();
+        assert_eq!(volatility_info.volatility_reference, 0);
+        assert_eq!(volatility_info.tick_group_index_reference, 10);
+    }
+
+    #[test]
+    fn test_next_volatility_info_disabled() {
+        let mut globalpool = globalpool_with_volatility(40_000);
+        globalpool.variable_fee_control = 0;
+
+        let volatility_info = next_globalpool_volatility_info(&globalpool, 700).unwrap();
+        assert_eq!(volatility_info, globalpool.volatility_info);
+    }
+
+    #[test]
+    fn test_next_volatility_info_invalid_timestamp() {
+        let globalpool = globalpool_with_volatility(40_000);
+
+        let result = next_globalpool_volatility_info(&globalpool, 99);
+        assert_eq!(result.unwrap_err(), ErrorCode::InvalidTimestamp);
+    }
+}
@@ -0,0 +1,632 @@
+use {
+    super::tick_manager::{next_fee_growths_inside, next_tick_modify_liquidity_update},
+    crate::{
+        errors::ErrorCode,
+        math::{
+            checked_mul_shift_right, convert_to_liquidity_delta, get_amount_delta_a,
+            get_amount_delta_b, sqrt_price_from_tick_index,
+        },
+        state::*,
+        util::TickSequence,
+    },
+    anchor_lang::prelude::*,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct LimitOrderFillUpdate {
+    pub tick_lower_update: TickUpdate,
+    pub tick_upper_update: TickUpdate,
+    pub range: LimitOrderRange,
+}
+
+// Fills the limit orders pending on the range completed by crossing `tick_index`, ie. the range
+// above the tick when the price moves down (a to b) and the range below it when the price moves up.
+// Must be called after the tick is crossed, with the fee growths it was crossed with.
+pub fn fill_limit_orders(
+    swap_tick_sequence: &mut TickSequence,
+    array_index: usize,
+    tick_index: i32,
+    tick_spacing: u16,
+    a_to_b: bool,
+    fee_growth_global_a: u128,
+    fee_growth_global_b: u128,
+    reward_infos: &[GlobalpoolRewardInfo; NUM_REWARDS],
+) -> Result<()> {
+    let (tick_lower_index, tick_current_index) = if a_to_b {
+        (tick_index, tick_index - 1)
+    } else {
+        (tick_index - tick_spacing as i32, tick_index)
+    };
+    let tick_upper_index = tick_lower_index + tick_spacing as i32;
+
+    // Orders on the last range of a tick array are not allowed, so both ticks are in the array
+    let range = match swap_tick_sequence.get_limit_order_range(
+        array_index,
+        tick_lower_index,
+        tick_spacing,
+    )? {
+        Some(range) if range.has_pending_orders() && range.a_to_b != a_to_b => range,
+        _ => return Ok(()),
+    };
+
+    let tick_lower = *swap_tick_sequence.get_tick(array_index, tick_lower_index, tick_spacing)?;
+    let tick_upper = *swap_tick_sequence.get_tick(array_index, tick_upper_index, tick_spacing)?;
+
+    let update = next_limit_order_fill(
+        &range,
+        &tick_lower,
+        tick_lower_index,
+        &tick_upper,
+        tick_upper_index,
+        tick_current_index,
+        fee_growth_global_a,
+        fee_growth_global_b,
+        reward_infos,
+    )?;
+
+    swap_tick_sequence.update_tick(
+        array_index,
+        tick_lower_index,
+        tick_spacing,
+        &update.tick_lower_update,
+    )?;
+    swap_tick_sequence.update_tick(
+        array_index,
+        tick_upper_index,
+        tick_spacing,
+        &update.tick_upper_update,
+    )?;
+    swap_tick_sequence.update_limit_order_range(
+        array_index,
+        tick_lower_index,
+        tick_spacing,
+        &update.range,
+    )
+}
+
+// Calculates the tick & range updates of filling the pending orders of a range the price moved
+// past. The order liquidity is removed from the ticks, which leaves the proceeds in the vaults for
+// the orders to claim, and the range moves on to the next epoch. Order liquidity is never lent to
+// trade positions, so it is always at the ticks to be removed.
+pub fn next_limit_order_fill(
+    range: &LimitOrderRange,
+    tick_lower: &Tick,
+    tick_lower_index: i32,
+    tick_upper: &Tick,
+    tick_upper_index: i32,
+    tick_current_index: i32,
+    fee_growth_global_a: u128,
+    fee_growth_global_b: u128,
+    reward_infos: &[GlobalpoolRewardInfo; NUM_REWARDS],
+) -> Result<LimitOrderFillUpdate> {
+    let liquidity_delta = convert_to_liquidity_delta(range.liquidity, false)?;
+
+    let (fee_growth_inside_a, fee_growth_inside_b) = next_fee_growths_inside(
+        tick_current_index,
+        tick_lower,
+        tick_lower_index,
+        tick_upper,
+        tick_upper_index,
+        fee_growth_global_a,
+        fee_growth_global_b,
+    );
+
+    let tick_lower_update = next_tick_modify_liquidity_update(
+        tick_lower,
+        tick_lower_index,
+        tick_current_index,
+        fee_growth_global_a,
+        fee_growth_global_b,
+        reward_infos,
+        liquidity_delta,
+        false,
+        false,
+    )?;
+
+    let tick_upper_update = next_tick_modify_liquidity_update(
+        tick_upper,
+        tick_upper_index,
+        tick_current_index,
+        fee_growth_global_a,
+        fee_growth_global_b,
+        reward_infos,
+        liquidity_delta,
+        true,
+        false,
+    )?;
+
+    Ok(LimitOrderFillUpdate {
+        tick_lower_update,
+        tick_upper_update,
+        range: LimitOrderRange {
+            liquidity: 0,
+            a_to_b: range.a_to_b,
+            epoch: range.epoch.wrapping_add(1),
+            liquidity_unclaimed: range.liquidity,
+            fee_growth_inside_a,
+            fee_growth_inside_b,
+        },
+    })
+}
+
+// Calculates the token amount to deposit for a limit order of `liquidity` on the range, ie. token A
+// for orders converting A into B, and token B otherwise. Rounded up.
+pub fn calculate_limit_order_deposit(
+    tick_lower_index: i32,
+    tick_spacing: u16,
+    liquidity: u128,
+    a_to_b: bool,
+) -> Result<u64> {
+    let sqrt_lower_price = sqrt_price_from_tick_index(tick_lower_index);
+    let sqrt_upper_price = sqrt_price_from_tick_index(tick_lower_index + tick_spacing as i32);
+
+    let amount = if a_to_b {
+        get_amount_delta_a(sqrt_lower_price, sqrt_upper_price, liquidity, true)?
+    } else {
+        get_amount_delta_b(sqrt_lower_price, sqrt_upper_price, liquidity, true)?
+    };
+    Ok(amount)
+}
+
+// Calculates the fees earned by a limit order up to the given fee growths inside its range.
+// Fee deltas default to zero on overflow, as they do for liquidity positions.
+pub fn calculate_limit_order_fees(
+    order: &LimitOrder,
+    fee_growth_inside_a: u128,
+    fee_growth_inside_b: u128,
+) -> (u64, u64) {
+    let growth_delta_a = fee_growth_inside_a.wrapping_sub(order.fee_growth_checkpoint_a);
+    let growth_delta_b = fee_growth_inside_b.wrapping_sub(order.fee_growth_checkpoint_b);
+
+    (
+        checked_mul_shift_right(order.liquidity, growth_delta_a).unwrap_or(0),
+        checked_mul_shift_right(order.liquidity, growth_delta_b).unwrap_or(0),
+    )
+}
+
+// Calculates the token amounts a filled limit order claims: the converted tokens, rounded down,
+// and the fees earned until the fill.
+pub fn calculate_limit_order_proceeds(
+    order: &LimitOrder,
+    range: &LimitOrderRange,
+    tick_spacing: u16,
+) -> Result<(u64, u64)> {
+    let sqrt_lower_price = sqrt_price_from_tick_index(order.tick_lower_index);
+    let sqrt_upper_price = sqrt_price_from_tick_index(order.tick_upper_index(tick_spacing));
+
+    let (fee_a, fee_b) =
+        calculate_limit_order_fees(order, range.fee_growth_inside_a, range.fee_growth_inside_b);
+
+    let (amount_a, amount_b) = if order.a_to_b {
+        let amount_b =
+            get_amount_delta_b(sqrt_lower_price, sqrt_upper_price, order.liquidity, false)?;
+        (0, amount_b)
+    } else {
+        let amount_a =
+            get_amount_delta_a(sqrt_lower_price, sqrt_upper_price, order.liquidity, false)?;
+        (amount_a, 0)
+    };
+
+    Ok((
+        amount_a
+            .checked_add(fee_a)
+            .ok_or(ErrorCode::AmountCalcOverflow)?,
+        amount_b
+            .checked_add(fee_b)
+            .ok_or(ErrorCode::AmountCalcOverflow)?,
+    ))
+}
+
+#[cfg(test)]
+mod limit_order_manager_tests {
+    use {super::*, crate::math::Q64_RESOLUTION, std::cell::RefCell};
+
+    const TS_64: u16 = 64;
+
+    fn tick_with_liquidity(liquidity_net: i128, liquidity_gross: u128) -> Tick {
+        Tick {
+            initialized: true,
+            liquidity_net,
+            liquidity_gross,
+            ..Default::default()
+        }
+    }
+
+    fn pending_range(liquidity: u128, a_to_b: bool) -> LimitOrderRange {
+        LimitOrderRange {
+            liquidity,
+            a_to_b,
+            epoch: 3,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_next_limit_order_fill_removes_order_liquidity() {
+        // A liquidity position of 500 shares the ticks with the orders
+        let tick_lower = tick_with_liquidity(1_500, 1_500);
+        let tick_upper = Tick {
+            fee_growth_outside_a: 100,
+            fee_growth_outside_b: 200,
+            ..tick_with_liquidity(-1_500, 1_500)
+        };
+
+        // Above the range, the growth inside is what the upper tick has below it
+        let update = next_limit_order_fill(
+            &pending_range(1_000, true),
+            &tick_lower,
+            0,
+            &tick_upper,
+            64,
+            64,
+            300,
+            400,
+            &[GlobalpoolRewardInfo::default(); NUM_REWARDS],
+        )
+        .unwrap();
+
+        assert_eq!(update.tick_lower_update.liquidity_net, 500);
+        assert_eq!(update.tick_lower_update.liquidity_gross, 500);
+        assert_eq!(update.tick_upper_update.liquidity_net, -500);
+        assert_eq!(update.tick_upper_update.liquidity_gross, 500);
+        assert_eq!(
+            update.range,
+            LimitOrderRange {
+                liquidity: 0,
+                a_to_b: true,
+                epoch: 4,
+                liquidity_unclaimed: 1_000,
+                fee_growth_inside_a: 100,
+                fee_growth_inside_b: 200,
+            }
+        );
+    }
+
+    #[test]
+    fn test_next_limit_order_fill_uninitializes_ticks() {
+        let update = next_limit_order_fill(
+            &pending_range(1_000, false),
+            &tick_with_liquidity(1_000, 1_000),
+            0,
+            &tick_with_liquidity(-1_000, 1_000),
+            64,
+            -1,
+            0,
+            0,
+            &[GlobalpoolRewardInfo::default(); NUM_REWARDS],
+        )
+        .unwrap();
+
+        assert_eq!(update.tick_lower_update, TickUpdate::default());
+        assert_eq!(update.tick_upper_update, TickUpdate::default());
+    }
+
+    fn tick_array_with_orders(
+        liquidity: u128,
+        a_to_b: bool,
+    ) -> (RefCell<TickArray>, RefCell<LimitOrderArray>) {
+        let mut tick_array = TickArray::default();
+        tick_array.ticks[1] = tick_with_liquidity(liquidity as i128, liquidity);
+        tick_array.ticks[2] = tick_with_liquidity(-(liquidity as i128), liquidity);
+        tick_array.limit_order_range_count = 1;
+
+        let mut limit_order_array = LimitOrderArray::default();
+        limit_order_array.ranges[1] = pending_range(liquidity, a_to_b);
+
+        (RefCell::new(tick_array), RefCell::new(limit_order_array))
+    }
+
+    #[test]
+    fn test_fill_limit_orders_moving_up() {
+        let (tick_array, limit_order_array) = tick_array_with_orders(1_000, true);
+        {
+            let mut tick_sequence = TickSequence::new(tick_array.borrow_mut(), None, None);
+            tick_sequence
+                .add_limit_order_array(limit_order_array.borrow_mut())
+                .unwrap();
+
+            let rewards = [GlobalpoolRewardInfo::default(); NUM_REWARDS];
+            // Crossing the lower tick moving up doesn't complete the range
+            fill_limit_orders(&mut tick_sequence, 0, 64, TS_64, false, 0, 0, &rewards).unwrap();
+            assert!(tick_sequence.get_tick(0, 64, TS_64).unwrap().initialized);
+
+            fill_limit_orders(&mut tick_sequence, 0, 128, TS_64, false, 0, 0, &rewards).unwrap();
+            assert!(!tick_sequence.get_tick(0, 64, TS_64).unwrap().initialized);
+            assert!(!tick_sequence.get_tick(0, 128, TS_64).unwrap().initialized);
+        }
+
+        assert_eq!({ tick_array.borrow().limit_order_range_count }, 0);
+        let range = limit_order_array.borrow().ranges[1];
+        assert_eq!({ range.liquidity }, 0);
+        assert_eq!({ range.liquidity_unclaimed }, 1_000);
+        assert_eq!({ range.epoch }, 4);
+    }
+
+    #[test]
+    fn test_fill_limit_orders_wrong_direction() {
+        let (tick_array, limit_order_array) = tick_array_with_orders(1_000, true);
+        let mut tick_sequence = TickSequence::new(tick_array.borrow_mut(), None, None);
+        tick_sequence
+            .add_limit_order_array(limit_order_array.borrow_mut())
+            .unwrap();
+
+        // Orders converting A into B are not filled by the price moving down
+        let rewards = [GlobalpoolRewardInfo::default(); NUM_REWARDS];
+        fill_limit_orders(&mut tick_sequence, 0, 64, TS_64, true, 0, 0, &rewards).unwrap();
+        assert!(tick_sequence.get_tick(0, 64, TS_64).unwrap().initialized);
+        let range = tick_sequence
+            .get_limit_order_range(0, 64, TS_64)
+            .unwrap()
+            .unwrap();
+        assert_eq!({ range.liquidity }, 1_000);
+    }
+
+    #[test]
+    fn test_fill_limit_orders_missing_array() {
+        let (tick_array, limit_order_array) = tick_array_with_orders(1_000, true);
+        let mut tick_sequence = TickSequence::new(tick_array.borrow_mut(), None, None);
+
+        // Without the limit order array, the orders can't be skipped
+        let rewards = [GlobalpoolRewardInfo::default(); NUM_REWARDS];
+        let result = fill_limit_orders(&mut tick_sequence, 0, 128, TS_64, false, 0, 0, &rewards);
+        assert_eq!(
+            result.unwrap_err(),
+            ErrorCode::LimitOrderArrayMissing.into()
+        );
+        drop(tick_sequence);
+        assert_eq!({ limit_order_array.borrow().ranges[1].liquidity }, 1_000);
+        assert_eq!({ tick_array.borrow().limit_order_range_count }, 1);
+    }
+
+    #[test]
+    fn test_calculate_limit_order_proceeds() {
+        let order = LimitOrder {
+            tick_lower_index: 0,
+            a_to_b: true,
+            liquidity: 1_000_000_000,
+            fee_growth_checkpoint_a: 1 << Q64_RESOLUTION,
+            ..Default::default()
+        };
+        let range = LimitOrderRange {
+            fee_growth_inside_a: 3 << Q64_RESOLUTION,
+            fee_growth_inside_b: 1 << (Q64_RESOLUTION - 1),
+            ..Default::default()
+        };
+
+        let deposit = calculate_limit_order_deposit(0, TS_64, order.liquidity, true).unwrap();
+        let (amount_a, amount_b) = calculate_limit_order_proceeds(&order, &range, TS_64).unwrap();
+
+        // Fees of 2 token A and 0.5 token B per unit of liquidity
+        assert_eq!(amount_a, 2_000_000_000);
+        let amount_b_converted = amount_b - 500_000_000;
+        // The order sells above a price of 1, so it gets more token B than it deposited of A
+        assert!(amount_b_converted > deposit);
+        assert!(amount_b_converted < deposit * 101 / 100);
+    }
+}
+
+#[cfg(test)]
+mod swap_limit_order_tests {
+    use {
+        super::*,
+        crate::{
+            manager::swap_manager::swap, math::sqrt_price_from_tick_index,
+            state::globalpool_builder::GlobalpoolBuilder,
+        },
+        std::cell::RefCell,
+    };
+
+    const TS_64: u16 = 64;
+    const POSITION_LIQUIDITY: u128 = 1_000_000_000;
+    const ORDER_LIQUIDITY: u128 = 500_000_000;
+
+    // A position over the whole tick array, and limit orders converting A into B on [128, 192)
+    fn setup() -> (Globalpool, RefCell<TickArray>, RefCell<LimitOrderArray>) {
+        let globalpool = GlobalpoolBuilder::new()
+            .tick_spacing(TS_64)
+            .tick_current_index(10)
+            .sqrt_price(sqrt_price_from_tick_index(10))
+            .liquidity(POSITION_LIQUIDITY)
+            .fee_rate(3_000)
+            .build();
+
+        let mut tick_array = TickArray::default();
+        for (offset, liquidity_net, liquidity_gross) in [
+            (0, POSITION_LIQUIDITY as i128, POSITION_LIQUIDITY),
+            (2, ORDER_LIQUIDITY as i128, ORDER_LIQUIDITY),
+            (3, -(ORDER_LIQUIDITY as i128), ORDER_LIQUIDITY),
+            (87, -(POSITION_LIQUIDITY as i128), POSITION_LIQUIDITY),
+        ] {
+            tick_array.ticks[offset] = Tick {
+                initialized: true,
+                liquidity_net,
+                liquidity_gross,
+                ..Default::default()
+            };
+        }
+        tick_array.limit_order_range_count = 1;
+
+        let mut limit_order_array = LimitOrderArray::default();
+        limit_order_array.ranges[2] = LimitOrderRange {
+            liquidity: ORDER_LIQUIDITY,
+            a_to_b: true,
+            ..Default::default()
+        };
+
+        (
+            globalpool,
+            RefCell::new(tick_array),
+            RefCell::new(limit_order_array),
+        )
+    }
+
+    #[test]
+    fn test_swap_fills_limit_orders_without_flip_back() {
+        let (globalpool, tick_array, limit_order_array) = setup();
+
+        let post_swap = {
+            let mut tick_sequence = TickSequence::new(tick_array.borrow_mut(), None, None);
+            tick_sequence
+                .add_limit_order_array(limit_order_array.borrow_mut())
+                .unwrap();
+            swap(
+                &globalpool,
+                &mut tick_sequence,
+                u64::MAX / 2,
+                sqrt_price_from_tick_index(256),
+                true,
+                false,
+                0,
+            )
+            .unwrap()
+        };
+        assert_eq!(post_swap.next_tick_index, 256);
+        assert_eq!(post_swap.next_liquidity, POSITION_LIQUIDITY);
+
+        // The order liquidity is gone from the ticks
+        assert!(!tick_array.borrow().ticks[2].initialized);
+        assert!(!tick_array.borrow().ticks[3].initialized);
+        assert_eq!({ tick_array.borrow().limit_order_range_count }, 0);
+
+        let range = limit_order_array.borrow().ranges[2];
+        assert_eq!({ range.liquidity }, 0);
+        assert_eq!({ range.liquidity_unclaimed }, ORDER_LIQUIDITY);
+        assert_eq!({ range.epoch }, 1);
+        // The orders earned fees in token B while the price went through the range
+        let fee_growth_inside_b = range.fee_growth_inside_b;
+        assert!(fee_growth_inside_b > 0);
+
+        // Swapping back doesn't convert the proceeds back, the range has no liquidity of the orders
+        let globalpool = Globalpool {
+            tick_current_index: post_swap.next_tick_index,
+            sqrt_price: post_swap.next_sqrt_price,
+            liquidity_available: post_swap.next_liquidity,
+            fee_growth_global_b: post_swap.next_fee_growth_global,
+            ..globalpool
+        };
+        let post_swap_back = {
+            let mut tick_sequence = TickSequence::new(tick_array.borrow_mut(), None, None);
+            swap(
+                &globalpool,
+                &mut tick_sequence,
+                u64::MAX / 2,
+                sqrt_price_from_tick_index(10),
+                true,
+                true,
+                0,
+            )
+            .unwrap()
+        };
+        assert_eq!(post_swap_back.next_liquidity, POSITION_LIQUIDITY);
+        assert_eq!(limit_order_array.borrow().ranges[2], range);
+    }
+
+    #[test]
+    fn test_swap_within_range_keeps_limit_orders() {
+        let (globalpool, tick_array, limit_order_array) = setup();
+
+        let post_swap = {
+            let mut tick_sequence = TickSequence::new(tick_array.borrow_mut(), None, None);
+            tick_sequence
+                .add_limit_order_array(limit_order_array.borrow_mut())
+                .unwrap();
+            swap(
+                &globalpool,
+                &mut tick_sequence,
+                u64::MAX / 2,
+                sqrt_price_from_tick_index(160),
+                true,
+                false,
+                0,
+            )
+            .unwrap()
+        };
+        assert_eq!(
+            post_swap.next_liquidity,
+            POSITION_LIQUIDITY + ORDER_LIQUIDITY
+        );
+        assert_eq!(
+            { limit_order_array.borrow().ranges[2].liquidity },
+            ORDER_LIQUIDITY
+        );
+        assert_eq!({ tick_array.borrow().limit_order_range_count }, 1);
+    }
+
+    #[test]
+    fn test_swap_without_limit_order_array() {
+        let (globalpool, tick_array, limit_order_array) = setup();
+
+        // Crossing the range of the orders without their limit order array fails the swap
+        let result = {
+            let mut tick_sequence = TickSequence::new(tick_array.borrow_mut(), None, None);
+            swap(
+                &globalpool,
+                &mut tick_sequence,
+                u64::MAX / 2,
+                sqrt_price_from_tick_index(256),
+                true,
+                false,
+                0,
+            )
+        };
+        assert_eq!(
+            result.unwrap_err(),
+            ErrorCode::LimitOrderArrayMissing.into()
+        );
+        assert_eq!(
+            { limit_order_array.borrow().ranges[2].liquidity },
+            ORDER_LIQUIDITY
+        );
+    }
+
+    #[test]
+    fn test_swap_into_range_tracks_limit_order_liquidity() {
+        let (globalpool, tick_array, limit_order_array) = setup();
+
+        // Ending within the range of the orders, their liquidity is in range
+        let post_swap = {
+            let mut tick_sequence = TickSequence::new(tick_array.borrow_mut(), None, None);
+            tick_sequence
+                .add_limit_order_array(limit_order_array.borrow_mut())
+                .unwrap();
+            swap(
+                &globalpool,
+                &mut tick_sequence,
+                u64::MAX / 2,
+                sqrt_price_from_tick_index(160),
+                true,
+                false,
+                0,
+            )
+            .unwrap()
+        };
+        assert_eq!(post_swap.next_liquidity_limit_orders, ORDER_LIQUIDITY);
+
+        // Leaving it without filling the orders takes it out of range again
+        let globalpool = Globalpool {
+            tick_current_index: post_swap.next_tick_index,
+            sqrt_price: post_swap.next_sqrt_price,
+            liquidity_available: post_swap.next_liquidity,
+            liquidity_limit_orders: post_swap.next_liquidity_limit_orders,
+            ..globalpool
+        };
+        let post_swap_back = {
+            let mut tick_sequence = TickSequence::new(tick_array.borrow_mut(), None, None);
+            tick_sequence
+                .add_limit_order_array(limit_order_array.borrow_mut())
+                .unwrap();
+            swap(
+                &globalpool,
+                &mut tick_sequence,
+                u64::MAX / 2,
+                sqrt_price_from_tick_index(64),
+                true,
+                true,
+                0,
+            )
+            .unwrap()
+        };
+        assert_eq!(post_swap_back.next_liquidity, POSITION_LIQUIDITY);
+        assert_eq!(post_swap_back.next_liquidity_limit_orders, 0);
+    }
+}
@@ -1,13 +1,15 @@
 use {
     super::{
-        globalpool_manager::next_globalpool_liquidity,
-        loan_manager::ModifyLoanUpdate,
-        position_manager::next_position_modify_liquidity_update,
-        tick_manager::{next_fee_growths_inside, next_tick_modify_liquidity_update},
+        globalpool_manager::{next_globalpool_liquidity, next_globalpool_reward_infos},
+        loan_manager::{calculate_tick_liquidity_lent_by_positions, ModifyLoanUpdate},
+        position_manager::{next_position_interest_update, next_position_modify_liquidity_update},
+        tick_manager::{
+            next_fee_growths_inside, next_reward_growths_inside, next_tick_modify_liquidity_update,
+        },
     },
     crate::{
         errors::ErrorCode,
-        math::{get_amount_delta_a, get_amount_delta_b, sqrt_price_from_tick_index},
+        math::{get_amount_delta_a, get_amount_delta_b, sqrt_price_from_tick_index, U256},
         state::*,
     },
     anchor_lang::prelude::{AccountLoader, *},
@@ -19,11 +21,12 @@ pub struct ModifyLiquidityUpdate {
     pub tick_lower_update: TickUpdate,
     pub tick_upper_update: TickUpdate,
     pub position_update: LiquidityPositionUpdate,
+    pub reward_infos: [GlobalpoolRewardInfo; NUM_REWARDS],
 }
 
 // Calculates state after modifying liquidity by the liquidity_delta for the given positon.
-// Fee growths will also be calculated by this function.
-// To trigger only calculation of fee growths, use calculate_fee_growths.
+// Fee and reward growths will also be calculated by this function.
+// To trigger only calculation of fee and reward growths, use calculate_fee_and_reward_growths.
 pub fn calculate_modify_liquidity<'info>(
     globalpool: &Globalpool,
     position: &LiquidityPosition,
@@ -52,13 +55,13 @@ pub fn calculate_modify_liquidity<'info>(
     )?)
 }
 
-pub fn calculate_fee_growths<'info>(
+pub fn calculate_fee_and_reward_growths<'info>(
     globalpool: &Globalpool,
     position: &LiquidityPosition,
     tick_array_lower: &AccountLoader<'info, TickArray>,
     tick_array_upper: &AccountLoader<'info, TickArray>,
     timestamp: u64,
-) -> Result<LiquidityPositionUpdate> {
+) -> Result<(LiquidityPositionUpdate, [GlobalpoolRewardInfo; NUM_REWARDS])> {
     let tick_array_lower = tick_array_lower.load()?;
     let tick_lower =
         tick_array_lower.get_tick(position.tick_lower_index, globalpool.tick_spacing)?;
@@ -67,7 +70,7 @@ pub fn calculate_fee_growths<'info>(
     let tick_upper =
         tick_array_upper.get_tick(position.tick_upper_index, globalpool.tick_spacing)?;
 
-    // Pass in a liquidity_delta value of 0 to trigger only calculations for fee growths.
+    // Pass in a liquidity_delta value of 0 to trigger only calculations for fee and reward growths.
     // Calculating fees for positions with zero liquidity will result in an error.
     let update = _calculate_modify_liquidity(
         globalpool,
@@ -79,7 +82,7 @@ pub fn calculate_fee_growths<'info>(
         0,
         timestamp,
     )?;
-    Ok(update.position_update)
+    Ok((update.position_update, update.reward_infos))
 }
 
 // Calculates the state changes after modifying liquidity of a globalpool position.
@@ -91,7 +94,7 @@ fn _calculate_modify_liquidity(
     tick_lower_index: i32,
     tick_upper_index: i32,
     liquidity_delta: i128,
-    _timestamp: u64,
+    timestamp: u64,
 ) -> Result<ModifyLiquidityUpdate> {
     // Disallow only updating position fee growth when position has zero liquidity
     if liquidity_delta == 0 && position.liquidity == 0 {
@@ -105,12 +108,15 @@ fn _calculate_modify_liquidity(
         liquidity_delta,
     )?;
 
+    let next_reward_infos = next_globalpool_reward_infos(globalpool, timestamp)?;
+
     let tick_lower_update = next_tick_modify_liquidity_update(
         tick_lower,
         tick_lower_index,
         globalpool.tick_current_index,
         globalpool.fee_growth_global_a,
         globalpool.fee_growth_global_b,
+        &next_reward_infos,
         liquidity_delta,
         false,
         false,
@@ -122,6 +128,7 @@ fn _calculate_modify_liquidity(
         globalpool.tick_current_index,
         globalpool.fee_growth_global_a,
         globalpool.fee_growth_global_b,
+        &next_reward_infos,
         liquidity_delta,
         true,
         false,
@@ -137,11 +144,21 @@ fn _calculate_modify_liquidity(
         globalpool.fee_growth_global_b,
     );
 
+    let reward_growths_inside = next_reward_growths_inside(
+        globalpool.tick_current_index,
+        tick_lower,
+        tick_lower_index,
+        tick_upper,
+        tick_upper_index,
+        &next_reward_infos,
+    );
+
     let position_update = next_position_modify_liquidity_update(
         position,
         liquidity_delta,
         fee_growth_inside_a,
         fee_growth_inside_b,
+        &reward_growths_inside,
     )?;
 
     Ok(ModifyLiquidityUpdate {
@@ -149,6 +166,7 @@ fn _calculate_modify_liquidity(
         position_update,
         tick_lower_update,
         tick_upper_update,
+        reward_infos: next_reward_infos,
     })
 }
 
@@ -187,6 +205,180 @@ pub fn calculate_liquidity_token_deltas(
     Ok((delta_a, delta_b))
 }
 
+// Liquidity that a position can withdraw right away. Liquidity borrowed at a tick, or reserved for
+// the limit orders and queued withdrawals at it, is not idle for any of the positions bounded by the
+// tick. Queued liquidity is redeemed first-come by the positions that queued it, so it is idle for
+// them when `is_redeeming_queued`. The tick's TickInterest and LimitOrderArray accounts don't exist
+// until a loan or order is made there.
+pub fn calculate_withdrawable_liquidity<'info>(
+    globalpool: &Globalpool,
+    position: &LiquidityPosition,
+    tick_array_lower: &AccountLoader<'info, TickArray>,
+    tick_array_upper: &AccountLoader<'info, TickArray>,
+    tick_interest_lower: &AccountInfo<'info>,
+    tick_interest_upper: &AccountInfo<'info>,
+    limit_order_array_lower: &AccountInfo<'info>,
+    limit_order_array_upper: &AccountInfo<'info>,
+    is_redeeming_queued: bool,
+) -> Result<u128> {
+    let tick_array_lower = tick_array_lower.load()?;
+    let tick_lower = tick_array_lower.get_tick(position.tick_lower_index, globalpool.tick_spacing)?;
+    let tick_lower_liquidity_idle = calculate_tick_liquidity_idle(
+        globalpool,
+        tick_lower,
+        position.tick_lower_index,
+        &TickInterest::load(tick_interest_lower)?,
+        limit_order_array_lower,
+        is_redeeming_queued,
+    )?;
+
+    let tick_array_upper = tick_array_upper.load()?;
+    let tick_upper = tick_array_upper.get_tick(position.tick_upper_index, globalpool.tick_spacing)?;
+    let tick_upper_liquidity_idle = calculate_tick_liquidity_idle(
+        globalpool,
+        tick_upper,
+        position.tick_upper_index,
+        &TickInterest::load(tick_interest_upper)?,
+        limit_order_array_upper,
+        is_redeeming_queued,
+    )?;
+
+    Ok(_calculate_withdrawable_liquidity(
+        position.liquidity,
+        tick_lower_liquidity_idle,
+        tick_upper_liquidity_idle,
+    ))
+}
+
+// Liquidity idle at a tick, see calculate_withdrawable_liquidity.
+pub fn calculate_tick_liquidity_idle<'info>(
+    globalpool: &Globalpool,
+    tick: &Tick,
+    tick_index: i32,
+    tick_interest: &TickInterest,
+    limit_order_array: &AccountInfo<'info>,
+    is_redeeming_queued: bool,
+) -> Result<u128> {
+    let liquidity_orders = LimitOrderArray::load_liquidity_at_tick(
+        limit_order_array,
+        tick_index,
+        globalpool.tick_spacing,
+    )?;
+    let liquidity_queued = if is_redeeming_queued {
+        0
+    } else {
+        tick_interest.liquidity_queued
+    };
+
+    Ok(tick.liquidity_idle(
+        tick_interest.liquidity_borrowed,
+        liquidity_orders + liquidity_queued,
+    ))
+}
+
+fn _calculate_withdrawable_liquidity(
+    position_liquidity: u128,
+    tick_lower_liquidity_idle: u128,
+    tick_upper_liquidity_idle: u128,
+) -> u128 {
+    position_liquidity
+        .min(tick_lower_liquidity_idle)
+        .min(tick_upper_liquidity_idle)
+}
+
+// Token minimum of a withdrawal, scaled down to the share of the requested liquidity withdrawn
+pub fn calculate_partial_token_min(
+    token_min: u64,
+    liquidity_withdrawn: u128,
+    liquidity_requested: u128,
+) -> Result<u64> {
+    if liquidity_requested == 0 {
+        return Err(ErrorCode::LiquidityZero.into());
+    }
+
+    let token_min = U256::from(token_min) * U256::from(liquidity_withdrawn)
+        / U256::from(liquidity_requested);
+
+    Ok(token_min.try_into_u64()?)
+}
+
+// Interest growths of a liquidity position's lower & upper ticks up to `timestamp`, summed. The
+// accounts must be the position's TickInterest addresses, which are not created until a trade
+// position borrows there.
+pub fn calculate_position_interest_growths<'info>(
+    tick_interest_lower: &AccountInfo<'info>,
+    tick_interest_upper: &AccountInfo<'info>,
+    timestamp: u64,
+) -> Result<(u128, u128)> {
+    let (lower_growth_a, lower_growth_b) =
+        TickInterest::load_interest_growths(tick_interest_lower, timestamp)?;
+    let (upper_growth_a, upper_growth_b) =
+        TickInterest::load_interest_growths(tick_interest_upper, timestamp)?;
+
+    Ok((
+        lower_growth_a.wrapping_add(upper_growth_a),
+        lower_growth_b.wrapping_add(upper_growth_b),
+    ))
+}
+
+// Checkpoints the interest of a tick at the liquidity that positions lend at it after changing
+// their liquidity there (see TickInterest). The tick's TickInterest and LimitOrderArray accounts
+// don't exist until a loan or order is made there, and without a loan there is nothing to release.
+pub fn checkpoint_tick_interest<'info>(
+    globalpool: &Globalpool,
+    tick_array: &AccountLoader<'info, TickArray>,
+    tick_index: i32,
+    tick_interest: &AccountInfo<'info>,
+    limit_order_array: &AccountInfo<'info>,
+    timestamp: u64,
+) -> Result<()> {
+    if tick_interest.owner != &crate::ID || tick_interest.data_is_empty() {
+        return Ok(());
+    }
+
+    let mut tick_interest = Account::<TickInterest>::try_from(tick_interest)?;
+    let liquidity_lent = calculate_tick_liquidity_lent_by_positions(
+        globalpool,
+        tick_array,
+        tick_index,
+        &tick_interest,
+        limit_order_array,
+    )?;
+    tick_interest.checkpoint(liquidity_lent, timestamp)?;
+    tick_interest.exit(&crate::ID)
+}
+
+// Checkpoints the interest of a liquidity position's lower & upper ticks (see
+// checkpoint_tick_interest).
+pub fn checkpoint_position_tick_interests<'info>(
+    globalpool: &Globalpool,
+    position: &LiquidityPosition,
+    tick_array_lower: &AccountLoader<'info, TickArray>,
+    tick_array_upper: &AccountLoader<'info, TickArray>,
+    tick_interest_lower: &AccountInfo<'info>,
+    tick_interest_upper: &AccountInfo<'info>,
+    limit_order_array_lower: &AccountInfo<'info>,
+    limit_order_array_upper: &AccountInfo<'info>,
+    timestamp: u64,
+) -> Result<()> {
+    checkpoint_tick_interest(
+        globalpool,
+        tick_array_lower,
+        position.tick_lower_index,
+        tick_interest_lower,
+        limit_order_array_lower,
+        timestamp,
+    )?;
+    checkpoint_tick_interest(
+        globalpool,
+        tick_array_upper,
+        position.tick_upper_index,
+        tick_interest_upper,
+        limit_order_array_upper,
+        timestamp,
+    )
+}
+
 // Liquidity Position sync liquidity values
 pub fn sync_modify_liquidity_values<'info>(
     globalpool: &mut Globalpool,
@@ -194,6 +386,7 @@ pub fn sync_modify_liquidity_values<'info>(
     tick_array_lower: &AccountLoader<'info, TickArray>,
     tick_array_upper: &AccountLoader<'info, TickArray>,
     modify_liquidity_update: ModifyLiquidityUpdate,
+    timestamp: u64,
 ) -> Result<()> {
     position.update(&modify_liquidity_update.position_update);
 
@@ -209,7 +402,11 @@ pub fn sync_modify_liquidity_values<'info>(
         &modify_liquidity_update.tick_upper_update,
     )?;
 
-    globalpool.update_liquidity(modify_liquidity_update.globalpool_liquidity);
+    globalpool.update_rewards_and_liquidity(
+        modify_liquidity_update.reward_infos,
+        modify_liquidity_update.globalpool_liquidity,
+        timestamp,
+    )?;
 
     Ok(())
 }
@@ -220,10 +417,16 @@ pub fn sync_modify_liquidity_values_for_loan<'info>(
     position: &mut TradePosition,
     tick_array_lower: &AccountLoader<'info, TickArray>,
     tick_array_upper: &AccountLoader<'info, TickArray>,
+    tick_interest_lower: &mut TickInterest,
+    tick_interest_upper: &mut TickInterest,
     modify_loan_update: &ModifyLoanUpdate,
+    timestamp: u64,
 ) -> Result<()> {
     position.update(&modify_loan_update.position_update);
 
+    tick_interest_lower.update_liquidity_borrowed(modify_loan_update.liquidity_borrowed_delta)?;
+    tick_interest_upper.update_liquidity_borrowed(modify_loan_update.liquidity_borrowed_delta)?;
+
     tick_array_lower.load_mut()?.update_tick(
         position.tick_lower_index,
         globalpool.tick_spacing,
@@ -236,7 +439,41 @@ pub fn sync_modify_liquidity_values_for_loan<'info>(
         &modify_loan_update.tick_upper_update,
     )?;
 
-    globalpool.update_liquidity(modify_loan_update.globalpool_liquidity);
+    globalpool.update_rewards_and_liquidity(
+        modify_loan_update.reward_infos,
+        modify_loan_update.globalpool_liquidity,
+        timestamp,
+    )?;
+
+    Ok(())
+}
+
+// Accrues the fees, rewards & interest earned by a liquidity position up to now, checkpointing it at
+// the current growths of its range. Positions must be synced before moving liquidity between them.
+pub fn sync_position_growths<'info>(
+    globalpool: &mut Globalpool,
+    position: &mut LiquidityPosition,
+    tick_array_lower: &AccountLoader<'info, TickArray>,
+    tick_array_upper: &AccountLoader<'info, TickArray>,
+    tick_interest_lower: &AccountInfo<'info>,
+    tick_interest_upper: &AccountInfo<'info>,
+    timestamp: u64,
+) -> Result<()> {
+    let (interest_growth_a, interest_growth_b) =
+        calculate_position_interest_growths(tick_interest_lower, tick_interest_upper, timestamp)?;
+    let interest_update =
+        next_position_interest_update(position, interest_growth_a, interest_growth_b);
+    position.update_interest(&interest_update);
+
+    let (position_update, reward_infos) = calculate_fee_and_reward_growths(
+        globalpool,
+        position,
+        tick_array_lower,
+        tick_array_upper,
+        timestamp,
+    )?;
+    globalpool.update_rewards(reward_infos, timestamp);
+    position.update(&position_update);
 
     Ok(())
 }
@@ -548,9 +785,9 @@ mod calculate_modify_liquidity_unit_tests {
                             initialized: true,
                             liquidity_gross: 20,
                             liquidity_net: -20,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: to_x64(10),
                             fee_growth_outside_b: to_x64(10),
+                            ..Default::default()
                         },
                     },
                 );
@@ -758,9 +995,9 @@ mod calculate_modify_liquidity_unit_tests {
                             initialized: true,
                             liquidity_gross: 10,
                             liquidity_net: 10,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: to_x64(10),
                             fee_growth_outside_b: to_x64(20),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
@@ -830,17 +1067,17 @@ mod calculate_modify_liquidity_unit_tests {
                             initialized: true,
                             liquidity_gross: 10,
                             liquidity_net: 10,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: to_x64(10),
                             fee_growth_outside_b: to_x64(10),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
                             liquidity_gross: 20,
                             liquidity_net: -20,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: to_x64(10),
                             fee_growth_outside_b: to_x64(10),
+                            ..Default::default()
                         },
                     },
                 );
@@ -999,17 +1236,17 @@ mod calculate_modify_liquidity_unit_tests {
                             initialized: true,
                             liquidity_gross: 10,
                             liquidity_net: 10,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: to_x64(10),
                             fee_growth_outside_b: to_x64(20),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
                             liquidity_gross: 10,
                             liquidity_net: -10,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: to_x64(10),
                             fee_growth_outside_b: to_x64(20),
+                            ..Default::default()
                         },
                     },
                 );
@@ -1057,9 +1294,9 @@ mod calculate_modify_liquidity_unit_tests {
                             initialized: true,
                             liquidity_gross: 10,
                             liquidity_net: 10,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: to_x64(10),
                             fee_growth_outside_b: to_x64(20),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
@@ -1119,9 +1356,9 @@ mod calculate_modify_liquidity_unit_tests {
                             initialized: true,
                             liquidity_gross: 10,
                             liquidity_net: 10,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: to_x64(10),
                             fee_growth_outside_b: to_x64(10),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
@@ -1168,11 +1405,11 @@ mod calculate_modify_liquidity_unit_tests {
                         initialized: true,
                         liquidity_net: -20,
                         liquidity_gross: 20,
-                        liquidity_borrowed: 0,
                         // 15 = 35 - 20
                         fee_growth_outside_a: to_x64(15),
                         // 15 = 35 - 20
                         fee_growth_outside_b: to_x64(15),
+                        ..Default::default()
                     }
                 );
 
@@ -1199,24 +1436,25 @@ mod calculate_modify_liquidity_unit_tests {
                             fee_owed_a: 150,
                             fee_growth_checkpoint_b: to_x64(5),
                             fee_owed_b: 150,
+                            ..Default::default()
                         },
                         tick_lower_update: TickUpdate {
                             initialized: true,
                             liquidity_gross: 10,
                             liquidity_net: 10,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: to_x64(10),
                             fee_growth_outside_b: to_x64(10),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
                             liquidity_net: -20,
                             liquidity_gross: 20,
-                            liquidity_borrowed: 0,
                             // 15
                             fee_growth_outside_a: to_x64(15),
                             // 15
                             fee_growth_outside_b: to_x64(15),
+                            ..Default::default()
                         },
                     },
                 );
@@ -1268,9 +1506,9 @@ mod calculate_modify_liquidity_unit_tests {
                             initialized: true,
                             liquidity_gross: 10,
                             liquidity_net: -10,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: to_x64(10),
                             fee_growth_outside_b: to_x64(20),
+                            ..Default::default()
                         },
                     },
                 );
@@ -1379,17 +1617,17 @@ mod calculate_modify_liquidity_unit_tests {
                             initialized: true,
                             liquidity_gross: 20,
                             liquidity_net: 20,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: to_x64(10),
                             fee_growth_outside_b: to_x64(10),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
                             liquidity_gross: 20,
                             liquidity_net: -20,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: to_x64(20),
                             fee_growth_outside_b: to_x64(20),
+                            ..Default::default()
                         },
                     },
                 );
@@ -1531,22 +1769,23 @@ mod calculate_modify_liquidity_unit_tests {
                             fee_owed_a: 500,
                             fee_growth_checkpoint_b: to_x64(50),
                             fee_owed_b: 500,
+                            ..Default::default()
                         },
                         tick_lower_update: TickUpdate {
                             initialized: true,
                             liquidity_net: 10,
                             liquidity_gross: 10,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: to_x64(100),
                             fee_growth_outside_b: to_x64(100),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
                             liquidity_net: -10,
                             liquidity_gross: 10,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: to_x64(50),
                             fee_growth_outside_b: to_x64(50),
+                            ..Default::default()
                         },
                     },
                 );
@@ -1591,6 +1830,7 @@ mod calculate_modify_liquidity_unit_tests {
                             fee_owed_a: 100,
                             fee_growth_checkpoint_b: to_x64(20),
                             fee_owed_b: 200,
+                            ..Default::default()
                         },
                         tick_lower_update: TickUpdate::default(),
                         tick_upper_update: TickUpdate::default(),
@@ -1631,6 +1871,7 @@ mod calculate_modify_liquidity_unit_tests {
                             fee_owed_a: 100,
                             fee_growth_checkpoint_b: to_x64(20),
                             fee_owed_b: 200,
+                            ..Default::default()
                         },
                         tick_lower_update: TickUpdate {
                             initialized: true,
@@ -1821,6 +2062,7 @@ mod calculate_modify_liquidity_unit_tests {
                         fee_owed_a: 100,
                         fee_growth_checkpoint_b: to_x64(20),
                         fee_owed_b: 200,
+                        ..Default::default()
                     },
                     tick_lower_update: TickUpdate {
                         initialized: true,
@@ -1966,6 +2208,7 @@ mod calculate_modify_liquidity_unit_tests {
                         fee_owed_a: 100,
                         fee_growth_checkpoint_b: to_x64(20),
                         fee_owed_b: 200,
+                        ..Default::default()
                     },
                     tick_lower_update: TickUpdate {
                         initialized: true,
@@ -2111,6 +2354,7 @@ mod calculate_modify_liquidity_unit_tests {
                         fee_owed_a: 100,
                         fee_growth_checkpoint_b: to_x64(20),
                         fee_owed_b: 200,
+                        ..Default::default()
                     },
                     tick_lower_update: TickUpdate {
                         initialized: true,
@@ -2249,6 +2493,7 @@ mod calculate_modify_liquidity_unit_tests {
                     fee_owed_a: 1000,
                     fee_growth_checkpoint_b: to_x64(198), // 220 - 20 - 2
                     fee_owed_b: 2000,
+                    ..Default::default()
                 }
             );
             test.apply_update(&update);
@@ -2277,22 +2522,23 @@ mod calculate_modify_liquidity_unit_tests {
                         fee_owed_a: 2500,
                         fee_growth_checkpoint_b: to_x64(218), // 240 - 20 - 2
                         fee_owed_b: 5000,
+                        ..Default::default()
                     },
                     tick_lower_update: TickUpdate {
                         initialized: true,
                         liquidity_gross: 10,
                         liquidity_net: 10,
-                        liquidity_borrowed: 0,
                         fee_growth_outside_a: to_x64(10),
                         fee_growth_outside_b: to_x64(20),
+                        ..Default::default()
                     },
                     tick_upper_update: TickUpdate {
                         initialized: true,
                         liquidity_gross: 10,
                         liquidity_net: -10,
-                        liquidity_borrowed: 0,
                         fee_growth_outside_a: to_x64(1),
                         fee_growth_outside_b: to_x64(2),
+                        ..Default::default()
                     },
                 },
             );
@@ -2392,14 +2638,15 @@ mod calculate_modify_liquidity_unit_tests {
                             fee_owed_a: 20000,
                             fee_growth_checkpoint_b: to_x64(20),
                             fee_owed_b: 20000,
+                            ..Default::default()
                         },
                         tick_lower_update: TickUpdate {
                             initialized: true,
                             liquidity_net: 1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: to_x64(20),
                             fee_growth_outside_b: to_x64(20),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
@@ -2454,9 +2701,9 @@ mod calculate_modify_liquidity_unit_tests {
                             initialized: true,
                             liquidity_net: 1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: u128::MAX - to_x64(100),
                             fee_growth_outside_b: u128::MAX - to_x64(100),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
@@ -2502,14 +2749,15 @@ mod calculate_modify_liquidity_unit_tests {
                             fee_owed_a: 20000,
                             fee_growth_checkpoint_b: to_x64(20),
                             fee_owed_b: 20000,
+                            ..Default::default()
                         },
                         tick_lower_update: TickUpdate {
                             initialized: true,
                             liquidity_net: 1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: to_x64(20),
                             fee_growth_outside_b: to_x64(20),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
@@ -2564,17 +2812,17 @@ mod calculate_modify_liquidity_unit_tests {
                             initialized: true,
                             liquidity_net: 1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: u128::MAX - to_x64(100),
                             fee_growth_outside_b: u128::MAX - to_x64(100),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
                             liquidity_net: -1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: u128::MAX - to_x64(100),
                             fee_growth_outside_b: u128::MAX - to_x64(100),
+                            ..Default::default()
                         },
                     },
                 );
@@ -2617,22 +2865,23 @@ mod calculate_modify_liquidity_unit_tests {
                             fee_owed_a: 20000,
                             fee_growth_checkpoint_b: to_x64(20),
                             fee_owed_b: 20000,
+                            ..Default::default()
                         },
                         tick_lower_update: TickUpdate {
                             initialized: true,
                             liquidity_net: 1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: to_x64(40),
                             fee_growth_outside_b: to_x64(40),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
                             liquidity_net: -1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: to_x64(20),
                             fee_growth_outside_b: to_x64(20), // 1
+                            ..Default::default()
                         },
                     },
                 );
@@ -2725,14 +2974,15 @@ mod calculate_modify_liquidity_unit_tests {
                             fee_owed_a: 90000,
                             fee_growth_checkpoint_b: to_x64(90),
                             fee_owed_b: 90000,
+                            ..Default::default()
                         },
                         tick_lower_update: TickUpdate {
                             initialized: true,
                             liquidity_net: 1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: u128::MAX - to_x64(80),
                             fee_growth_outside_b: u128::MAX - to_x64(80),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
@@ -2786,9 +3036,9 @@ mod calculate_modify_liquidity_unit_tests {
                             initialized: true,
                             liquidity_net: 1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: u128::MAX - to_x64(100),
                             fee_growth_outside_b: u128::MAX - to_x64(100),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
@@ -2830,14 +3080,15 @@ mod calculate_modify_liquidity_unit_tests {
                             fee_owed_a: 110000,
                             fee_growth_checkpoint_b: to_x64(110),
                             fee_owed_b: 110000,
+                            ..Default::default()
                         },
                         tick_lower_update: TickUpdate {
                             initialized: true,
                             liquidity_net: 1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: u128::MAX - to_x64(100),
                             fee_growth_outside_b: u128::MAX - to_x64(100),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
@@ -2891,17 +3142,17 @@ mod calculate_modify_liquidity_unit_tests {
                             initialized: true,
                             liquidity_net: 1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: u128::MAX - to_x64(100),
                             fee_growth_outside_b: u128::MAX - to_x64(100),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
                             liquidity_net: -1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: u128::MAX - to_x64(100),
                             fee_growth_outside_b: u128::MAX - to_x64(100),
+                            ..Default::default()
                         },
                     },
                 );
@@ -2939,22 +3190,23 @@ mod calculate_modify_liquidity_unit_tests {
                             fee_owed_a: 90000,
                             fee_growth_checkpoint_b: to_x64(90),
                             fee_owed_b: 90000,
+                            ..Default::default()
                         },
                         tick_lower_update: TickUpdate {
                             initialized: true,
                             liquidity_net: 1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: u128::MAX - to_x64(100),
                             fee_growth_outside_b: u128::MAX - to_x64(100),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
                             liquidity_net: -1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: to_x64(20),
                             fee_growth_outside_b: to_x64(20),
+                            ..Default::default()
                         },
                     },
                 );
@@ -3051,22 +3303,23 @@ mod calculate_modify_liquidity_unit_tests {
                             fee_owed_a: 20000,
                             fee_growth_checkpoint_b: to_x64(20),
                             fee_owed_b: 20000,
+                            ..Default::default()
                         },
                         tick_lower_update: TickUpdate {
                             initialized: true,
                             liquidity_net: 1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: u128::MAX - to_x64(80),
                             fee_growth_outside_b: u128::MAX - to_x64(80),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
                             liquidity_net: -1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: u128::MAX - to_x64(60),
                             fee_growth_outside_b: u128::MAX - to_x64(60),
+                            ..Default::default()
                         },
                     },
                 );
@@ -3115,9 +3368,9 @@ mod calculate_modify_liquidity_unit_tests {
                             initialized: true,
                             liquidity_net: 1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: u128::MAX - to_x64(100),
                             fee_growth_outside_b: u128::MAX - to_x64(100),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
@@ -3163,22 +3416,23 @@ mod calculate_modify_liquidity_unit_tests {
                             fee_owed_a: 20000,
                             fee_growth_checkpoint_b: to_x64(20),
                             fee_owed_b: 20000,
+                            ..Default::default()
                         },
                         tick_lower_update: TickUpdate {
                             initialized: true,
                             liquidity_net: 1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: u128::MAX - to_x64(100),
                             fee_growth_outside_b: u128::MAX - to_x64(100),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
                             liquidity_net: -1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: u128::MAX - to_x64(80),
                             fee_growth_outside_b: u128::MAX - to_x64(80),
+                            ..Default::default()
                         },
                     },
                 );
@@ -3227,17 +3481,17 @@ mod calculate_modify_liquidity_unit_tests {
                             initialized: true,
                             liquidity_net: 1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: u128::MAX - to_x64(100),
                             fee_growth_outside_b: u128::MAX - to_x64(100),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
                             liquidity_net: -1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: u128::MAX - to_x64(100),
                             fee_growth_outside_b: u128::MAX - to_x64(100),
+                            ..Default::default()
                         },
                     },
                 );
@@ -3281,22 +3535,23 @@ mod calculate_modify_liquidity_unit_tests {
                             fee_owed_a: 20000,
                             fee_growth_checkpoint_b: to_x64(20),
                             fee_owed_b: 20000,
+                            ..Default::default()
                         },
                         tick_lower_update: TickUpdate {
                             initialized: true,
                             liquidity_net: 1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: u128::MAX - to_x64(100),
                             fee_growth_outside_b: u128::MAX - to_x64(100),
+                            ..Default::default()
                         },
                         tick_upper_update: TickUpdate {
                             initialized: true,
                             liquidity_net: -1000,
                             liquidity_gross: 1000,
-                            liquidity_borrowed: 0,
                             fee_growth_outside_a: u128::MAX - to_x64(80),
                             fee_growth_outside_b: u128::MAX - to_x64(80),
+                            ..Default::default()
                         },
                     },
                 );
@@ -3307,3 +3562,103 @@ mod calculate_modify_liquidity_unit_tests {
         }
     }
 }
+
+#[cfg(test)]
+mod borrowed_liquidity_tests {
+    use {super::*, crate::util::*};
+
+    #[test]
+    fn withdrawable_liquidity_is_capped_by_idle_liquidity() {
+        assert_eq!(_calculate_withdrawable_liquidity(1000, 600, 1000), 600);
+        assert_eq!(_calculate_withdrawable_liquidity(1000, 1400, 900), 900);
+        // Without borrowed liquidity or orders, all of the position's liquidity is withdrawable
+        assert_eq!(_calculate_withdrawable_liquidity(1000, 1000, 1000), 1000);
+    }
+
+    #[test]
+    fn withdraw_liquidity_keeps_borrowed_liquidity_at_ticks() {
+        // Position at the only liquidity of its ticks, of which trade positions borrowed 400 at
+        // the lower tick (borrowing adds to the tick's liquidity gross)
+        let mut test = LiquidityTestFixture::new(LiquidityTestFixtureInfo {
+            curr_index_loc: CurrIndex::Below,
+            globalpool_liquidity: 0,
+            position_liquidity: 1000,
+            tick_lower_liquidity_gross: 1000,
+            tick_upper_liquidity_gross: 1000,
+            fee_growth_global_a: 0,
+            fee_growth_global_b: 0,
+        });
+        test.tick_lower.liquidity_gross = 1400;
+        assert_eq!(test.tick_lower.liquidity_idle(400, 0), 600);
+
+        let update = _calculate_modify_liquidity(
+            &test.globalpool,
+            &test.position,
+            &test.tick_lower,
+            &test.tick_upper,
+            test.position.tick_lower_index,
+            test.position.tick_upper_index,
+            -600,
+            100,
+        )
+        .unwrap();
+
+        assert_eq!(update.position_update.liquidity, 400);
+        assert_eq!(update.tick_lower_update.liquidity_gross, 800);
+        assert_eq!(update.tick_upper_update.liquidity_gross, 400);
+    }
+
+    #[test]
+    fn queued_liquidity_is_reserved_unless_redeeming() {
+        let globalpool = Globalpool {
+            tick_spacing: 64,
+            ..Default::default()
+        };
+        let tick = Tick {
+            initialized: true,
+            liquidity_gross: 1400,
+            ..Default::default()
+        };
+        let tick_interest = TickInterest {
+            liquidity_borrowed: 400,
+            liquidity_queued: 300,
+            ..Default::default()
+        };
+
+        // Limit order array of the tick array not created yet
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = vec![];
+        let limit_order_array = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &crate::ID,
+            false,
+            0,
+        );
+
+        let liquidity_idle = |is_redeeming_queued| {
+            calculate_tick_liquidity_idle(
+                &globalpool,
+                &tick,
+                0,
+                &tick_interest,
+                &limit_order_array,
+                is_redeeming_queued,
+            )
+            .unwrap()
+        };
+        assert_eq!(liquidity_idle(false), 300);
+        assert_eq!(liquidity_idle(true), 600);
+    }
+
+    #[test]
+    fn partial_token_min() {
+        assert_eq!(calculate_partial_token_min(1000, 600, 1000).unwrap(), 600);
+        assert_eq!(calculate_partial_token_min(1000, 1, 3).unwrap(), 333);
+        assert!(calculate_partial_token_min(1000, 0, 0).is_err());
+    }
+}
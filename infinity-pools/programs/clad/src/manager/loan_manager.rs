@@ -1,6 +1,7 @@
 use {
     super::{
-        globalpool_manager::next_globalpool_liquidity,
+        globalpool_manager::{next_globalpool_liquidity, next_globalpool_reward_infos},
+        liquidity_manager::{calculate_liquidity_token_deltas, calculate_tick_liquidity_idle},
         tick_manager::next_tick_modify_liquidity_update_from_loan,
     },
     crate::{errors::ErrorCode, math::*, state::*},
@@ -11,9 +12,47 @@ use {
 pub struct ModifyLoanUpdate {
     pub globalpool_liquidity: u128,
     pub loan_interest_annual_bps: u16, // 2^16 = 65,536 bps = 655.36% annual, which should be enough
+    pub liquidity_borrowed_delta: i128, // At each of the ticks, positive when borrowing
     pub tick_lower_update: TickUpdate,
     pub tick_upper_update: TickUpdate,
     pub position_update: TradePositionUpdate,
+    pub reward_infos: [GlobalpoolRewardInfo; NUM_REWARDS],
+}
+
+//
+// Liquidity that trade positions can borrow at a tick, ie. idle and not reserved for limit orders
+// or queued withdrawals (see Tick::liquidity_idle). The limit orders of the tick array are at
+// `limit_order_array`, which doesn't exist until the first order.
+//
+pub fn calculate_tick_liquidity_borrowable<'info>(
+    globalpool: &Globalpool,
+    tick_array: &AccountLoader<'info, TickArray>,
+    tick_index: i32,
+    tick_interest: &TickInterest,
+    limit_order_array: &AccountInfo<'info>,
+) -> Result<u128> {
+    calculate_tick_liquidity_idle(
+        globalpool,
+        tick_array.load()?.get_tick(tick_index, globalpool.tick_spacing)?,
+        tick_index,
+        tick_interest,
+        limit_order_array,
+        false,
+    )
+}
+
+//
+// Verify that enough liquidity is borrowable at both the lower & upper tick to borrow `liquidity`.
+//
+pub fn verify_liquidity_borrowable(
+    liquidity: u128,
+    tick_lower_liquidity_borrowable: u128,
+    tick_upper_liquidity_borrowable: u128,
+) -> Result<()> {
+    if liquidity > tick_lower_liquidity_borrowable || liquidity > tick_upper_liquidity_borrowable {
+        return Err(ErrorCode::InsufficientLiquidityToBorrow.into());
+    }
+    Ok(())
 }
 
 // Calculates state after modifying liquidity by the `borrowed_amount` for the given positon.
@@ -27,6 +66,7 @@ pub fn calculate_modify_loan<'info>(
     liquidity_delta: i128,
     borrowed_amount: i64,
     is_opening: bool,
+    timestamp: u64,
 ) -> Result<ModifyLoanUpdate> {
     // Disallow only updating position fee growth when position has zero liquidity
     if borrowed_amount == 0 {
@@ -49,24 +89,7 @@ pub fn calculate_modify_loan<'info>(
         liquidity_delta,
     )?;
 
-    //
-    // Verify that enough liquidity exists in Ticks, if liquidity is to-be-borrowed (ie. positive)
-    // Note: Must check both the lower & upper tick's `liquidity_gross`.
-    //
-
-    // msg!("liquidity_delta: {}", liquidity_delta);
-    // let lower = tick_lower.liquidity_gross;
-    // let upper = tick_upper.liquidity_gross;
-    // msg!("tick_lower.liquidity_gross: {}", lower);
-    // msg!("tick_upper.liquidity_gross: {}", upper);
-    if liquidity_delta > 0 {
-        let liquidity_delta_u128 = liquidity_delta.abs() as u128;
-        if liquidity_delta_u128 > tick_lower.liquidity_gross
-            && liquidity_delta_u128 > tick_upper.liquidity_gross
-        {
-            return Err(ErrorCode::InsufficientLiquidityToBorrow.into());
-        }
-    }
+    let next_reward_infos = next_globalpool_reward_infos(globalpool, timestamp)?;
 
     //
     // Calculate Tick Updates (shouldn't have any fee updates since the ticks are out of range)
@@ -77,6 +100,7 @@ pub fn calculate_modify_loan<'info>(
         globalpool.tick_current_index,
         globalpool.fee_growth_global_a,
         globalpool.fee_growth_global_b,
+        &next_reward_infos,
         liquidity_delta,
         false,
     )?;
@@ -87,6 +111,7 @@ pub fn calculate_modify_loan<'info>(
         globalpool.tick_current_index,
         globalpool.fee_growth_global_a,
         globalpool.fee_growth_global_b,
+        &next_reward_infos,
         liquidity_delta,
         true,
     )?;
@@ -94,6 +119,7 @@ pub fn calculate_modify_loan<'info>(
     let loan_interest_annual_bps: u16;
     if is_opening {
         loan_interest_annual_bps = _calculate_loan_interest_rate_annual(
+            globalpool,
             tick_lower_update.liquidity_gross,
             tick_upper_update.liquidity_gross,
             liquidity_delta as u128,
@@ -129,63 +155,185 @@ pub fn calculate_modify_loan<'info>(
     Ok(ModifyLoanUpdate {
         globalpool_liquidity: next_global_liquidity,
         loan_interest_annual_bps,
+        liquidity_borrowed_delta: liquidity_delta,
         position_update,
         tick_lower_update,
         tick_upper_update,
+        reward_infos: next_reward_infos,
     })
 }
 
 //
-// Simple linear interest rate based on utilization of tick liquidity gross.
-//
-// TODO: More complex utilization-based interest rate model, like Aave's.
+// Annual interest rate from the Globalpool's kinked interest rate model, at the utilization of the
+// more utilized of the lower & upper tick's liquidity gross.
 //
 pub fn _calculate_loan_interest_rate_annual(
+    globalpool: &Globalpool,
     tick_lower_liquidity_gross: u128,
     tick_upper_liquidity_gross: u128,
     liquidity_borrowed: u128,
     round_up: bool,
 ) -> Result<u16> {
-    let min_bps = 100_u128; // min annual: 1% => 100bps
+    let tick_lower_utilization =
+        get_utilization_bps(liquidity_borrowed, tick_lower_liquidity_gross, round_up)?;
+    let tick_upper_utilization =
+        get_utilization_bps(liquidity_borrowed, tick_upper_liquidity_gross, round_up)?;
+
+    let utilization = std::cmp::max(tick_lower_utilization, tick_upper_utilization);
+
+    Ok(get_interest_rate_annual_bps(
+        utilization,
+        globalpool.interest_base_rate_bps,
+        globalpool.interest_slope1_bps,
+        globalpool.interest_optimal_utilization_bps,
+        globalpool.interest_slope2_bps,
+    ))
+}
 
-    let multiplier = U256Muldiv::new(0, 100_u128); // 1% => 100 bps
-    let tick_lower_denom = U256Muldiv::new(0, tick_lower_liquidity_gross);
-    let tick_upper_denom = U256Muldiv::new(0, tick_upper_liquidity_gross);
+//
+// Annual interest rate for extending a trade position's loan, re-priced at the current utilization
+// of its lower & upper ticks (which already account for the position's borrowed liquidity).
+//
+pub fn calculate_position_interest_rate_annual<'info>(
+    globalpool: &Globalpool,
+    position: &TradePosition,
+    tick_array_lower: &AccountLoader<'info, TickArray>,
+    tick_array_upper: &AccountLoader<'info, TickArray>,
+) -> Result<u16> {
+    let tick_array_lower = tick_array_lower.load()?;
+    let tick_lower =
+        tick_array_lower.get_tick(position.tick_lower_index, globalpool.tick_spacing)?;
 
-    let (quotient_l, remainder_l) = U256Muldiv::new(0, liquidity_borrowed)
-        .mul(multiplier)
-        .div(tick_lower_denom, round_up);
+    let tick_array_upper = tick_array_upper.load()?;
+    let tick_upper =
+        tick_array_upper.get_tick(position.tick_upper_index, globalpool.tick_spacing)?;
 
-    let (quotient_u, remainder_u) = U256Muldiv::new(0, liquidity_borrowed)
-        .mul(multiplier)
-        .div(tick_upper_denom, round_up);
+    _calculate_loan_interest_rate_annual(
+        globalpool,
+        tick_lower.liquidity_gross,
+        tick_upper.liquidity_gross,
+        position.liquidity_borrowed,
+        true,
+    )
+}
 
-    let tick_lower_utilization = if round_up && !remainder_l.is_zero() {
-        quotient_l.add(U256Muldiv::new(0, 1)).try_into_u128()?
-    } else {
-        quotient_l.try_into_u128()?
-    };
+//
+// Splits the interest paid by a trade position between its lower & upper ticks, pro rata to the
+// liquidity that liquidity positions have at each. The upper tick's share absorbs rounding.
+//
+pub fn calculate_tick_interest_split(
+    interest_amount: u64,
+    tick_lower_liquidity_lent: u128,
+    tick_upper_liquidity_lent: u128,
+) -> Result<(u64, u64)> {
+    let liquidity_lent = U256::from(tick_lower_liquidity_lent) + U256::from(tick_upper_liquidity_lent);
+    if liquidity_lent.is_zero() {
+        return Ok((0, 0));
+    }
 
-    let tick_upper_utilization = if round_up && !remainder_u.is_zero() {
-        quotient_u.add(U256Muldiv::new(0, 1)).try_into_u128()?
-    } else {
-        quotient_u.try_into_u128()?
-    };
+    let interest_lower = (U256::from(interest_amount) * U256::from(tick_lower_liquidity_lent)
+        / liquidity_lent)
+        .try_into_u64()?;
 
-    // Max of min_bps, tick_lower_utilization, tick_upper_utilization
-    let utilization = std::cmp::max(
-        min_bps,
-        std::cmp::max(tick_lower_utilization, tick_upper_utilization),
-    );
+    Ok((interest_lower, interest_amount - interest_lower))
+}
 
-    // Downcast u128 to u16 (2^16 = 65,536 bps = 655.36% annual, which should be enough for interest rate)
-    let utilization = if utilization > std::u16::MAX as u128 {
-        std::u16::MAX
-    } else {
-        utilization as u16
-    };
+//
+// Liquidity that liquidity positions lend at a tick, ie. of the tick's lent liquidity (see
+// Tick::liquidity_lent) all but the limit orders', which are never lent. The limit orders of the
+// tick array are at `limit_order_array`, which doesn't exist until the first order.
+//
+pub fn calculate_tick_liquidity_lent_by_positions<'info>(
+    globalpool: &Globalpool,
+    tick_array: &AccountLoader<'info, TickArray>,
+    tick_index: i32,
+    tick_interest: &TickInterest,
+    limit_order_array: &AccountInfo<'info>,
+) -> Result<u128> {
+    let liquidity_orders = LimitOrderArray::load_liquidity_at_tick(
+        limit_order_array,
+        tick_index,
+        globalpool.tick_spacing,
+    )?;
 
-    Ok(utilization)
+    Ok(tick_array
+        .load()?
+        .get_tick(tick_index, globalpool.tick_spacing)?
+        .liquidity_lent(tick_interest.liquidity_borrowed)
+        .saturating_sub(liquidity_orders))
+}
+
+//
+// Pays the interest of a trade position for the time until `release_end` to the liquidity
+// positions bounded by its lower & upper ticks, released to them by the second (see TickInterest).
+// Without lent liquidity at either tick, the interest is paid to in-range liquidity instead.
+//
+pub fn pay_interest_to_ticks<'info>(
+    globalpool: &mut Globalpool,
+    position: &TradePosition,
+    tick_array_lower: &AccountLoader<'info, TickArray>,
+    tick_array_upper: &AccountLoader<'info, TickArray>,
+    tick_interest_lower: &mut TickInterest,
+    tick_interest_upper: &mut TickInterest,
+    limit_order_array_lower: &AccountInfo<'info>,
+    limit_order_array_upper: &AccountInfo<'info>,
+    interest_amount: u64,
+    is_token_a: bool,
+    release_end: u64,
+    timestamp: u64,
+) -> Result<()> {
+    if interest_amount == 0 {
+        return Ok(());
+    }
+
+    let tick_lower_liquidity_lent = calculate_tick_liquidity_lent_by_positions(
+        globalpool,
+        tick_array_lower,
+        position.tick_lower_index,
+        tick_interest_lower,
+        limit_order_array_lower,
+    )?;
+    let tick_upper_liquidity_lent = calculate_tick_liquidity_lent_by_positions(
+        globalpool,
+        tick_array_upper,
+        position.tick_upper_index,
+        tick_interest_upper,
+        limit_order_array_upper,
+    )?;
+
+    tick_interest_lower.checkpoint(tick_lower_liquidity_lent, timestamp)?;
+    tick_interest_upper.checkpoint(tick_upper_liquidity_lent, timestamp)?;
+
+    if tick_lower_liquidity_lent == 0 && tick_upper_liquidity_lent == 0 {
+        globalpool.update_fee_growth_from_interest(interest_amount, is_token_a);
+        return Ok(());
+    }
+
+    let (interest_lower, interest_upper) = calculate_tick_interest_split(
+        interest_amount,
+        tick_lower_liquidity_lent,
+        tick_upper_liquidity_lent,
+    )?;
+
+    tick_interest_lower.add_interest(interest_lower, is_token_a, release_end, timestamp)?;
+    tick_interest_upper.add_interest(interest_upper, is_token_a, release_end, timestamp)?;
+
+    Ok(())
+}
+
+//
+// Interest for `duration` seconds at `interest_rate_annual_bps` of `amount` (rounded down).
+//
+pub fn calculate_prorated_interest_amount(
+    amount: u64,
+    interest_rate_annual_bps: u16,
+    duration: u64,
+) -> Result<u64> {
+    let interest_amount =
+        U256::from(amount) * U256::from(interest_rate_annual_bps) * U256::from(duration)
+            / (U256::from(SECONDS_PER_YEAR) * U256::from(Clad::BPS_POWER));
+
+    Ok(interest_amount.try_into_u64()?)
 }
 
 pub fn calculate_loan_liquidity_token_delta(
@@ -262,3 +410,383 @@ pub fn calculate_collateral(
 
     Ok(collateral_amount)
 }
+
+//
+// Collateral of a trade position in excess of what `calculate_collateral` requires for the
+// liquidity owed, ie. the amount that can be withdrawn while keeping the position fully
+// collateralized against the worst case (debt entirely in the collateral token).
+//
+pub fn calculate_excess_collateral(
+    liquidity_owed: u128,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    trade_token_amount: u64,
+    collateral_amount: u64,
+    is_borrow_token_a: bool,
+) -> Result<u64> {
+    let required_collateral = calculate_collateral(
+        liquidity_owed,
+        tick_lower_index,
+        tick_upper_index,
+        trade_token_amount,
+        is_borrow_token_a,
+    )?;
+
+    msg!("required_collateral: {}", required_collateral);
+
+    Ok(collateral_amount.saturating_sub(required_collateral))
+}
+
+//
+// Converts an amount of one pool token to the other at the given Q64.64 sqrt price (rounded down).
+// Price is in token B per token A, so A -> B multiplies by sqrt_price^2 and B -> A divides by it.
+//
+pub fn convert_token_amount_at_sqrt_price(
+    amount: u64,
+    sqrt_price: u128,
+    is_a_to_b: bool,
+) -> Result<u64> {
+    if sqrt_price == 0 {
+        return Err(ErrorCode::DivideByZero.into());
+    }
+
+    let amount = U256::from(amount);
+    let sqrt_price = U256::from(sqrt_price);
+
+    // Apply the sqrt price twice to avoid overflowing 256 bits
+    let converted = if is_a_to_b {
+        let amount_x64 = (amount * sqrt_price) >> Q64_RESOLUTION;
+        (amount_x64 * sqrt_price) >> Q64_RESOLUTION
+    } else {
+        let amount_x64 = (amount << Q64_RESOLUTION) / sqrt_price;
+        (amount_x64 << Q64_RESOLUTION) / sqrt_price
+    };
+
+    Ok(converted.try_into_u64()?)
+}
+
+//
+// Health of a trade position in bps, ie. the value of its trade token & collateral over the value
+// of its debt (borrowed liquidity at the current price), both denominated in the collateral token.
+//
+// Fully collateralized positions stay at or above 100% (10,000 bps) until the current price moves
+// past the far end of the borrowed range, where the debt is entirely in the collateral token.
+//
+pub fn calculate_trade_position_health_bps(
+    liquidity_borrowed: u128,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_current_index: i32,
+    sqrt_current_price: u128,
+    trade_token_amount: u64,
+    collateral_amount: u64,
+    is_borrow_token_a: bool,
+) -> Result<u128> {
+    if liquidity_borrowed == 0 {
+        return Ok(u128::MAX);
+    }
+
+    let (debt_a, debt_b) = calculate_liquidity_token_deltas(
+        tick_lower_index,
+        tick_upper_index,
+        tick_current_index,
+        sqrt_current_price,
+        liquidity_borrowed as i128,
+    )?;
+
+    // Borrowing A means the collateral (and trade token) is B, so the debt is valued in B
+    let debt_value = if is_borrow_token_a {
+        debt_b as u128
+            + convert_token_amount_at_sqrt_price(debt_a, sqrt_current_price, true)? as u128
+    } else {
+        debt_a as u128
+            + convert_token_amount_at_sqrt_price(debt_b, sqrt_current_price, false)? as u128
+    };
+
+    if debt_value == 0 {
+        return Ok(u128::MAX);
+    }
+
+    let asset_value = trade_token_amount as u128 + collateral_amount as u128;
+
+    Ok(asset_value * Clad::BPS_POWER / debt_value)
+}
+
+//
+// Verifies that a trade position stays above its maintenance margin, plus a buffer, after
+// `collateral_withdrawn` of its collateral is withdrawn. The buffer keeps a withdrawal from leaving
+// the position liquidatable by the next small price move.
+//
+pub fn verify_health_after_collateral_withdrawal(
+    position: &TradePosition,
+    liquidity_owed: u128,
+    tick_current_index: i32,
+    sqrt_current_price: u128,
+    collateral_withdrawn: u64,
+    is_borrow_token_a: bool,
+) -> Result<()> {
+    let collateral_amount = position
+        .collateral_amount
+        .checked_sub(collateral_withdrawn)
+        .ok_or(ErrorCode::InsufficientCollateral)?;
+
+    let health_bps = calculate_trade_position_health_bps(
+        liquidity_owed,
+        position.tick_lower_index,
+        position.tick_upper_index,
+        tick_current_index,
+        sqrt_current_price,
+        position.trade_token_amount,
+        collateral_amount,
+        is_borrow_token_a,
+    )?;
+
+    msg!("health_bps after withdrawal: {}", health_bps);
+
+    let min_health_bps = position.maintenance_margin_bps as u128
+        + TradePosition::WITHDRAWAL_HEALTH_BUFFER_BPS as u128;
+    if health_bps < min_health_bps {
+        return Err(ErrorCode::InsufficientCollateral.into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod loan_manager_tests {
+    use {super::*, crate::state::Globalpool};
+
+    #[test]
+    fn test_verify_liquidity_borrowable() {
+        assert!(verify_liquidity_borrowable(1_000, 1_000, 5_000).is_ok());
+        // Both ticks need the liquidity idle
+        assert!(verify_liquidity_borrowable(1_001, 1_000, 5_000).is_err());
+        assert!(verify_liquidity_borrowable(1_001, 5_000, 1_000).is_err());
+    }
+
+    #[test]
+    fn test_calculate_tick_interest_split() {
+        assert_eq!(
+            calculate_tick_interest_split(1_000, 3_000, 1_000).unwrap(),
+            (750, 250)
+        );
+        // Rounding goes to the upper tick
+        assert_eq!(
+            calculate_tick_interest_split(1_001, 1_000, 1_000).unwrap(),
+            (500, 501)
+        );
+        assert_eq!(
+            calculate_tick_interest_split(1_000, 0, 5_000).unwrap(),
+            (0, 1_000)
+        );
+        assert_eq!(calculate_tick_interest_split(1_000, 0, 0).unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn test_convert_token_amount_at_sqrt_price() {
+        // Price of 1 (sqrt price of 1)
+        assert_eq!(
+            convert_token_amount_at_sqrt_price(1_000, TO_Q64, true).unwrap(),
+            1_000
+        );
+        // Price of 4 (sqrt price of 2)
+        assert_eq!(
+            convert_token_amount_at_sqrt_price(1_000, 2 * TO_Q64, true).unwrap(),
+            4_000
+        );
+        assert_eq!(
+            convert_token_amount_at_sqrt_price(4_000, 2 * TO_Q64, false).unwrap(),
+            1_000
+        );
+    }
+
+    #[test]
+    fn test_trade_position_health() {
+        let liquidity_borrowed = 1_000_000_000;
+        let tick_lower_index = 1000;
+        let tick_upper_index = 2000;
+
+        // Borrow A while the price is below the borrowed range
+        let tick_open_index = -1000;
+        let sqrt_open_price = sqrt_price_from_tick_index(tick_open_index);
+        let (borrowed_amount, is_borrow_token_a) = calculate_loan_liquidity_token_delta(
+            tick_open_index,
+            tick_lower_index,
+            tick_upper_index,
+            liquidity_borrowed as i128,
+        )
+        .unwrap();
+        assert!(is_borrow_token_a);
+
+        let trade_token_amount =
+            convert_token_amount_at_sqrt_price(borrowed_amount, sqrt_open_price, true).unwrap();
+        let collateral_amount = calculate_collateral(
+            liquidity_borrowed,
+            tick_lower_index,
+            tick_upper_index,
+            trade_token_amount,
+            is_borrow_token_a,
+        )
+        .unwrap();
+
+        // Healthy at the opening price
+        let health_at_open = calculate_trade_position_health_bps(
+            liquidity_borrowed,
+            tick_lower_index,
+            tick_upper_index,
+            tick_open_index,
+            sqrt_open_price,
+            trade_token_amount,
+            collateral_amount,
+            is_borrow_token_a,
+        )
+        .unwrap();
+        assert!(health_at_open > Globalpool::DEFAULT_MAINTENANCE_MARGIN_BPS as u128);
+
+        // Debt is entirely in the collateral token once the price is above the borrowed range
+        let tick_current_index = 3000;
+        let health_above_range = calculate_trade_position_health_bps(
+            liquidity_borrowed,
+            tick_lower_index,
+            tick_upper_index,
+            tick_current_index,
+            sqrt_price_from_tick_index(tick_current_index),
+            trade_token_amount,
+            collateral_amount,
+            is_borrow_token_a,
+        )
+        .unwrap();
+        assert!(health_above_range < health_at_open);
+        assert!(health_above_range < Globalpool::DEFAULT_MAINTENANCE_MARGIN_BPS as u128);
+
+        // Losing the collateral makes the position unhealthy even at the opening price
+        let health_without_collateral = calculate_trade_position_health_bps(
+            liquidity_borrowed,
+            tick_lower_index,
+            tick_upper_index,
+            tick_open_index,
+            sqrt_open_price,
+            trade_token_amount,
+            0,
+            is_borrow_token_a,
+        )
+        .unwrap();
+        assert!(health_without_collateral <= Clad::BPS_POWER);
+    }
+
+    #[test]
+    fn test_health_after_collateral_withdrawal() {
+        let liquidity_borrowed = 1_000_000_000;
+        let tick_lower_index = 1000;
+        let tick_upper_index = 2000;
+        let tick_open_index = -1000;
+        let sqrt_open_price = sqrt_price_from_tick_index(tick_open_index);
+
+        let (borrowed_amount, _) = calculate_loan_liquidity_token_delta(
+            tick_open_index,
+            tick_lower_index,
+            tick_upper_index,
+            liquidity_borrowed as i128,
+        )
+        .unwrap();
+        let trade_token_amount =
+            convert_token_amount_at_sqrt_price(borrowed_amount, sqrt_open_price, true).unwrap();
+
+        let position = TradePosition {
+            tick_lower_index,
+            tick_upper_index,
+            liquidity_borrowed,
+            trade_token_amount,
+            // Twice the trade token as collateral
+            collateral_amount: trade_token_amount * 2,
+            maintenance_margin_bps: Globalpool::DEFAULT_MAINTENANCE_MARGIN_BPS,
+            ..Default::default()
+        };
+
+        let verify = |collateral_withdrawn| {
+            verify_health_after_collateral_withdrawal(
+                &position,
+                liquidity_borrowed,
+                tick_open_index,
+                sqrt_open_price,
+                collateral_withdrawn,
+                true,
+            )
+        };
+
+        // ~300% health with all collateral, ~200% after withdrawing half
+        assert!(verify(0).is_ok());
+        assert!(verify(trade_token_amount).is_ok());
+
+        // Just above the maintenance margin, but within the buffer
+        let collateral_at_margin = trade_token_amount
+            * (Globalpool::DEFAULT_MAINTENANCE_MARGIN_BPS as u64 + 100)
+            / Clad::BPS_POWER as u64
+            - trade_token_amount;
+        assert!(verify(position.collateral_amount - collateral_at_margin).is_err());
+
+        // More than the position's collateral
+        assert!(verify(position.collateral_amount + 1).is_err());
+    }
+
+    #[test]
+    fn test_trade_position_health_no_debt() {
+        let health =
+            calculate_trade_position_health_bps(0, 1000, 2000, 0, TO_Q64, 100, 100, true).unwrap();
+        assert_eq!(health, u128::MAX);
+    }
+
+    #[test]
+    fn test_prorated_interest_amount() {
+        // 10% annual on 1,000,000 for a full year
+        assert_eq!(
+            calculate_prorated_interest_amount(1_000_000, 1_000, SECONDS_PER_YEAR).unwrap(),
+            100_000
+        );
+
+        // 10% annual on 1,000,000 for 10 days
+        assert_eq!(
+            calculate_prorated_interest_amount(1_000_000, 1_000, 864_000).unwrap(),
+            2_739
+        );
+
+        assert_eq!(calculate_prorated_interest_amount(1_000_000, 1_000, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_excess_collateral() {
+        let liquidity_borrowed = 1_000_000_000;
+        let trade_token_amount = 10_000;
+        let required_collateral =
+            calculate_collateral(liquidity_borrowed, 1000, 2000, trade_token_amount, true)
+                .unwrap();
+
+        let excess = |collateral_amount| {
+            calculate_excess_collateral(
+                liquidity_borrowed,
+                1000,
+                2000,
+                trade_token_amount,
+                collateral_amount,
+                true,
+            )
+            .unwrap()
+        };
+
+        assert_eq!(excess(required_collateral), 0);
+        assert_eq!(excess(required_collateral + 500), 500);
+        assert_eq!(excess(required_collateral - 1), 0);
+
+        // Accrued interest raises the requirement
+        let excess_with_interest = calculate_excess_collateral(
+            liquidity_borrowed + liquidity_borrowed / 10,
+            1000,
+            2000,
+            trade_token_amount,
+            required_collateral + 500,
+            true,
+        )
+        .unwrap();
+        assert!(excess_with_interest < 500);
+    }
+}
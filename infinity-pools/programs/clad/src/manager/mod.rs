@@ -1,5 +1,7 @@
+pub mod limit_order_manager;
 pub mod liquidity_manager;
 pub mod loan_manager;
+pub mod oracle_manager;
 pub mod position_manager;
 pub mod swap_manager;
 pub mod tick_manager;
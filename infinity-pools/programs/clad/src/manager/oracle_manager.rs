@@ -0,0 +1,221 @@
+use {
+    crate::{errors::ErrorCode, math::*, state::*},
+    anchor_lang::prelude::*,
+    solana_program::clock::UnixTimestamp,
+};
+
+//
+// Converts an oracle price of token A in token B (`price * 10^expo`, in whole tokens) to a Q64.64
+// price in atomic units, ie. the same unit as `sqrt_price^2` of the pool.
+//
+pub fn calculate_oracle_price_x64(
+    price: u64,
+    expo: i32,
+    decimals_a: u8,
+    decimals_b: u8,
+) -> Result<u128> {
+    let exponent = expo + decimals_b as i32 - decimals_a as i32;
+    let price_x64 = U256::from(price) << Q64_RESOLUTION;
+    let scale = U256::from(10)
+        .checked_pow(U256::from(exponent.unsigned_abs()))
+        .ok_or(ErrorCode::MultiplicationOverflow)?;
+
+    let price_x64 = if exponent >= 0 {
+        price_x64
+            .checked_mul(scale)
+            .ok_or(ErrorCode::MultiplicationOverflow)?
+    } else {
+        price_x64 / scale
+    };
+
+    Ok(price_x64.try_into_u128()?)
+}
+
+//
+// Verifies that the pool price (from `sqrt_price`) is within `band_bps` of the oracle price.
+//
+pub fn verify_price_within_band(
+    sqrt_price: u128,
+    oracle_price_x64: u128,
+    band_bps: u16,
+) -> Result<()> {
+    let sqrt_price = U256::from(sqrt_price);
+    let pool_price_x64 = (sqrt_price * sqrt_price) >> Q64_RESOLUTION;
+    let oracle_price_x64 = U256::from(oracle_price_x64);
+
+    let deviation = if pool_price_x64 > oracle_price_x64 {
+        pool_price_x64 - oracle_price_x64
+    } else {
+        oracle_price_x64 - pool_price_x64
+    };
+
+    if deviation * U256::from(Clad::BPS_POWER) > oracle_price_x64 * U256::from(band_bps) {
+        return Err(ErrorCode::OraclePriceOutOfBand.into());
+    }
+
+    Ok(())
+}
+
+//
+// Reads the price of token A in token B from the pool's Pyth feeds and verifies that the pool
+// price hasn't moved beyond the pool's oracle price band. Pools without registered feeds have no
+// band to check, and the feed accounts passed for them are ignored.
+//
+pub fn verify_oracle_price_band(
+    globalpool: &Globalpool,
+    token_price_feed_a: &AccountInfo,
+    token_price_feed_b: &AccountInfo,
+    decimals_a: u8,
+    decimals_b: u8,
+    current_timestamp: UnixTimestamp,
+) -> Result<()> {
+    if !globalpool.has_price_feeds() {
+        return Ok(());
+    }
+
+    let token_price_feed_a = PriceFeed::load(token_price_feed_a)?;
+    let token_price_feed_b = PriceFeed::load(token_price_feed_b)?;
+
+    let oracle_price = token_price_feed_a.read_price_in_quote(
+        &token_price_feed_b,
+        current_timestamp,
+        globalpool.oracle_stale_seconds,
+    )?;
+
+    let oracle_price_x64 = calculate_oracle_price_x64(
+        oracle_price.price_with_expo,
+        oracle_price.exponent,
+        decimals_a,
+        decimals_b,
+    )?;
+
+    verify_price_within_band(
+        globalpool.sqrt_price,
+        oracle_price_x64,
+        globalpool.oracle_price_band_bps,
+    )
+}
+
+//
+// Writes an observation of the pool tick (before a swap moves it) to the Globalpool's oracle.
+// The oracle is optional, so nothing is written until it is initialized. The caller must verify the
+// oracle address.
+//
+pub fn write_oracle_observation(
+    oracle: &AccountInfo,
+    timestamp: u64,
+    tick_current_index: i32,
+) -> Result<()> {
+    if oracle.owner != &crate::ID || oracle.data_is_empty() {
+        return Ok(());
+    }
+
+    let oracle = AccountLoader::<Oracle>::try_from(oracle)?;
+    let mut oracle = oracle.load_mut()?;
+    oracle.write(timestamp, tick_current_index)
+}
+
+//
+// Time-weighted average tick of the Globalpool over the last `window_seconds`, shortened to the
+// oracle's history if it is younger. None without an initialized oracle or any history yet.
+// The caller must verify the oracle address.
+//
+pub fn observe_twap_tick(
+    oracle: &AccountInfo,
+    timestamp: u64,
+    window_seconds: u64,
+    tick_current_index: i32,
+) -> Result<Option<i32>> {
+    if oracle.owner != &crate::ID || oracle.data_is_empty() {
+        return Ok(None);
+    }
+
+    let oracle = AccountLoader::<Oracle>::try_from(oracle)?;
+    let oracle = oracle.load()?;
+    calculate_twap_tick(&oracle, timestamp, window_seconds, tick_current_index)
+}
+
+pub fn calculate_twap_tick(
+    oracle: &Oracle,
+    timestamp: u64,
+    window_seconds: u64,
+    tick_current_index: i32,
+) -> Result<Option<i32>> {
+    let window_seconds = window_seconds.min(timestamp.saturating_sub(oracle.oldest_timestamp()));
+    if window_seconds == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(oracle.observe(
+        timestamp,
+        window_seconds,
+        tick_current_index,
+    )?))
+}
+
+#[cfg(test)]
+mod oracle_manager_tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_oracle_price_x64_same_decimals() {
+        // 1.5 (150 * 10^-2) with equal decimals
+        let price_x64 = calculate_oracle_price_x64(150, -2, 6, 6).unwrap();
+        assert_eq!(price_x64, 3 << 63);
+    }
+
+    #[test]
+    fn test_calculate_oracle_price_x64_decimals_adjusted() {
+        // 20 USDC (6 decimals) per HNT (8 decimals) is 0.2 atomic USDC per atomic HNT
+        let price_x64 = calculate_oracle_price_x64(2_000_000_000, -8, 8, 6).unwrap();
+        assert_eq!(price_x64, (2u128 << 64) / 10);
+
+        // 20 atomic B per atomic A when B has more decimals
+        let price_x64 = calculate_oracle_price_x64(2, 0, 6, 7).unwrap();
+        assert_eq!(price_x64, 20 << 64);
+    }
+
+    #[test]
+    fn test_verify_price_within_band() {
+        // Pool price of 1.0
+        let sqrt_price = 1u128 << 64;
+
+        // 4% off oracle with a 5% band
+        assert!(verify_price_within_band(sqrt_price, (104u128 << 64) / 100, 500).is_ok());
+        assert!(verify_price_within_band(sqrt_price, (96u128 << 64) / 100, 500).is_ok());
+    }
+
+    #[test]
+    fn test_verify_price_out_of_band() {
+        let sqrt_price = 1u128 << 64;
+
+        let result = verify_price_within_band(sqrt_price, (110u128 << 64) / 100, 500);
+        assert_eq!(result.unwrap_err(), ErrorCode::OraclePriceOutOfBand.into());
+
+        let result = verify_price_within_band(sqrt_price, (90u128 << 64) / 100, 500);
+        assert_eq!(result.unwrap_err(), ErrorCode::OraclePriceOutOfBand.into());
+    }
+
+    #[test]
+    fn test_calculate_twap_tick() {
+        let mut oracle = Box::new(Oracle::default());
+        oracle.initialize(Pubkey::new_unique(), 1_000);
+
+        // No history yet
+        assert_eq!(calculate_twap_tick(&oracle, 1_000, 600, 50).unwrap(), None);
+
+        oracle.write(1_100, 10).unwrap(); // tick 10 for [1000, 1100)
+
+        // Window shortened to the oracle's history
+        assert_eq!(
+            calculate_twap_tick(&oracle, 1_200, 600, 30).unwrap(),
+            Some(20)
+        );
+
+        // Full window once the history is long enough
+        assert_eq!(
+            calculate_twap_tick(&oracle, 1_200, 100, 30).unwrap(),
+            Some(30)
+        );
+    }
+}
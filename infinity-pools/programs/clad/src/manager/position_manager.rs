@@ -1,7 +1,9 @@
 use crate::{
     errors::ErrorCode,
     math::{add_liquidity_delta, checked_mul_shift_right},
-    state::{LiquidityPosition, LiquidityPositionUpdate},
+    state::{
+        LiquidityPosition, LiquidityPositionInterestUpdate, LiquidityPositionUpdate, NUM_REWARDS,
+    },
 };
 
 pub fn next_position_modify_liquidity_update(
@@ -9,6 +11,7 @@ pub fn next_position_modify_liquidity_update(
     liquidity_delta: i128,
     fee_growth_inside_a: u128,
     fee_growth_inside_b: u128,
+    reward_growths_inside: &[u128; NUM_REWARDS],
 ) -> Result<LiquidityPositionUpdate, ErrorCode> {
     let mut update = LiquidityPositionUpdate::default();
 
@@ -28,17 +31,57 @@ pub fn next_position_modify_liquidity_update(
     update.fee_owed_a = position.fee_owed_a.wrapping_add(fee_delta_a);
     update.fee_owed_b = position.fee_owed_b.wrapping_add(fee_delta_b);
 
+    // Calculate reward deltas the same way, losing rewards earned on overflow.
+    update.reward_infos = position.reward_infos;
+    for (i, reward_info) in update.reward_infos.iter_mut().enumerate() {
+        let growth_delta =
+            reward_growths_inside[i].wrapping_sub(reward_info.growth_inside_checkpoint);
+        let amount_owed_delta =
+            checked_mul_shift_right(position.liquidity, growth_delta).unwrap_or(0);
+
+        reward_info.growth_inside_checkpoint = reward_growths_inside[i];
+
+        // Overflows allowed. Must collect rewards owed before overflow.
+        reward_info.amount_owed = reward_info.amount_owed.wrapping_add(amount_owed_delta);
+    }
+
     update.liquidity = add_liquidity_delta(position.liquidity, liquidity_delta)?;
 
     Ok(update)
 }
 
+// Calculates the interest owed to a position from the interest growths of its lower & upper ticks
+// (summed). A position's liquidity counts at both of its ticks, so it earns the growth of both.
+pub fn next_position_interest_update(
+    position: &LiquidityPosition,
+    interest_growth_a: u128,
+    interest_growth_b: u128,
+) -> LiquidityPositionInterestUpdate {
+    // Interest deltas default to zero on overflow, as fee deltas do
+    let growth_delta_a = interest_growth_a.wrapping_sub(position.interest_growth_checkpoint_a);
+    let interest_delta_a =
+        checked_mul_shift_right(position.liquidity, growth_delta_a).unwrap_or(0);
+
+    let growth_delta_b = interest_growth_b.wrapping_sub(position.interest_growth_checkpoint_b);
+    let interest_delta_b =
+        checked_mul_shift_right(position.liquidity, growth_delta_b).unwrap_or(0);
+
+    // Overflows allowed. Must collect interest owed before overflow.
+    LiquidityPositionInterestUpdate {
+        interest_growth_checkpoint_a: interest_growth_a,
+        interest_owed_a: position.interest_owed_a.wrapping_add(interest_delta_a),
+        interest_growth_checkpoint_b: interest_growth_b,
+        interest_owed_b: position.interest_owed_b.wrapping_add(interest_delta_b),
+    }
+}
+
 #[cfg(test)]
 mod position_manager_unit_tests {
     use {
-        super::next_position_modify_liquidity_update,
+        super::{next_position_interest_update, next_position_modify_liquidity_update},
         crate::{
-            math::Q64_RESOLUTION, state::liquidity_position_builder::LiquidityPositionBuilder,
+            math::Q64_RESOLUTION,
+            state::{liquidity_position_builder::LiquidityPositionBuilder, NUM_REWARDS},
         },
     };
 
@@ -56,6 +99,7 @@ mod position_manager_unit_tests {
             1000,
             1000 << Q64_RESOLUTION,
             2000 << Q64_RESOLUTION,
+            &[0; NUM_REWARDS],
         )
         .unwrap();
 
@@ -78,6 +122,7 @@ mod position_manager_unit_tests {
             -5000,
             120 << Q64_RESOLUTION,
             250 << Q64_RESOLUTION,
+            &[0; NUM_REWARDS],
         )
         .unwrap();
 
@@ -92,7 +137,7 @@ mod position_manager_unit_tests {
     #[should_panic(expected = "LiquidityUnderflow")]
     fn liquidity_underflow() {
         let position = LiquidityPositionBuilder::new(-10, 10).build();
-        next_position_modify_liquidity_update(&position, -100, 0, 0).unwrap();
+        next_position_modify_liquidity_update(&position, -100, 0, 0, &[0; NUM_REWARDS]).unwrap();
     }
 
     #[test]
@@ -101,7 +146,8 @@ mod position_manager_unit_tests {
         let position = LiquidityPositionBuilder::new(-10, 10)
             .liquidity(u128::MAX)
             .build();
-        next_position_modify_liquidity_update(&position, i128::MAX, 0, 0).unwrap();
+        next_position_modify_liquidity_update(&position, i128::MAX, 0, 0, &[0; NUM_REWARDS])
+            .unwrap();
     }
 
     #[test]
@@ -116,6 +162,7 @@ mod position_manager_unit_tests {
             i64::MAX as i128,
             u128::MAX,
             u128::MAX,
+            &[0; NUM_REWARDS],
         )
         .unwrap();
         assert_eq!(update.fee_growth_checkpoint_a, u128::MAX);
@@ -123,4 +170,58 @@ mod position_manager_unit_tests {
         assert_eq!(update.fee_owed_a, 10);
         assert_eq!(update.fee_owed_b, 20);
     }
+
+    #[test]
+    fn ok_reward_growth() {
+        let mut position = LiquidityPositionBuilder::new(-10, 10)
+            .liquidity(1000)
+            .build();
+        position.reward_infos[0].growth_inside_checkpoint = 10 << Q64_RESOLUTION;
+        position.reward_infos[0].amount_owed = 50;
+
+        let update = next_position_modify_liquidity_update(
+            &position,
+            0,
+            0,
+            0,
+            &[15 << Q64_RESOLUTION, 2 << Q64_RESOLUTION, 4 << Q64_RESOLUTION],
+        )
+        .unwrap();
+
+        assert_eq!(update.reward_infos[0].growth_inside_checkpoint, 15 << Q64_RESOLUTION);
+        assert_eq!(update.reward_infos[0].amount_owed, 5050);
+        assert_eq!(update.reward_infos[1].growth_inside_checkpoint, 2 << Q64_RESOLUTION);
+        assert_eq!(update.reward_infos[1].amount_owed, 2000);
+        assert_eq!(update.reward_infos[2].amount_owed, 4000);
+    }
+
+    #[test]
+    fn ok_interest_growth() {
+        let mut position = LiquidityPositionBuilder::new(-10, 10)
+            .liquidity(1000)
+            .build();
+        position.interest_growth_checkpoint_a = 4 << Q64_RESOLUTION;
+        position.interest_owed_a = 100;
+
+        let update =
+            next_position_interest_update(&position, 6 << Q64_RESOLUTION, 3 << Q64_RESOLUTION);
+
+        assert_eq!(update.interest_growth_checkpoint_a, 6 << Q64_RESOLUTION);
+        assert_eq!(update.interest_owed_a, 2100);
+        assert_eq!(update.interest_growth_checkpoint_b, 3 << Q64_RESOLUTION);
+        assert_eq!(update.interest_owed_b, 3000);
+    }
+
+    #[test]
+    fn interest_delta_overflow_defaults_zero() {
+        let mut position = LiquidityPositionBuilder::new(-10, 10)
+            .liquidity(i64::MAX as u128)
+            .build();
+        position.interest_owed_b = 20;
+
+        let update = next_position_interest_update(&position, 0, u128::MAX);
+
+        assert_eq!(update.interest_growth_checkpoint_b, u128::MAX);
+        assert_eq!(update.interest_owed_b, 20);
+    }
 }
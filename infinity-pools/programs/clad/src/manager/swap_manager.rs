@@ -1,9 +1,18 @@
 use {
     crate::{
-        errors::ErrorCode, manager::tick_manager::next_tick_cross_update, math::*, state::*,
-        util::TickSequence,
+        errors::ErrorCode,
+        manager::{
+            globalpool_manager::{next_globalpool_reward_infos, next_globalpool_volatility_info},
+            limit_order_manager::fill_limit_orders,
+            oracle_manager::write_oracle_observation,
+            tick_manager::next_tick_cross_update,
+        },
+        math::*,
+        state::*,
+        util::{load_limit_order_arrays, load_tick_sequence, to_timestamp_u64, TickSequence},
     },
     anchor_lang::prelude::*,
+    anchor_spl::token::TokenAccount,
     solana_program::{instruction::Instruction, program},
     std::convert::TryInto,
 };
@@ -13,9 +22,12 @@ pub struct PostSwapUpdate {
     pub amount_a: u64,
     pub amount_b: u64,
     pub next_liquidity: u128,
+    pub next_liquidity_limit_orders: u128,
     pub next_tick_index: i32,
     pub next_sqrt_price: u128,
     pub next_fee_growth_global: u128,
+    pub next_reward_infos: [GlobalpoolRewardInfo; NUM_REWARDS],
+    pub next_volatility_info: GlobalpoolVolatilityInfo,
     pub next_protocol_fee: u64,
 }
 
@@ -26,7 +38,7 @@ pub fn swap(
     sqrt_price_limit: u128,
     amount_specified_is_input: bool,
     a_to_b: bool,
-    _timestamp: u64,
+    timestamp: u64,
 ) -> Result<PostSwapUpdate> {
     if sqrt_price_limit < MIN_SQRT_PRICE_X64 || sqrt_price_limit > MAX_SQRT_PRICE_X64 {
         return Err(ErrorCode::SqrtPriceOutOfBounds.into());
@@ -42,6 +54,9 @@ pub fn swap(
         return Err(ErrorCode::ZeroTradableAmount.into());
     }
 
+    let next_reward_infos = next_globalpool_reward_infos(globalpool, timestamp)?;
+    let mut next_volatility_info = next_globalpool_volatility_info(globalpool, timestamp)?;
+
     let tick_spacing = globalpool.tick_spacing;
     let fee_rate = globalpool.fee_rate;
     let protocol_fee_rate = globalpool.protocol_fee_rate;
@@ -68,12 +83,41 @@ pub fn swap(
                 curr_array_index,
             )?;
 
-        let (next_tick_sqrt_price, sqrt_price_target) =
-            get_next_sqrt_prices(next_tick_index, sqrt_price_limit, a_to_b);
+        // With the dynamic fee, each step ends at the next tick group boundary at the latest, and
+        // is charged for the volatility up to its start
+        let step_tick_index = if globalpool.is_dynamic_fee_enabled() {
+            get_step_tick_index(
+                curr_tick_index,
+                curr_sqrt_price,
+                next_tick_index,
+                tick_spacing,
+                a_to_b,
+            )
+        } else {
+            next_tick_index
+        };
+
+        let next_tick_sqrt_price = sqrt_price_from_tick_index(next_tick_index);
+        let (step_tick_sqrt_price, sqrt_price_target) =
+            get_next_sqrt_prices(step_tick_index, sqrt_price_limit, a_to_b);
+
+        let step_fee_rate = if globalpool.is_dynamic_fee_enabled() {
+            update_volatility_accumulator(globalpool, &mut next_volatility_info, curr_tick_index);
+            get_dynamic_fee_rate(
+                fee_rate,
+                get_variable_fee_rate(
+                    globalpool.variable_fee_control,
+                    next_volatility_info.volatility_accumulator,
+                    tick_spacing,
+                ),
+            )
+        } else {
+            fee_rate
+        };
 
         let swap_computation = compute_swap(
             amount_remaining,
-            fee_rate,
+            step_fee_rate,
             curr_liquidity,
             curr_sqrt_price,
             sqrt_price_target,
@@ -133,6 +177,7 @@ pub fn swap(
                     curr_liquidity,
                     fee_growth_global_a,
                     fee_growth_global_b,
+                    &next_reward_infos,
                 )?;
 
                 curr_liquidity = next_liquidity;
@@ -142,6 +187,18 @@ pub fn swap(
                     tick_spacing,
                     &update,
                 )?;
+
+                // Limit orders on the range the price just moved past are converted
+                fill_limit_orders(
+                    swap_tick_sequence,
+                    next_array_index,
+                    next_tick_index,
+                    tick_spacing,
+                    a_to_b,
+                    fee_growth_global_a,
+                    fee_growth_global_b,
+                    &next_reward_infos,
+                )?;
             }
 
             let tick_offset = swap_tick_sequence.get_tick_offset(
@@ -168,6 +225,20 @@ pub fn swap(
             } else {
                 next_tick_index
             };
+        } else if swap_computation.next_price == step_tick_sqrt_price {
+            // Stopped at a tick group boundary before the next initialized tick, which is searched
+            // again from the boundary, possibly in a later tick array
+            curr_tick_index = if a_to_b {
+                step_tick_index - 1
+            } else {
+                step_tick_index
+            };
+            curr_array_index = swap_tick_sequence.get_search_array_index(
+                curr_tick_index,
+                tick_spacing,
+                a_to_b,
+                curr_array_index,
+            )?;
         } else if swap_computation.next_price != curr_sqrt_price {
             curr_tick_index = tick_index_from_sqrt_price(&swap_computation.next_price);
         }
@@ -175,6 +246,20 @@ pub fn swap(
         curr_sqrt_price = swap_computation.next_price;
     }
 
+    // Carry the volatility up to the end of the swap over to the next swap
+    if globalpool.is_dynamic_fee_enabled() {
+        update_volatility_accumulator(globalpool, &mut next_volatility_info, curr_tick_index);
+    }
+
+    // Limit orders pending on the range the price ends in, if it moved to another range
+    let range_index = |tick_index: i32| tick_index.div_euclid(tick_spacing as i32);
+    let next_liquidity_limit_orders =
+        if range_index(curr_tick_index) == range_index(globalpool.tick_current_index) {
+            globalpool.liquidity_limit_orders
+        } else {
+            swap_tick_sequence.get_limit_order_liquidity_in_range(curr_tick_index, tick_spacing)?
+        };
+
     let (amount_a, amount_b) = if a_to_b == amount_specified_is_input {
         (amount - amount_remaining, amount_calculated)
     } else {
@@ -185,13 +270,54 @@ pub fn swap(
         amount_a,
         amount_b,
         next_liquidity: curr_liquidity,
+        next_liquidity_limit_orders,
         next_tick_index: curr_tick_index,
         next_sqrt_price: curr_sqrt_price,
         next_fee_growth_global: curr_fee_growth_global_input,
+        next_reward_infos,
+        next_volatility_info,
         next_protocol_fee: curr_protocol_fee,
     })
 }
 
+// Tick index at the end of the tick group the price is in, in the direction of the swap, unless the
+// next initialized tick comes first. A price right at the start of its tick group moving down ends
+// at the start of the group below.
+fn get_step_tick_index(
+    curr_tick_index: i32,
+    curr_sqrt_price: u128,
+    next_tick_index: i32,
+    tick_spacing: u16,
+    a_to_b: bool,
+) -> i32 {
+    let tick_group_start_index =
+        get_tick_group_index(curr_tick_index, tick_spacing) * tick_spacing as i32;
+    if a_to_b {
+        let tick_group_boundary_index =
+            if sqrt_price_from_tick_index(tick_group_start_index) >= curr_sqrt_price {
+                tick_group_start_index - tick_spacing as i32
+            } else {
+                tick_group_start_index
+            };
+        tick_group_boundary_index.max(next_tick_index)
+    } else {
+        (tick_group_start_index + tick_spacing as i32).min(next_tick_index)
+    }
+}
+
+fn update_volatility_accumulator(
+    globalpool: &Globalpool,
+    volatility_info: &mut GlobalpoolVolatilityInfo,
+    tick_index: i32,
+) {
+    volatility_info.volatility_accumulator = get_volatility_accumulator(
+        volatility_info.volatility_reference,
+        volatility_info.tick_group_index_reference,
+        get_tick_group_index(tick_index, globalpool.tick_spacing),
+        globalpool.max_volatility_accumulator,
+    );
+}
+
 fn calculate_fees(
     fee_amount: u64,
     protocol_fee_rate: u16,
@@ -227,6 +353,7 @@ fn calculate_update(
     liquidity: u128,
     fee_growth_global_a: u128,
     fee_growth_global_b: u128,
+    reward_infos: &[GlobalpoolRewardInfo; NUM_REWARDS],
 ) -> Result<(TickUpdate, u128)> {
     // Use updated fee_growth for crossing tick
     // Use -liquidity_net if going left, +liquidity_net going right
@@ -236,7 +363,8 @@ fn calculate_update(
         tick.liquidity_net
     };
 
-    let update = next_tick_cross_update(tick, fee_growth_global_a, fee_growth_global_b)?;
+    let update =
+        next_tick_cross_update(tick, fee_growth_global_a, fee_growth_global_b, reward_infos)?;
 
     // Update the global liquidity to reflect the new current tick
     let next_liquidity = add_liquidity_delta(liquidity, signed_liquidity_net)?;
@@ -258,15 +386,219 @@ fn get_next_sqrt_prices(
     (next_tick_price, next_sqrt_price_limit)
 }
 
+//
+// Token amounts of a trade position swap, ie. the amount of the swapped token that left the
+// Globalpool's vault (in) and the amount of the other token that was received (out).
+//
+#[derive(Debug, PartialEq)]
+pub struct RouterSwapUpdate {
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
+//
+// Swap instruction data of trade positions routed through the Globalpool itself.
+//
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct GlobalpoolSwapData {
+    pub sqrt_price_limit: u128,
+    pub other_amount_threshold: u64,
+}
+
+//
+// Swaps the tokens of a trade position held in the Globalpool's vaults, through the Globalpool's
+// swap router.
+//
+// - External router (eg. Jupiter): CPI with `swap_instruction_data`, where the first remaining
+//   account must be the router program. The swap is measured by the vault balance deltas.
+// - Clad (this program): swap against the Globalpool itself with `GlobalpoolSwapData`, where the
+//   remaining accounts are the tick arrays to traverse, followed by the LimitOrderArrays of those
+//   with pending limit orders (all 3 tick arrays must be passed then). Since the vaults are on both
+//   sides of the swap, no tokens are transferred and the swap is measured by the swap amounts.
+//   Like the swap instruction, it needs the `allow_swap` permission and writes to the oracle.
+//
+// `amount` and `amount_specified_is_input` are only used by the Globalpool swap, so callers must
+// verify the returned amounts regardless of the router.
+//
+pub fn execute_swap_for_globalpool(
+    globalpool: &mut Account<Globalpool>,
+    clad: &Clad,
+    oracle: &AccountInfo,
+    token_vault_a: &mut Account<TokenAccount>,
+    token_vault_b: &mut Account<TokenAccount>,
+    remaining_accounts: &[AccountInfo],
+    swap_instruction_data: &Vec<u8>,
+    amount: u64,
+    amount_specified_is_input: bool,
+    a_to_b: bool,
+) -> Result<RouterSwapUpdate> {
+    if globalpool.is_swap_routed_internally() {
+        let swap_data = GlobalpoolSwapData::try_from_slice(swap_instruction_data)
+            .map_err(|_| ErrorCode::InvalidSwapInstructionData)?;
+
+        return execute_globalpool_swap(
+            globalpool,
+            clad,
+            oracle,
+            remaining_accounts,
+            &swap_data,
+            amount,
+            amount_specified_is_input,
+            a_to_b,
+        );
+    }
+
+    let (in_before_balance, out_before_balance) = if a_to_b {
+        (token_vault_a.amount, token_vault_b.amount)
+    } else {
+        (token_vault_b.amount, token_vault_a.amount)
+    };
+
+    execute_router_swap(globalpool, remaining_accounts, swap_instruction_data)?;
+
+    // Reload vaults for updated token balances
+    token_vault_a.reload()?;
+    token_vault_b.reload()?;
+
+    let (in_after_balance, out_after_balance) = if a_to_b {
+        (token_vault_a.amount, token_vault_b.amount)
+    } else {
+        (token_vault_b.amount, token_vault_a.amount)
+    };
+
+    calculate_vault_swap_update(
+        in_before_balance,
+        in_after_balance,
+        out_before_balance,
+        out_after_balance,
+    )
+}
+
+//
+// Swap amounts from the vault balances before & after the router swap.
+// The swapped token must decrease and the other token must increase.
+//
+pub fn calculate_vault_swap_update(
+    in_before_balance: u64,
+    in_after_balance: u64,
+    out_before_balance: u64,
+    out_after_balance: u64,
+) -> Result<RouterSwapUpdate> {
+    msg!("swap_in before:  {}", in_before_balance);
+    msg!("swap_in after:   {}", in_after_balance);
+    msg!("swap_out before: {}", out_before_balance);
+    msg!("swap_out after:  {}", out_after_balance);
+
+    if in_after_balance >= in_before_balance || out_after_balance <= out_before_balance {
+        return Err(ErrorCode::InvalidLoanTradeSwapDirection.into());
+    }
+
+    Ok(RouterSwapUpdate {
+        amount_in: in_before_balance - in_after_balance,
+        amount_out: out_after_balance - out_before_balance,
+    })
+}
+
+fn execute_globalpool_swap(
+    globalpool: &mut Account<Globalpool>,
+    clad: &Clad,
+    oracle: &AccountInfo,
+    remaining_accounts: &[AccountInfo],
+    swap_data: &GlobalpoolSwapData,
+    amount: u64,
+    amount_specified_is_input: bool,
+    a_to_b: bool,
+) -> Result<RouterSwapUpdate> {
+    require!(clad.permissions.allow_swap, ErrorCode::SwapNotAllowed);
+
+    let tick_arrays = remaining_accounts
+        .iter()
+        .take(3)
+        .map(AccountLoader::<TickArray>::try_from)
+        .collect::<Result<Vec<_>>>()?;
+
+    if tick_arrays.is_empty() {
+        return Err(ErrorCode::InvalidTickArraySequence.into());
+    }
+
+    for tick_array in tick_arrays.iter() {
+        if tick_array.load()?.globalpool != globalpool.key() {
+            return Err(ErrorCode::InvalidTickArraySequence.into());
+        }
+    }
+
+    // LimitOrderArrays follow the 3 tick arrays
+    let limit_order_arrays = load_limit_order_arrays(remaining_accounts.get(3..).unwrap_or(&[]))?;
+
+    let mut swap_tick_sequence =
+        load_tick_sequence(&tick_arrays[0], tick_arrays.get(1), tick_arrays.get(2))?;
+    for limit_order_array in limit_order_arrays.iter() {
+        swap_tick_sequence.add_limit_order_array(limit_order_array.load_mut()?)?;
+    }
+
+    let timestamp = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
+    let swap_update = swap(
+        globalpool,
+        &mut swap_tick_sequence,
+        amount,
+        swap_data.sqrt_price_limit,
+        amount_specified_is_input,
+        a_to_b,
+        timestamp,
+    )?;
+
+    msg!("swap_update: {:?}", swap_update);
+
+    let (amount_in, amount_out) = if a_to_b {
+        (swap_update.amount_a, swap_update.amount_b)
+    } else {
+        (swap_update.amount_b, swap_update.amount_a)
+    };
+
+    if amount_specified_is_input {
+        if swap_data.other_amount_threshold > amount_out {
+            return Err(ErrorCode::AmountOutBelowMinimum.into());
+        }
+    } else if swap_data.other_amount_threshold < amount_in {
+        return Err(ErrorCode::AmountInAboveMaximum.into());
+    }
+
+    // Observe the tick before the swap moves it
+    write_oracle_observation(oracle, timestamp, globalpool.tick_current_index)?;
+
+    // Both sides of the swap are the Globalpool's vaults, so only the pool state is updated
+    globalpool.update_after_swap(
+        swap_update.next_liquidity,
+        swap_update.next_liquidity_limit_orders,
+        swap_update.next_tick_index,
+        swap_update.next_sqrt_price,
+        swap_update.next_fee_growth_global,
+        swap_update.next_reward_infos,
+        swap_update.next_volatility_info,
+        swap_update.next_protocol_fee,
+        a_to_b,
+        timestamp,
+    )?;
+
+    Ok(RouterSwapUpdate {
+        amount_in,
+        amount_out,
+    })
+}
+
 //
 // TODO: Validate that the receiver of the token swap is the globalpool's token vault
 //
-pub fn execute_jupiter_swap_for_globalpool(
+fn execute_router_swap(
     globalpool: &Account<Globalpool>,
     remaining_accounts: &[AccountInfo<'_>],
     swap_instruction_data: &Vec<u8>,
 ) -> Result<()> {
     // 0th index is router pid, so skip it
+    if remaining_accounts.is_empty() || remaining_accounts[0].key() != globalpool.swap_router {
+        return Err(ErrorCode::InvalidSwapRouter.into());
+    }
+
     let swap_route_accounts: Vec<AccountMeta> = remaining_accounts[1..]
         .iter()
         .map(|acct| {
@@ -284,15 +616,14 @@ pub fn execute_jupiter_swap_for_globalpool(
     //
 
     let swap_instruction = Instruction {
-        // Jupiter Program ID hard-coded in the program for now
-        program_id: jupiter_cpi::id(), // == JUP4Fb2cqiRUcaTHdrPC8h2gNsA2ETXiPDD33WcGuJB
+        program_id: globalpool.swap_router,
         accounts: swap_route_accounts,
         data: swap_instruction_data.clone(),
     };
 
     program::invoke_signed(
         &swap_instruction,
-        remaining_accounts, // all accounts are for swap (incl Jupiter account)
+        remaining_accounts, // all accounts are for swap (incl router account)
         &[&globalpool.seeds()],
     )?;
 
@@ -2483,3 +2814,245 @@ mod swap_error_tests {
         swap_test_info.run(&mut tick_sequence, 100);
     }
 }
+
+#[cfg(test)]
+mod vault_swap_update_tests {
+    use super::*;
+
+    #[test]
+    fn test_vault_swap_update() {
+        let swap_update = calculate_vault_swap_update(1_000, 400, 2_000, 2_550).unwrap();
+        assert_eq!(
+            swap_update,
+            RouterSwapUpdate {
+                amount_in: 600,
+                amount_out: 550,
+            }
+        );
+    }
+
+    #[test]
+    fn test_vault_swap_update_wrong_direction() {
+        // Swapped token increased
+        let result = calculate_vault_swap_update(1_000, 1_100, 2_000, 2_550);
+        assert_eq!(
+            result.unwrap_err(),
+            ErrorCode::InvalidLoanTradeSwapDirection.into()
+        );
+
+        // Nothing received
+        let result = calculate_vault_swap_update(1_000, 400, 2_000, 2_000);
+        assert_eq!(
+            result.unwrap_err(),
+            ErrorCode::InvalidLoanTradeSwapDirection.into()
+        );
+    }
+}
+
+#[cfg(test)]
+mod swap_dynamic_fee_tests {
+    use {super::*, crate::util::test_utils::swap_test_fixture::*};
+
+    fn swap_test_fixture(liquidity: u128) -> SwapTestFixture {
+        swap_test_fixture_in_direction(liquidity, false)
+    }
+
+    fn swap_test_fixture_in_direction(liquidity: u128, a_to_b: bool) -> SwapTestFixture {
+        let sqrt_price_limit = if a_to_b {
+            sqrt_price_from_tick_index(-TICK_ARRAY_SIZE * TS_128 as i32 * 2)
+        } else {
+            sqrt_price_from_tick_index(TICK_ARRAY_SIZE * TS_128 as i32 * 3 - 1)
+        };
+        SwapTestFixture::new(SwapTestFixtureInfo {
+            tick_spacing: TS_128,
+            liquidity,
+            curr_tick_index: 64,
+            start_tick_index: 0,
+            trade_amount: 1_000_000_000,
+            sqrt_price_limit,
+            amount_specified_is_input: true,
+            a_to_b,
+            array_1_ticks: &vec![],
+            array_2_ticks: Some(&vec![]),
+            array_3_ticks: Some(&vec![]),
+            fee_rate: 3_000,
+            ..Default::default()
+        })
+    }
+
+    fn run(swap_test_info: &SwapTestFixture, timestamp: u64) -> PostSwapUpdate {
+        let mut tick_sequence = TickSequence::new(
+            swap_test_info.tick_arrays[0].borrow_mut(),
+            Some(swap_test_info.tick_arrays[1].borrow_mut()),
+            Some(swap_test_info.tick_arrays[2].borrow_mut()),
+        );
+        swap_test_info.run(&mut tick_sequence, timestamp)
+    }
+
+    #[test]
+    /// Disabled by default, leaving the volatility untouched.
+    fn dynamic_fee_disabled() {
+        let swap_test_info = swap_test_fixture(10_000_000_000);
+        let post_swap = run(&swap_test_info, 100);
+
+        assert_eq!(
+            post_swap.next_volatility_info,
+            GlobalpoolVolatilityInfo::default()
+        );
+    }
+
+    #[test]
+    fn step_tick_index_at_tick_group_boundary() {
+        let sqrt_price_at = sqrt_price_from_tick_index;
+        // Up to the start of the next tick group
+        assert_eq!(
+            get_step_tick_index(64, sqrt_price_at(64), 1000, 128, false),
+            128
+        );
+        assert_eq!(
+            get_step_tick_index(-1, sqrt_price_at(-1), 1000, 128, false),
+            0
+        );
+        // Down to the start of the tick group, or of the group below when right at it
+        assert_eq!(
+            get_step_tick_index(64, sqrt_price_at(64), -1000, 128, true),
+            0
+        );
+        assert_eq!(
+            get_step_tick_index(128, sqrt_price_at(128), -1000, 128, true),
+            0
+        );
+        assert_eq!(
+            get_step_tick_index(-1, sqrt_price_at(0), -1000, 128, true),
+            -128
+        );
+        // The next initialized tick comes first
+        assert_eq!(
+            get_step_tick_index(64, sqrt_price_at(64), 100, 128, false),
+            100
+        );
+        assert_eq!(
+            get_step_tick_index(64, sqrt_price_at(64), 30, 128, true),
+            30
+        );
+    }
+
+    #[test]
+    /// Steps ending at tick group boundaries carry the search for the next initialized tick over
+    /// to the following tick arrays.
+    fn dynamic_fee_steps_across_tick_arrays() {
+        for a_to_b in [false, true] {
+            let swap_test_info = swap_test_fixture_in_direction(1_000_000_000, a_to_b);
+            let post_swap_fixed = run(&swap_test_info, 100);
+
+            let mut swap_test_info_dynamic = swap_test_fixture_in_direction(1_000_000_000, a_to_b);
+            swap_test_info_dynamic
+                .globalpool
+                .update_dynamic_fee_config(10_000, 30, 600, 5_000, 350_000)
+                .unwrap();
+            let post_swap = run(&swap_test_info_dynamic, 100);
+
+            assert!(post_swap.next_tick_index.abs() > TICK_ARRAY_SIZE * TS_128 as i32);
+            assert!(post_swap.next_tick_index.abs() < post_swap_fixed.next_tick_index.abs());
+            assert_eq!(
+                post_swap.next_volatility_info.volatility_accumulator,
+                350_000
+            );
+        }
+    }
+
+    #[test]
+    /// A swap within the starting tick group is charged the base fee.
+    fn dynamic_fee_within_tick_group() {
+        let swap_test_info = swap_test_fixture(10_000_000_000_000);
+        let post_swap_fixed = run(&swap_test_info, 100);
+
+        let mut swap_test_info_dynamic = swap_test_fixture(10_000_000_000_000);
+        swap_test_info_dynamic
+            .globalpool
+            .update_dynamic_fee_config(10_000, 30, 600, 5_000, 350_000)
+            .unwrap();
+        let post_swap = run(&swap_test_info_dynamic, 100);
+
+        assert!(post_swap.next_tick_index < TS_128 as i32);
+        assert_eq!(post_swap.amount_a, post_swap_fixed.amount_a);
+        assert_eq!(post_swap.next_sqrt_price, post_swap_fixed.next_sqrt_price);
+        assert_eq!(
+            post_swap.next_fee_growth_global,
+            post_swap_fixed.next_fee_growth_global
+        );
+        assert_eq!(post_swap.next_volatility_info.volatility_accumulator, 0);
+    }
+
+    #[test]
+    /// The first swap in a while measures volatility from the starting tick group. Each tick group
+    /// it moves through is charged for the volatility up to its start, even without initialized
+    /// ticks in between. The accumulated volatility is carried over to the next swap.
+    fn dynamic_fee_accumulates_volatility() {
+        let mut swap_test_info = swap_test_fixture(10_000_000_000);
+        let post_swap_fixed = run(&swap_test_info, 100);
+
+        let mut swap_test_info_dynamic = swap_test_fixture(10_000_000_000);
+        swap_test_info_dynamic
+            .globalpool
+            .update_dynamic_fee_config(10_000, 30, 600, 5_000, 350_000)
+            .unwrap();
+        let post_swap = run(&swap_test_info_dynamic, 100);
+
+        // Charged more than the base fee past the starting tick group, so the same amount in
+        // moves the price less and receives less
+        assert!(post_swap.amount_a < post_swap_fixed.amount_a);
+        assert!(post_swap.next_tick_index < post_swap_fixed.next_tick_index);
+        assert!(post_swap.next_fee_growth_global > post_swap_fixed.next_fee_growth_global);
+
+        let tick_group_delta = post_swap.next_tick_index.div_euclid(TS_128 as i32);
+        assert!(tick_group_delta > 1);
+        assert_eq!(
+            post_swap.next_volatility_info,
+            GlobalpoolVolatilityInfo {
+                volatility_accumulator: std::cmp::min(
+                    tick_group_delta as u32 * VOLATILITY_ACCUMULATOR_SCALE_FACTOR,
+                    350_000
+                ),
+                volatility_reference: 0,
+                tick_group_index_reference: 0,
+                last_swap_timestamp: 100,
+            }
+        );
+
+        // The next swap within the filter period pays the variable fee for the movement so far
+        swap_test_info.globalpool.update_after_swap(
+            post_swap_fixed.next_liquidity,
+            post_swap_fixed.next_liquidity_limit_orders,
+            post_swap_fixed.next_tick_index,
+            post_swap_fixed.next_sqrt_price,
+            post_swap_fixed.next_fee_growth_global,
+            post_swap_fixed.next_reward_infos,
+            post_swap_fixed.next_volatility_info,
+            post_swap_fixed.next_protocol_fee,
+            false,
+            110,
+        )
+        .unwrap();
+        swap_test_info_dynamic.globalpool.update_after_swap(
+            post_swap.next_liquidity,
+            post_swap.next_liquidity_limit_orders,
+            post_swap.next_tick_index,
+            post_swap.next_sqrt_price,
+            post_swap.next_fee_growth_global,
+            post_swap.next_reward_infos,
+            post_swap.next_volatility_info,
+            post_swap.next_protocol_fee,
+            false,
+            110,
+        )
+        .unwrap();
+        let post_swap_fixed = run(&swap_test_info, 110);
+        let post_swap = run(&swap_test_info_dynamic, 110);
+
+        assert!(post_swap.amount_a < post_swap_fixed.amount_a);
+        assert!(post_swap.next_fee_growth_global > post_swap_fixed.next_fee_growth_global);
+        assert_eq!(post_swap.next_volatility_info.tick_group_index_reference, 0);
+        assert_eq!(post_swap.next_volatility_info.last_swap_timestamp, 110);
+    }
+}
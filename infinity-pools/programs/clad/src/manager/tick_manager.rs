@@ -1,19 +1,30 @@
 use crate::{
     errors::ErrorCode,
     math::add_liquidity_delta,
-    state::{Tick, TickUpdate},
+    state::{GlobalpoolRewardInfo, Tick, TickUpdate, NUM_REWARDS},
 };
 
 pub fn next_tick_cross_update(
     tick: &Tick,
     fee_growth_global_a: u128,
     fee_growth_global_b: u128,
+    reward_infos: &[GlobalpoolRewardInfo; NUM_REWARDS],
 ) -> Result<TickUpdate, ErrorCode> {
     let mut update = TickUpdate::from(tick);
 
     update.fee_growth_outside_a = fee_growth_global_a.wrapping_sub(tick.fee_growth_outside_a);
     update.fee_growth_outside_b = fee_growth_global_b.wrapping_sub(tick.fee_growth_outside_b);
 
+    for (i, reward_info) in reward_infos.iter().enumerate() {
+        if !reward_info.initialized() {
+            continue;
+        }
+
+        update.reward_growths_outside[i] = reward_info
+            .growth_global_x64
+            .wrapping_sub(tick.reward_growths_outside[i]);
+    }
+
     Ok(update)
 }
 
@@ -23,6 +34,7 @@ pub fn next_tick_modify_liquidity_update(
     tick_current_index: i32,
     fee_growth_global_a: u128,
     fee_growth_global_b: u128,
+    reward_infos: &[GlobalpoolRewardInfo; NUM_REWARDS],
     liquidity_delta: i128,
     is_upper_tick: bool,
     is_loan_accounting: bool,
@@ -37,21 +49,29 @@ pub fn next_tick_modify_liquidity_update(
 
     // Update to an uninitialized tick if remaining liquidity is being removed
     if liquidity_gross == 0 && !is_loan_accounting {
-        // return Ok(TickUpdate { liquidity_borrowed: tick.liquidity_borrowed, ..Default::default() });
         // msg!("tick {:?} has liquidity gross = 0", tick_index);
         return Ok(TickUpdate::default());
     }
 
-    let (fee_growth_outside_a, fee_growth_outside_b) = if tick.liquidity_gross == 0 {
-        // By convention, assume all prior growth happened below the tick
-        if tick_current_index >= tick_index {
-            (fee_growth_global_a, fee_growth_global_b)
+    let (fee_growth_outside_a, fee_growth_outside_b, reward_growths_outside) =
+        if tick.liquidity_gross == 0 {
+            // By convention, assume all prior growth happened below the tick
+            if tick_current_index >= tick_index {
+                (
+                    fee_growth_global_a,
+                    fee_growth_global_b,
+                    next_reward_growths_global(reward_infos),
+                )
+            } else {
+                (0, 0, [0; NUM_REWARDS])
+            }
         } else {
-            (0, 0)
-        }
-    } else {
-        (tick.fee_growth_outside_a, tick.fee_growth_outside_b)
-    };
+            (
+                tick.fee_growth_outside_a,
+                tick.fee_growth_outside_b,
+                tick.reward_growths_outside,
+            )
+        };
 
     let liquidity_net = if is_upper_tick {
         tick.liquidity_net
@@ -67,36 +87,46 @@ pub fn next_tick_modify_liquidity_update(
         initialized: true,
         liquidity_net,
         liquidity_gross,
-        liquidity_borrowed: tick.liquidity_borrowed,
         fee_growth_outside_a,
         fee_growth_outside_b,
+        reward_growths_outside,
     })
 }
 
+fn next_reward_growths_global(
+    reward_infos: &[GlobalpoolRewardInfo; NUM_REWARDS],
+) -> [u128; NUM_REWARDS] {
+    let mut reward_growths = [0; NUM_REWARDS];
+    for (i, reward_info) in reward_infos.iter().enumerate() {
+        if reward_info.initialized() {
+            reward_growths[i] = reward_info.growth_global_x64;
+        }
+    }
+    reward_growths
+}
+
 pub fn next_tick_modify_liquidity_update_from_loan(
     tick: &Tick,
     tick_index: i32,
     tick_current_index: i32,
     fee_growth_global_a: u128,
     fee_growth_global_b: u128,
+    reward_infos: &[GlobalpoolRewardInfo; NUM_REWARDS],
     liquidity_delta: i128,
     is_upper_tick: bool,
 ) -> Result<TickUpdate, ErrorCode> {
-    let mut update = next_tick_modify_liquidity_update(
+    // The liquidity borrowed at the tick is tracked by its TickInterest
+    next_tick_modify_liquidity_update(
         tick,
         tick_index,
         tick_current_index,
         fee_growth_global_a,
         fee_growth_global_b,
+        reward_infos,
         liquidity_delta,
         is_upper_tick,
         true,
-    )?;
-
-    update.liquidity_borrowed += liquidity_delta;
-    // msg!("update: {:?}", update);
-
-    Ok(update)
+    )
 }
 
 // Calculates the fee growths inside of tick_lower and tick_upper based on their
@@ -150,16 +180,65 @@ pub fn next_fee_growths_inside(
     )
 }
 
+// Calculates the reward growths inside of tick_lower and tick_upper based on their positions
+// relative to tick_current_index. An uninitialized reward will always have a reward growth of zero.
+pub fn next_reward_growths_inside(
+    tick_current_index: i32,
+    tick_lower: &Tick,
+    tick_lower_index: i32,
+    tick_upper: &Tick,
+    tick_upper_index: i32,
+    reward_infos: &[GlobalpoolRewardInfo; NUM_REWARDS],
+) -> [u128; NUM_REWARDS] {
+    let mut reward_growths_inside = [0; NUM_REWARDS];
+
+    for (i, reward_info) in reward_infos.iter().enumerate() {
+        if !reward_info.initialized() {
+            continue;
+        }
+
+        // By convention, assume all prior growth happened below the tick
+        let reward_growths_below = if !tick_lower.initialized {
+            reward_info.growth_global_x64
+        } else if tick_current_index < tick_lower_index {
+            reward_info
+                .growth_global_x64
+                .wrapping_sub(tick_lower.reward_growths_outside[i])
+        } else {
+            tick_lower.reward_growths_outside[i]
+        };
+
+        // By convention, assume all prior growth happened below the tick, not above
+        let reward_growths_above = if !tick_upper.initialized {
+            0
+        } else if tick_current_index < tick_upper_index {
+            tick_upper.reward_growths_outside[i]
+        } else {
+            reward_info
+                .growth_global_x64
+                .wrapping_sub(tick_upper.reward_growths_outside[i])
+        };
+
+        reward_growths_inside[i] = reward_info
+            .growth_global_x64
+            .wrapping_sub(reward_growths_below)
+            .wrapping_sub(reward_growths_above);
+    }
+
+    reward_growths_inside
+}
+
 #[cfg(test)]
 mod tick_manager_tests {
     use crate::{
         errors::ErrorCode,
         manager::tick_manager::{
-            next_fee_growths_inside, next_tick_cross_update, next_tick_modify_liquidity_update,
-            TickUpdate,
+            next_fee_growths_inside, next_reward_growths_inside, next_tick_cross_update,
+            next_tick_modify_liquidity_update, TickUpdate,
         },
-        state::{tick_builder::TickBuilder, Tick},
+        state::{tick_builder::TickBuilder, GlobalpoolRewardInfo, Tick, NUM_REWARDS},
     };
+    use anchor_lang::prelude::Pubkey;
 
     #[test]
     fn test_next_fee_growths_inside() {
@@ -263,6 +342,45 @@ mod tick_manager_tests {
         }
     }
 
+    #[test]
+    fn test_next_reward_growths_inside() {
+        let reward_infos = [
+            GlobalpoolRewardInfo {
+                mint: Pubkey::new_unique(),
+                growth_global_x64: 3000,
+                ..Default::default()
+            },
+            GlobalpoolRewardInfo::default(),
+            GlobalpoolRewardInfo::default(),
+        ];
+        let tick_lower = Tick {
+            initialized: true,
+            reward_growths_outside: [2000, 0, 0],
+            ..Default::default()
+        };
+
+        for (name, tick_current_index, tick_upper_reward_growth_outside, expected) in [
+            ("current tick index below ticks", -200, 1000, [1000, 0, 0]),
+            ("current tick index between ticks", 0, 500, [500, 0, 0]),
+            ("current tick index above ticks", 200, 2500, [500, 0, 0]),
+        ] {
+            let tick_upper = Tick {
+                initialized: true,
+                reward_growths_outside: [tick_upper_reward_growth_outside, 0, 0],
+                ..Default::default()
+            };
+            let reward_growths_inside = next_reward_growths_inside(
+                tick_current_index,
+                &tick_lower,
+                -100,
+                &tick_upper,
+                100,
+                &reward_infos,
+            );
+            assert_eq!(reward_growths_inside, expected, "{}", name);
+        }
+    }
+
     #[test]
     fn test_next_tick_modify_liquidity_update() {
         #[derive(Default)]
@@ -308,9 +426,9 @@ mod tick_manager_tests {
                     initialized: true,
                     liquidity_net: 42069,
                     liquidity_gross: 42069,
-                    liquidity_borrowed: 0,
                     fee_growth_outside_a: 100,
                     fee_growth_outside_b: 100,
+                    ..Default::default()
                 },
                 ..Default::default()
             },
@@ -419,6 +537,7 @@ mod tick_manager_tests {
                 test.tick_current_index,
                 test.fee_growth_global_a,
                 test.fee_growth_global_b,
+                &[GlobalpoolRewardInfo::default(); NUM_REWARDS],
                 test.liquidity_delta,
                 test.is_upper_tick,
                 false,
@@ -516,6 +635,7 @@ mod tick_manager_tests {
                 test.tick_current_index,
                 0,
                 0,
+                &[GlobalpoolRewardInfo::default(); NUM_REWARDS],
                 test.liquidity_delta,
                 test.is_upper_tick,
                 false,
@@ -555,6 +675,7 @@ mod tick_manager_tests {
                 &test.tick,
                 test.fee_growth_global_a,
                 test.fee_growth_global_b,
+                &[GlobalpoolRewardInfo::default(); NUM_REWARDS],
             )
             .unwrap();
 
@@ -0,0 +1,128 @@
+use crate::math::MAX_FEE_RATE;
+
+// The volatility accumulator counts tick groups (tick spacings) moved, scaled by this factor.
+pub const VOLATILITY_ACCUMULATOR_SCALE_FACTOR: u32 = 10_000;
+
+// The variable fee control is scaled by this factor (see `get_variable_fee_rate`).
+pub const VARIABLE_FEE_CONTROL_SCALE_FACTOR: u128 = 100_000;
+
+// Portion of the volatility accumulator kept as reference is represented as basis points.
+pub const MAX_VOLATILITY_REDUCTION_FACTOR_BPS: u16 = 10_000;
+
+//
+// Get the tick group of `tick_index`. Volatility is measured in tick groups moved, so that pools of
+// any tick spacing accumulate it at the same pace.
+//
+pub fn get_tick_group_index(tick_index: i32, tick_spacing: u16) -> i32 {
+    tick_index.div_euclid(tick_spacing as i32)
+}
+
+//
+// Get the volatility accumulator after moving from `tick_group_index_reference` to
+// `tick_group_index`, on top of `volatility_reference` and capped at `max_volatility_accumulator`.
+//
+pub fn get_volatility_accumulator(
+    volatility_reference: u32,
+    tick_group_index_reference: i32,
+    tick_group_index: i32,
+    max_volatility_accumulator: u32,
+) -> u32 {
+    let tick_group_delta =
+        (tick_group_index as i64 - tick_group_index_reference as i64).unsigned_abs();
+    let volatility_accumulator = (volatility_reference as u64).saturating_add(
+        tick_group_delta.saturating_mul(VOLATILITY_ACCUMULATOR_SCALE_FACTOR as u64),
+    );
+
+    std::cmp::min(volatility_accumulator, max_volatility_accumulator as u64) as u32
+}
+
+//
+// Get the variable fee rate (in hundredths of a bip) from the square of the volatility in ticks:
+//
+//   variable_fee_rate = variable_fee_control * (volatility_accumulator * tick_spacing / SCALE)^2
+//                       / VARIABLE_FEE_CONTROL_SCALE_FACTOR
+//
+// rounded up. The rate saturates at MAX_FEE_RATE.
+//
+pub fn get_variable_fee_rate(
+    variable_fee_control: u32,
+    volatility_accumulator: u32,
+    tick_spacing: u16,
+) -> u16 {
+    let volatility_x_tick_spacing = volatility_accumulator as u128 * tick_spacing as u128;
+    let numerator = (variable_fee_control as u128)
+        .saturating_mul(volatility_x_tick_spacing)
+        .saturating_mul(volatility_x_tick_spacing);
+    let denominator = VARIABLE_FEE_CONTROL_SCALE_FACTOR
+        * VOLATILITY_ACCUMULATOR_SCALE_FACTOR as u128
+        * VOLATILITY_ACCUMULATOR_SCALE_FACTOR as u128;
+
+    let variable_fee_rate = numerator / denominator + (numerator % denominator != 0) as u128;
+
+    std::cmp::min(variable_fee_rate, MAX_FEE_RATE as u128) as u16
+}
+
+//
+// Get the fee rate of a swap step: the base fee rate plus the variable fee rate, capped at
+// MAX_FEE_RATE.
+//
+pub fn get_dynamic_fee_rate(base_fee_rate: u16, variable_fee_rate: u16) -> u16 {
+    std::cmp::min(
+        base_fee_rate.saturating_add(variable_fee_rate),
+        MAX_FEE_RATE,
+    )
+}
+
+#[cfg(test)]
+mod test_dynamic_fee_math {
+    use super::*;
+
+    #[test]
+    fn test_tick_group_index() {
+        assert_eq!(get_tick_group_index(0, 64), 0);
+        assert_eq!(get_tick_group_index(63, 64), 0);
+        assert_eq!(get_tick_group_index(64, 64), 1);
+        assert_eq!(get_tick_group_index(-1, 64), -1);
+        assert_eq!(get_tick_group_index(-64, 64), -1);
+        assert_eq!(get_tick_group_index(-65, 64), -2);
+    }
+
+    #[test]
+    fn test_volatility_accumulator() {
+        // Moving 3 tick groups in either direction on top of the reference
+        assert_eq!(get_volatility_accumulator(5_000, 10, 13, 1_000_000), 35_000);
+        assert_eq!(get_volatility_accumulator(5_000, 10, 7, 1_000_000), 35_000);
+        // No movement keeps the reference
+        assert_eq!(get_volatility_accumulator(5_000, 10, 10, 1_000_000), 5_000);
+        // Capped at the max
+        assert_eq!(
+            get_volatility_accumulator(5_000, 10, 1_000, 100_000),
+            100_000
+        );
+        assert_eq!(
+            get_volatility_accumulator(u32::MAX, i32::MIN, i32::MAX, u32::MAX),
+            u32::MAX
+        );
+    }
+
+    #[test]
+    fn test_variable_fee_rate() {
+        // 2 tick groups of 64 ticks = 128 ticks, 128^2 * 10_000 / 100_000 = 1638.4 -> 1639 (0.16%)
+        assert_eq!(get_variable_fee_rate(10_000, 20_000, 64), 1_639);
+        // Zero control or volatility disables the variable fee
+        assert_eq!(get_variable_fee_rate(0, 20_000, 64), 0);
+        assert_eq!(get_variable_fee_rate(10_000, 0, 64), 0);
+        // Saturates at the max fee rate
+        assert_eq!(
+            get_variable_fee_rate(u32::MAX, u32::MAX, u16::MAX),
+            MAX_FEE_RATE
+        );
+    }
+
+    #[test]
+    fn test_dynamic_fee_rate() {
+        assert_eq!(get_dynamic_fee_rate(3_000, 1_639), 4_639);
+        assert_eq!(get_dynamic_fee_rate(3_000, 0), 3_000);
+        assert_eq!(get_dynamic_fee_rate(9_000, 2_000), MAX_FEE_RATE);
+    }
+}
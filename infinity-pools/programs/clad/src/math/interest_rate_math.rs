@@ -0,0 +1,287 @@
+use crate::{
+    errors::ErrorCode,
+    math::{Q64_RESOLUTION, U256},
+};
+
+// Utilization and interest rates are represented as basis points.
+// Utilization is capped at 100% (10_000 bps).
+pub const MAX_UTILIZATION_BPS: u16 = 10_000;
+
+// Ignore leap years
+pub const SECONDS_PER_YEAR: u64 = 31_536_000;
+
+// Borrow index is a Q64.64 that starts at 1.0 and grows with the accrued interest.
+pub const INITIAL_BORROW_INDEX: u128 = 1 << Q64_RESOLUTION;
+
+//
+// Get the utilization (in bps) of `liquidity_gross` by `liquidity_borrowed`, capped at 100%.
+// Fully utilized if there's no liquidity to borrow from.
+//
+pub fn get_utilization_bps(
+    liquidity_borrowed: u128,
+    liquidity_gross: u128,
+    round_up: bool,
+) -> Result<u16, ErrorCode> {
+    if liquidity_gross == 0 {
+        return Ok(if liquidity_borrowed == 0 { 0 } else { MAX_UTILIZATION_BPS });
+    }
+
+    let numerator = U256::from(liquidity_borrowed) * U256::from(MAX_UTILIZATION_BPS);
+    let denominator = U256::from(liquidity_gross);
+
+    let quotient = numerator / denominator;
+    let utilization = if round_up && !(numerator % denominator).is_zero() {
+        quotient + U256::one()
+    } else {
+        quotient
+    };
+
+    if utilization > U256::from(MAX_UTILIZATION_BPS) {
+        Ok(MAX_UTILIZATION_BPS)
+    } else {
+        Ok(utilization.try_into_u64()? as u16)
+    }
+}
+
+//
+// Get the annual interest rate (in bps) at the given utilization, from a kinked (Aave-style) curve:
+//
+//   utilization <= optimal: base_rate + slope1 * utilization / optimal
+//   utilization >  optimal: base_rate + slope1 + slope2 * (utilization - optimal) / (100% - optimal)
+//
+// The rate saturates at u16::MAX (655.36% annual).
+//
+pub fn get_interest_rate_annual_bps(
+    utilization_bps: u16,
+    base_rate_bps: u16,
+    slope1_bps: u16,
+    optimal_utilization_bps: u16,
+    slope2_bps: u16,
+) -> u16 {
+    let utilization = std::cmp::min(utilization_bps, MAX_UTILIZATION_BPS) as u128;
+    let optimal_utilization = std::cmp::min(optimal_utilization_bps, MAX_UTILIZATION_BPS) as u128;
+
+    let variable_rate = if utilization <= optimal_utilization {
+        // Zero optimal utilization implies zero utilization here, so the variable rate is zero
+        (slope1_bps as u128 * utilization)
+            .checked_div(optimal_utilization)
+            .unwrap_or(0)
+    } else {
+        slope1_bps as u128
+            + slope2_bps as u128 * (utilization - optimal_utilization)
+                / (MAX_UTILIZATION_BPS as u128 - optimal_utilization)
+    };
+
+    let rate = base_rate_bps as u128 + variable_rate;
+
+    if rate > u16::MAX as u128 {
+        u16::MAX
+    } else {
+        rate as u16
+    }
+}
+
+//
+// Get the borrow index after accruing `interest_rate_annual_bps` for `elapsed_seconds` (rounded up).
+//
+// next_index = index * (1 + rate * elapsed / year)
+//
+pub fn get_next_borrow_index(
+    borrow_index: u128,
+    interest_rate_annual_bps: u16,
+    elapsed_seconds: u64,
+) -> Result<u128, ErrorCode> {
+    let numerator = U256::from(borrow_index)
+        * U256::from(interest_rate_annual_bps)
+        * U256::from(elapsed_seconds);
+    let denominator = U256::from(MAX_UTILIZATION_BPS) * U256::from(SECONDS_PER_YEAR);
+
+    let accrued = numerator / denominator;
+    let accrued = if !(numerator % denominator).is_zero() {
+        accrued + U256::one()
+    } else {
+        accrued
+    };
+
+    let next_borrow_index = accrued
+        .checked_add(U256::from(borrow_index))
+        .ok_or(ErrorCode::MultiplicationOverflow)?;
+
+    next_borrow_index.try_into_u128()
+}
+
+//
+// Get the liquidity owed as interest on `liquidity_borrowed` since `borrow_index_checkpoint`
+// (rounded up, in favor of the lenders).
+//
+pub fn get_interest_owed_liquidity(
+    liquidity_borrowed: u128,
+    borrow_index: u128,
+    borrow_index_checkpoint: u128,
+) -> Result<u128, ErrorCode> {
+    if borrow_index_checkpoint == 0 {
+        return Err(ErrorCode::DivideByZero);
+    }
+
+    let index_delta = borrow_index.saturating_sub(borrow_index_checkpoint);
+
+    let numerator = U256::from(liquidity_borrowed) * U256::from(index_delta);
+    let denominator = U256::from(borrow_index_checkpoint);
+
+    let owed = numerator / denominator;
+    let owed = if !(numerator % denominator).is_zero() {
+        owed + U256::one()
+    } else {
+        owed
+    };
+
+    owed.try_into_u128()
+}
+
+#[cfg(test)]
+mod fuzz_tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_interest_rate_monotonic_in_utilization(
+            utilization in 0..MAX_UTILIZATION_BPS,
+            utilization_delta in 1..=MAX_UTILIZATION_BPS,
+            base_rate in 0..u16::MAX,
+            slope1 in 0..u16::MAX,
+            optimal_utilization in 0..=MAX_UTILIZATION_BPS,
+            slope2 in 0..u16::MAX,
+        ) {
+            let next_utilization =
+                std::cmp::min(utilization + utilization_delta, MAX_UTILIZATION_BPS);
+
+            let rate = get_interest_rate_annual_bps(
+                utilization, base_rate, slope1, optimal_utilization, slope2,
+            );
+            let next_rate = get_interest_rate_annual_bps(
+                next_utilization, base_rate, slope1, optimal_utilization, slope2,
+            );
+
+            assert!(rate <= next_rate);
+            assert!(rate >= base_rate);
+        }
+
+        #[test]
+        fn test_utilization_monotonic_in_liquidity_borrowed(
+            liquidity_gross in 1..u128::MAX,
+            liquidity_borrowed in 0..u128::MAX,
+            liquidity_borrowed_delta in 0..u128::MAX,
+            round_up in proptest::bool::ANY,
+        ) {
+            let next_liquidity_borrowed = liquidity_borrowed.saturating_add(liquidity_borrowed_delta);
+
+            let utilization = get_utilization_bps(liquidity_borrowed, liquidity_gross, round_up).unwrap();
+            let next_utilization =
+                get_utilization_bps(next_liquidity_borrowed, liquidity_gross, round_up).unwrap();
+
+            assert!(utilization <= next_utilization);
+            assert!(next_utilization <= MAX_UTILIZATION_BPS);
+        }
+
+        #[test]
+        fn test_interest_owed_monotonic_in_time(
+            liquidity_borrowed in 0..u64::MAX as u128,
+            interest_rate in 0..u16::MAX,
+            elapsed in 0..SECONDS_PER_YEAR,
+            elapsed_delta in 0..SECONDS_PER_YEAR,
+        ) {
+            // Repaying early should never cost more than repaying later
+            let index = get_next_borrow_index(INITIAL_BORROW_INDEX, interest_rate, elapsed).unwrap();
+            let later_index = get_next_borrow_index(index, interest_rate, elapsed_delta).unwrap();
+
+            let owed = get_interest_owed_liquidity(liquidity_borrowed, index, INITIAL_BORROW_INDEX).unwrap();
+            let later_owed =
+                get_interest_owed_liquidity(liquidity_borrowed, later_index, INITIAL_BORROW_INDEX).unwrap();
+
+            assert!(index >= INITIAL_BORROW_INDEX);
+            assert!(later_index >= index);
+            assert!(owed <= later_owed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_interest_rate_math {
+    use super::*;
+
+    #[test]
+    fn test_utilization_bps() {
+        assert_eq!(get_utilization_bps(0, 100, true).unwrap(), 0);
+        assert_eq!(get_utilization_bps(50, 100, true).unwrap(), 5_000);
+        assert_eq!(get_utilization_bps(1, 3, false).unwrap(), 3_333);
+        assert_eq!(get_utilization_bps(1, 3, true).unwrap(), 3_334);
+        assert_eq!(get_utilization_bps(200, 100, true).unwrap(), MAX_UTILIZATION_BPS);
+        assert_eq!(get_utilization_bps(1, 0, true).unwrap(), MAX_UTILIZATION_BPS);
+        assert_eq!(get_utilization_bps(0, 0, true).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_interest_rate_kink() {
+        // 1% base, 4% slope1 up to 80% utilization, 75% slope2 after
+        let rate = |utilization| get_interest_rate_annual_bps(utilization, 100, 400, 8_000, 7_500);
+
+        assert_eq!(rate(0), 100);
+        assert_eq!(rate(4_000), 300);
+        assert_eq!(rate(8_000), 500);
+        assert_eq!(rate(9_000), 4_250);
+        assert_eq!(rate(MAX_UTILIZATION_BPS), 8_000);
+    }
+
+    #[test]
+    fn test_interest_rate_zero_optimal_utilization() {
+        assert_eq!(get_interest_rate_annual_bps(0, 100, 400, 0, 7_500), 100);
+        assert_eq!(get_interest_rate_annual_bps(5_000, 100, 400, 0, 7_500), 4_250);
+    }
+
+    #[test]
+    fn test_next_borrow_index() {
+        // 10% annual for a full year
+        let index = get_next_borrow_index(INITIAL_BORROW_INDEX, 1_000, SECONDS_PER_YEAR).unwrap();
+        assert_eq!(index, INITIAL_BORROW_INDEX + INITIAL_BORROW_INDEX / 10 + 1);
+
+        // No time elapsed or zero rate
+        assert_eq!(
+            get_next_borrow_index(INITIAL_BORROW_INDEX, 1_000, 0).unwrap(),
+            INITIAL_BORROW_INDEX
+        );
+        assert_eq!(
+            get_next_borrow_index(INITIAL_BORROW_INDEX, 0, SECONDS_PER_YEAR).unwrap(),
+            INITIAL_BORROW_INDEX
+        );
+    }
+
+    #[test]
+    fn test_interest_owed_liquidity() {
+        let index = INITIAL_BORROW_INDEX + INITIAL_BORROW_INDEX / 10;
+        assert_eq!(
+            get_interest_owed_liquidity(1_000_000, index, INITIAL_BORROW_INDEX).unwrap(),
+            100_000
+        );
+
+        // Checkpoint taken after the index grew
+        assert_eq!(
+            get_interest_owed_liquidity(1_100_000, index + index / 10, index).unwrap(),
+            110_000
+        );
+
+        assert_eq!(
+            get_interest_owed_liquidity(1_000_000, INITIAL_BORROW_INDEX, INITIAL_BORROW_INDEX)
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_interest_rate_saturates() {
+        assert_eq!(
+            get_interest_rate_annual_bps(MAX_UTILIZATION_BPS, u16::MAX, u16::MAX, 5_000, u16::MAX),
+            u16::MAX
+        );
+    }
+}
@@ -1,5 +1,7 @@
 pub mod bit_math;
 pub mod bn;
+pub mod dynamic_fee_math;
+pub mod interest_rate_math;
 pub mod liquidity_math;
 pub mod swap_math;
 pub mod tick_math;
@@ -8,6 +10,8 @@ pub mod u256_math;
 
 pub use bit_math::*;
 pub use bn::*;
+pub use dynamic_fee_math::*;
+pub use interest_rate_math::*;
 pub use liquidity_math::*;
 pub use swap_math::*;
 pub use tick_math::*;
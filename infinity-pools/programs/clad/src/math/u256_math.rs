@@ -892,6 +892,68 @@ mod fuzz_tests {
                 },
             }
         }
+
+        #[test]
+        fn test_div_remainder(
+            dividend_hi in any::<u128>(),
+            dividend_lo in any::<u128>(),
+            dividend_bits in 0u32..=256,
+            divisor_hi in any::<u128>(),
+            divisor_lo in any::<u128>(),
+            divisor_bits in 1u32..=256,
+        ) {
+            // Truncate the operands to random widths so every word count of the dividend and
+            // divisor is exercised, not just full 256-bit values
+            let other_dividend = truncate(dividend_hi, dividend_lo, dividend_bits);
+            let other_divisor = truncate(divisor_hi, divisor_lo, divisor_bits);
+            prop_assume!(!other_divisor.is_zero());
+
+            let (quotient, remainder) = to_muldiv(other_dividend).div(to_muldiv(other_divisor), true);
+            let (other_quotient, other_remainder) = other_dividend.div_mod(other_divisor);
+
+            assert_equality(quotient, other_quotient);
+            assert_equality(remainder, other_remainder);
+        }
+
+        #[test]
+        fn test_shift_word_right(n_hi in any::<u128>(), n_lo in any::<u128>()) {
+            let n = U256Muldiv::new(n_hi, n_lo);
+            let result = n.shift_word_right();
+
+            let other_n = (U256::from(n_hi) << 128) + U256::from(n_lo);
+            let other_result = other_n >> 64;
+
+            assert_equality(result, other_result);
+        }
+
+        #[test]
+        fn test_shift_full_range(n_hi in any::<u128>(), n_lo in any::<u128>(), shift_amount in 0u32..=320) {
+            let n = U256Muldiv::new(n_hi, n_lo);
+            let other_n = (U256::from(n_hi) << 128) + U256::from(n_lo);
+
+            // Shifts of 256 bits or more clear the value
+            let (other_left, other_right) = if shift_amount >= 256 {
+                (U256::zero(), U256::zero())
+            } else {
+                (other_n << shift_amount, other_n >> shift_amount)
+            };
+
+            assert_equality(n.shift_left(shift_amount), other_left);
+            assert_equality(n.shift_right(shift_amount), other_right);
+        }
+    }
+
+    fn truncate(hi: u128, lo: u128, bits: u32) -> U256 {
+        let n = (U256::from(hi) << 128) + U256::from(lo);
+        if bits == 0 {
+            U256::zero()
+        } else {
+            n >> (256 - bits)
+        }
+    }
+
+    fn to_muldiv(n: U256) -> U256Muldiv {
+        U256Muldiv::new((n >> 128).as_u128(), (n & U256::from(u128::MAX)).as_u128())
     }
 }
 
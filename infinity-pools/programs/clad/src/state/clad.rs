@@ -33,6 +33,8 @@ impl Default for Permissions {
 #[account]
 #[derive(Default, Debug)]
 pub struct Clad {
+    pub admin: Pubkey,
+
     pub permissions: Permissions,
 
     pub protocol_fee_rate: u16,
@@ -53,10 +55,12 @@ impl Clad {
 
     pub fn initialize(
         &mut self,
+        admin: Pubkey,
         permissions: Permissions,
         protocol_fee_rate: u16,
         clad_bump: u8,
     ) -> Result<()> {
+        self.admin = admin;
         self.permissions = permissions;
         self.clad_bump = clad_bump;
 
@@ -65,6 +69,10 @@ impl Clad {
         Ok(())
     }
 
+    pub fn update_permissions(&mut self, permissions: Permissions) {
+        self.permissions = permissions;
+    }
+
     pub fn update_protocol_fee_rate(&mut self, protocol_fee_rate: u16) -> Result<()> {
         if protocol_fee_rate > MAX_PROTOCOL_FEE_RATE {
             return Err(ErrorCode::ProtocolFeeRateMaxExceeded.into());
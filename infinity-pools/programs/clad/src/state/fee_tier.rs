@@ -0,0 +1,92 @@
+use {
+    crate::{errors::ErrorCode, math::MAX_FEE_RATE},
+    anchor_lang::prelude::*,
+};
+
+//
+// Tick spacing & default fee rate that Globalpools can be created with, registered by the Clad
+// admin (one per tick spacing) to keep liquidity from fragmenting across arbitrary combos.
+//
+#[account]
+#[derive(Default, Debug)]
+pub struct FeeTier {
+    pub clad: Pubkey,
+    pub tick_spacing: u16,
+    pub default_fee_rate: u16, // Fee rate of Globalpools created with this tier
+    pub fee_rate_seed: u16,    // Initial default fee rate, kept as the Globalpool address seed
+}
+
+impl FeeTier {
+    pub const LEN: usize = 8 + std::mem::size_of::<FeeTier>();
+
+    pub fn initialize(
+        &mut self,
+        clad: Pubkey,
+        tick_spacing: u16,
+        default_fee_rate: u16,
+    ) -> Result<()> {
+        if tick_spacing == 0 {
+            return Err(ErrorCode::InvalidTickSpacing.into());
+        }
+
+        self.clad = clad;
+        self.tick_spacing = tick_spacing;
+        self.update_default_fee_rate(default_fee_rate)?;
+
+        // Changing the default fee rate must not change the Globalpool addresses of this tier, which
+        // would allow a second Globalpool of the same tokens & tick spacing
+        self.fee_rate_seed = default_fee_rate;
+
+        Ok(())
+    }
+
+    pub fn update_default_fee_rate(&mut self, default_fee_rate: u16) -> Result<()> {
+        if default_fee_rate > MAX_FEE_RATE {
+            return Err(ErrorCode::FeeRateMaxExceeded.into());
+        }
+        self.default_fee_rate = default_fee_rate;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod fee_tier_tests {
+    use super::*;
+
+    #[test]
+    fn test_initialize() {
+        let mut fee_tier = FeeTier::default();
+        let clad = Pubkey::new_unique();
+        fee_tier.initialize(clad, 64, 3_000).unwrap();
+
+        assert_eq!(fee_tier.clad, clad);
+        assert_eq!(fee_tier.tick_spacing, 64);
+        assert_eq!(fee_tier.default_fee_rate, 3_000);
+        assert_eq!(fee_tier.fee_rate_seed, 3_000);
+    }
+
+    #[test]
+    fn test_update_default_fee_rate_keeps_seed() {
+        let mut fee_tier = FeeTier::default();
+        fee_tier.initialize(Pubkey::new_unique(), 64, 3_000).unwrap();
+        fee_tier.update_default_fee_rate(500).unwrap();
+
+        assert_eq!(fee_tier.default_fee_rate, 500);
+        assert_eq!(fee_tier.fee_rate_seed, 3_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "InvalidTickSpacing")]
+    fn test_initialize_zero_tick_spacing() {
+        let mut fee_tier = FeeTier::default();
+        fee_tier.initialize(Pubkey::new_unique(), 0, 3_000).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "FeeRateMaxExceeded")]
+    fn test_update_default_fee_rate_max_exceeded() {
+        let mut fee_tier = FeeTier::default();
+        fee_tier.update_default_fee_rate(MAX_FEE_RATE + 1).unwrap();
+    }
+}
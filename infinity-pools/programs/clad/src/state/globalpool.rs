@@ -2,14 +2,23 @@ use {
     crate::{
         errors::ErrorCode,
         math::{
-            add_liquidity_delta, tick_index_from_sqrt_price, MAX_FEE_RATE, MAX_PROTOCOL_FEE_RATE,
-            MAX_SQRT_PRICE_X64, MIN_SQRT_PRICE_X64, Q64_RESOLUTION,
+            add_liquidity_delta, checked_mul_div_round_up, get_interest_rate_annual_bps,
+            get_next_borrow_index, get_utilization_bps, tick_index_from_sqrt_price,
+            FEE_RATE_MUL_VALUE, INITIAL_BORROW_INDEX, MAX_FEE_RATE, MAX_PROTOCOL_FEE_RATE,
+            MAX_SQRT_PRICE_X64, MAX_UTILIZATION_BPS, MAX_VOLATILITY_REDUCTION_FACTOR_BPS,
+            MIN_SQRT_PRICE_X64, PROTOCOL_FEE_RATE_MUL_VALUE, Q64_RESOLUTION,
         },
+        state::TradePosition,
         util::to_timestamp_u64,
     },
     anchor_lang::prelude::*,
 };
 
+// Number of reward emissions per Globalpool. Each reward adds a Q64.64 growth to every tick, and
+// tick arrays are created through CPI (capped at 10 KiB), so the liquidity borrowed at a tick is
+// kept in its TickInterest account to leave room for three rewards.
+pub const NUM_REWARDS: usize = 3;
+
 #[account]
 #[derive(Default)]
 pub struct Globalpool {
@@ -42,14 +51,14 @@ pub struct Globalpool {
 
     pub token_mint_a: Pubkey,  // 32
     pub token_vault_a: Pubkey, // 32
-    // pub token_price_feed_a: Pubkey,
+    pub token_price_feed_a: Pubkey, // 32
 
     // Q64.64
     pub fee_growth_global_a: u128, // 16
 
     pub token_mint_b: Pubkey,  // 32
     pub token_vault_b: Pubkey, // 32
-    // pub token_price_feed_b: Pubkey,
+    pub token_price_feed_b: Pubkey, // 32
 
     // Q64.64
     pub fee_growth_global_b: u128, // 16
@@ -58,10 +67,118 @@ pub struct Globalpool {
     pub inception_time: u64,
 
     pub fee_authority: Pubkey,
+
+    // Proposed fee authority, must accept before taking over `fee_authority`
+    pub pending_fee_authority: Pubkey,
+
+    // Trade positions whose value over debt falls below this ratio can be liquidated (in bps)
+    pub maintenance_margin_bps: u16,
+    // Portion of the leftover collateral paid to the liquidator (in bps)
+    pub liquidation_bonus_bps: u16,
+
+    // Oracle prices published more than this many seconds ago are rejected
+    pub oracle_stale_seconds: u64,
+    // Max deviation of the pool price from the oracle price for trade positions (in bps)
+    pub oracle_price_band_bps: u16,
+
+    // Kinked interest rate model of loans, by utilization of the borrowed ticks (all in bps)
+    pub interest_base_rate_bps: u16,
+    pub interest_slope1_bps: u16,
+    pub interest_optimal_utilization_bps: u16,
+    pub interest_slope2_bps: u16,
+
+    // If enabled, new trade positions accrue interest by the second instead of paying it upfront
+    pub interest_accrual_enabled: bool,
+
+    // Q64.64, grows with the interest accrued on borrowed liquidity (starts at 1.0)
+    pub borrow_index: u128,
+    pub borrow_index_updated_at: u64,
+
+    // Max duration of trade position loans since opening, including extensions (in seconds)
+    pub max_loan_duration: u64,
+
+    // Program that trade position swaps are routed through (Jupiter by default).
+    // If set to this program, the swaps are routed through the Globalpool itself.
+    pub swap_router: Pubkey,
+
+    // Fee charged on flash loans of the vaults, in hundredths of a bip (10^-6)
+    pub flash_loan_fee_rate: u16,
+    // Amounts lent by the flash loan in progress, repaid (with fee) before the transaction ends
+    pub flash_loan_amount_a: u64,
+    pub flash_loan_amount_b: u64,
+
+    // Liquidity mining rewards, emitted to in-range liquidity by the second
+    pub reward_last_updated_timestamp: u64,
+    pub reward_infos: [GlobalpoolRewardInfo; NUM_REWARDS],
+    // Liquidity of the limit orders pending on the range the price is in. Orders earn no rewards,
+    // so it's left out of the in-range liquidity the rewards are emitted to.
+    pub liquidity_limit_orders: u128,
+
+    // Dynamic fee mode: a variable fee on top of `fee_rate` (the base fee the seeds are keyed on),
+    // growing with the square of the volatility from recent tick movement. Disabled if zero.
+    pub variable_fee_control: u32,
+    // Swaps within the filter period (in seconds) of the last swap keep accumulating volatility
+    // from the same reference. Until the decay period, the reference keeps a portion (in bps) of
+    // the volatility accumulated so far. After it, volatility resets.
+    pub volatility_filter_period: u16,
+    pub volatility_decay_period: u16,
+    pub volatility_reduction_factor_bps: u16,
+    pub max_volatility_accumulator: u32,
+    pub volatility_info: GlobalpoolVolatilityInfo,
+}
+
+#[derive(Copy, Clone, AnchorSerialize, AnchorDeserialize, Default, Debug, PartialEq)]
+pub struct GlobalpoolRewardInfo {
+    pub mint: Pubkey,  // Reward token mint
+    pub vault: Pubkey, // Reward token vault, owned by the Globalpool
+    pub authority: Pubkey, // Authority that sets the emissions
+    // Q64.64, reward tokens emitted per second
+    pub emissions_per_second_x64: u128,
+    // Q64.64, reward tokens emitted per unit of in-range liquidity since the reward was initialized
+    pub growth_global_x64: u128,
+}
+
+impl GlobalpoolRewardInfo {
+    pub fn initialized(&self) -> bool {
+        self.mint.ne(&Pubkey::default())
+    }
+}
+
+#[derive(Copy, Clone, AnchorSerialize, AnchorDeserialize, Default, Debug, PartialEq)]
+pub struct GlobalpoolVolatilityInfo {
+    // Volatility accumulated from tick movement, in tick groups scaled by
+    // VOLATILITY_ACCUMULATOR_SCALE_FACTOR
+    pub volatility_accumulator: u32,
+    // Volatility carried over from previous swaps
+    pub volatility_reference: u32,
+    // Tick group that tick movement is measured from
+    pub tick_group_index_reference: i32,
+    pub last_swap_timestamp: u64,
 }
 
 impl Globalpool {
     pub const LEN: usize = 8 + std::mem::size_of::<Globalpool>() + 384;
+
+    pub const DEFAULT_MAINTENANCE_MARGIN_BPS: u16 = 10_500; // 105%
+    pub const MIN_MAINTENANCE_MARGIN_BPS: u16 = 10_000; // 100%
+    pub const MAX_MAINTENANCE_MARGIN_BPS: u16 = 20_000; // 200%
+    pub const DEFAULT_LIQUIDATION_BONUS_BPS: u16 = 1_000; // 10%
+    pub const MAX_LIQUIDATION_BONUS_BPS: u16 = 10_000; // 100%
+
+    // NOTE: 30 seconds for mainnet, but keep it long for localnet testing
+    pub const DEFAULT_ORACLE_STALE_SECONDS: u64 = 100_000;
+    pub const DEFAULT_ORACLE_PRICE_BAND_BPS: u16 = 500; // 5%
+    pub const MAX_ORACLE_PRICE_BAND_BPS: u16 = 10_000; // 100%
+
+    pub const DEFAULT_INTEREST_BASE_RATE_BPS: u16 = 100; // 1%
+    pub const DEFAULT_INTEREST_SLOPE1_BPS: u16 = 400; // 4%
+    pub const DEFAULT_INTEREST_OPTIMAL_UTILIZATION_BPS: u16 = 8_000; // 80%
+    pub const DEFAULT_INTEREST_SLOPE2_BPS: u16 = 7_500; // 75%
+
+    pub const DEFAULT_MAX_LOAN_DURATION: u64 = 864_000; // 10 days
+
+    pub const DEFAULT_FLASH_LOAN_FEE_RATE: u16 = 900; // 0.09%
+
     pub fn seeds<'a>(&self) -> [&[u8]; 6] {
         [
             &b"globalpool"[..],
@@ -79,14 +196,13 @@ impl Globalpool {
         tick_spacing: u16,
         sqrt_price: u128,
         fee_rate: u16,
+        fee_rate_seed: u16,
         protocol_fee_rate: u16,
         fee_authority: Pubkey,
         token_mint_a: Pubkey,
         token_vault_a: Pubkey,
         token_mint_b: Pubkey,
         token_vault_b: Pubkey,
-        // token_price_feed_a: Pubkey,
-        // token_price_feed_b: Pubkey,
     ) -> Result<()> {
         if token_mint_a.ge(&token_mint_b) {
             return Err(ErrorCode::InvalidTokenMintOrder.into());
@@ -105,16 +221,10 @@ impl Globalpool {
         self.tick_spacing = tick_spacing;
         self.tick_spacing_seed = self.tick_spacing.to_le_bytes();
 
-        if fee_rate > MAX_FEE_RATE {
-            return Err(ErrorCode::FeeRateMaxExceeded.into());
-        }
-        self.fee_rate = fee_rate;
-        self.fee_rate_seed = self.fee_rate.to_le_bytes();
+        self.update_fee_rate(fee_rate)?;
+        self.fee_rate_seed = fee_rate_seed.to_le_bytes();
 
-        if protocol_fee_rate > MAX_PROTOCOL_FEE_RATE {
-            return Err(ErrorCode::ProtocolFeeRateMaxExceeded.into());
-        }
-        self.protocol_fee_rate = protocol_fee_rate;
+        self.update_protocol_fee_rate(protocol_fee_rate)?;
 
         self.liquidity_available = 0;
         self.liquidity_borrowed = 0;
@@ -127,17 +237,316 @@ impl Globalpool {
 
         self.token_mint_a = token_mint_a;
         self.token_vault_a = token_vault_a;
-        // self.token_price_feed_a = token_price_feed_a;
+        self.token_price_feed_a = Pubkey::default();
         self.fee_growth_global_a = 0;
         self.fee_authority = fee_authority;
+        self.pending_fee_authority = Pubkey::default();
+
+        self.maintenance_margin_bps = Globalpool::DEFAULT_MAINTENANCE_MARGIN_BPS;
+        self.liquidation_bonus_bps = Globalpool::DEFAULT_LIQUIDATION_BONUS_BPS;
+
+        self.oracle_stale_seconds = Globalpool::DEFAULT_ORACLE_STALE_SECONDS;
+        self.oracle_price_band_bps = Globalpool::DEFAULT_ORACLE_PRICE_BAND_BPS;
+
+        self.interest_base_rate_bps = Globalpool::DEFAULT_INTEREST_BASE_RATE_BPS;
+        self.interest_slope1_bps = Globalpool::DEFAULT_INTEREST_SLOPE1_BPS;
+        self.interest_optimal_utilization_bps = Globalpool::DEFAULT_INTEREST_OPTIMAL_UTILIZATION_BPS;
+        self.interest_slope2_bps = Globalpool::DEFAULT_INTEREST_SLOPE2_BPS;
 
         self.token_mint_b = token_mint_b;
         self.token_vault_b = token_vault_b;
-        // self.token_price_feed_b = token_price_feed_b;
+        self.token_price_feed_b = Pubkey::default();
         self.fee_growth_global_b = 0;
 
         self.inception_time = to_timestamp_u64(Clock::get()?.unix_timestamp)?;
 
+        self.interest_accrual_enabled = false;
+        self.borrow_index = INITIAL_BORROW_INDEX;
+        self.borrow_index_updated_at = self.inception_time;
+
+        self.max_loan_duration = Globalpool::DEFAULT_MAX_LOAN_DURATION;
+
+        self.swap_router = crate::jupiter::ID;
+
+        self.flash_loan_fee_rate = Globalpool::DEFAULT_FLASH_LOAN_FEE_RATE;
+        self.flash_loan_amount_a = 0;
+        self.flash_loan_amount_b = 0;
+
+        self.reward_last_updated_timestamp = self.inception_time;
+
+        self.variable_fee_control = 0;
+        self.volatility_filter_period = 0;
+        self.volatility_decay_period = 0;
+        self.volatility_reduction_factor_bps = 0;
+        self.max_volatility_accumulator = 0;
+        self.volatility_info = GlobalpoolVolatilityInfo::default();
+
+        Ok(())
+    }
+
+    /// Update the fee rate. The PDA seed keeps the fee rate the pool was created with.
+    pub fn update_fee_rate(&mut self, fee_rate: u16) -> Result<()> {
+        if fee_rate > MAX_FEE_RATE {
+            return Err(ErrorCode::FeeRateMaxExceeded.into());
+        }
+        self.fee_rate = fee_rate;
+
+        Ok(())
+    }
+
+    /// Update the dynamic fee config. Zero `variable_fee_control` disables the dynamic fee.
+    /// Accumulated volatility is reset, as it was measured under the previous config.
+    pub fn update_dynamic_fee_config(
+        &mut self,
+        variable_fee_control: u32,
+        volatility_filter_period: u16,
+        volatility_decay_period: u16,
+        volatility_reduction_factor_bps: u16,
+        max_volatility_accumulator: u32,
+    ) -> Result<()> {
+        if variable_fee_control > 0
+            && (volatility_filter_period >= volatility_decay_period
+                || volatility_reduction_factor_bps > MAX_VOLATILITY_REDUCTION_FACTOR_BPS)
+        {
+            return Err(ErrorCode::InvalidDynamicFeeConfig.into());
+        }
+        self.variable_fee_control = variable_fee_control;
+        self.volatility_filter_period = volatility_filter_period;
+        self.volatility_decay_period = volatility_decay_period;
+        self.volatility_reduction_factor_bps = volatility_reduction_factor_bps;
+        self.max_volatility_accumulator = max_volatility_accumulator;
+        self.volatility_info = GlobalpoolVolatilityInfo::default();
+
+        Ok(())
+    }
+
+    pub fn is_dynamic_fee_enabled(&self) -> bool {
+        self.variable_fee_control > 0
+    }
+
+    pub fn update_protocol_fee_rate(&mut self, protocol_fee_rate: u16) -> Result<()> {
+        if protocol_fee_rate > MAX_PROTOCOL_FEE_RATE {
+            return Err(ErrorCode::ProtocolFeeRateMaxExceeded.into());
+        }
+        self.protocol_fee_rate = protocol_fee_rate;
+
+        Ok(())
+    }
+
+    pub fn update_pending_fee_authority(&mut self, pending_fee_authority: Pubkey) {
+        self.pending_fee_authority = pending_fee_authority;
+    }
+
+    pub fn accept_fee_authority(&mut self) {
+        self.fee_authority = self.pending_fee_authority;
+        self.pending_fee_authority = Pubkey::default();
+    }
+
+    pub fn update_liquidation_config(
+        &mut self,
+        maintenance_margin_bps: u16,
+        liquidation_bonus_bps: u16,
+    ) -> Result<()> {
+        if maintenance_margin_bps < Globalpool::MIN_MAINTENANCE_MARGIN_BPS
+            || maintenance_margin_bps > Globalpool::MAX_MAINTENANCE_MARGIN_BPS
+        {
+            return Err(ErrorCode::InvalidMaintenanceMargin.into());
+        }
+        if liquidation_bonus_bps > Globalpool::MAX_LIQUIDATION_BONUS_BPS {
+            return Err(ErrorCode::LiquidationBonusMaxExceeded.into());
+        }
+        self.maintenance_margin_bps = maintenance_margin_bps;
+        self.liquidation_bonus_bps = liquidation_bonus_bps;
+
+        Ok(())
+    }
+
+    /// Pools without registered price feeds skip the oracle price band checks.
+    pub fn has_price_feeds(&self) -> bool {
+        self.token_price_feed_a != Pubkey::default() && self.token_price_feed_b != Pubkey::default()
+    }
+
+    pub fn update_price_feeds(
+        &mut self,
+        token_price_feed_a: Pubkey,
+        token_price_feed_b: Pubkey,
+        oracle_stale_seconds: u64,
+        oracle_price_band_bps: u16,
+    ) -> Result<()> {
+        if oracle_price_band_bps > Globalpool::MAX_ORACLE_PRICE_BAND_BPS {
+            return Err(ErrorCode::OraclePriceBandMaxExceeded.into());
+        }
+        self.token_price_feed_a = token_price_feed_a;
+        self.token_price_feed_b = token_price_feed_b;
+        self.oracle_stale_seconds = oracle_stale_seconds;
+        self.oracle_price_band_bps = oracle_price_band_bps;
+
+        Ok(())
+    }
+
+    /// Update the interest rate model, accruing the borrow index first so that the time elapsed
+    /// until `timestamp` is charged at the previous rate.
+    pub fn update_interest_rate_model(
+        &mut self,
+        base_rate_bps: u16,
+        slope1_bps: u16,
+        optimal_utilization_bps: u16,
+        slope2_bps: u16,
+        timestamp: u64,
+    ) -> Result<()> {
+        if optimal_utilization_bps == 0 || optimal_utilization_bps > MAX_UTILIZATION_BPS {
+            return Err(ErrorCode::InvalidInterestRateModel.into());
+        }
+        self.update_borrow_index(timestamp)?;
+        self.interest_base_rate_bps = base_rate_bps;
+        self.interest_slope1_bps = slope1_bps;
+        self.interest_optimal_utilization_bps = optimal_utilization_bps;
+        self.interest_slope2_bps = slope2_bps;
+
+        Ok(())
+    }
+
+    pub fn update_max_loan_duration(&mut self, max_loan_duration: u64) -> Result<()> {
+        if max_loan_duration < TradePosition::MIN_LOAN_DURATION {
+            return Err(ErrorCode::InvalidLoanDuration.into());
+        }
+        self.max_loan_duration = max_loan_duration;
+
+        Ok(())
+    }
+
+    pub fn update_swap_router(&mut self, swap_router: Pubkey) {
+        self.swap_router = swap_router;
+    }
+
+    pub fn is_swap_routed_internally(&self) -> bool {
+        self.swap_router == crate::ID
+    }
+
+    pub fn update_flash_loan_fee_rate(&mut self, flash_loan_fee_rate: u16) -> Result<()> {
+        if flash_loan_fee_rate > MAX_FEE_RATE {
+            return Err(ErrorCode::FeeRateMaxExceeded.into());
+        }
+        self.flash_loan_fee_rate = flash_loan_fee_rate;
+
+        Ok(())
+    }
+
+    /// Fee of a flash loan of `amount`, rounded up.
+    pub fn flash_loan_fee(&self, amount: u64) -> Result<u64> {
+        let fee = checked_mul_div_round_up(
+            amount as u128,
+            self.flash_loan_fee_rate as u128,
+            FEE_RATE_MUL_VALUE,
+        )?;
+
+        Ok(u64::try_from(fee).map_err(|_| ErrorCode::AmountCalcOverflow)?)
+    }
+
+    pub fn is_flash_loan_in_progress(&self) -> bool {
+        self.flash_loan_amount_a > 0 || self.flash_loan_amount_b > 0
+    }
+
+    pub fn start_flash_loan(&mut self, amount_a: u64, amount_b: u64) -> Result<()> {
+        if self.is_flash_loan_in_progress() {
+            return Err(ErrorCode::FlashLoanInProgress.into());
+        }
+        if amount_a == 0 && amount_b == 0 {
+            return Err(ErrorCode::ZeroBorrowableAmount.into());
+        }
+        self.flash_loan_amount_a = amount_a;
+        self.flash_loan_amount_b = amount_b;
+
+        Ok(())
+    }
+
+    /// Ends the flash loan in progress and splits its fees between the protocol and LPs, like swap
+    /// fees. Without liquidity in range, LP fees are owed to the protocol.
+    pub fn update_after_flash_loan(&mut self, fee_amount_a: u64, fee_amount_b: u64) -> Result<()> {
+        if !self.is_flash_loan_in_progress() {
+            return Err(ErrorCode::FlashLoanNotInProgress.into());
+        }
+        self.flash_loan_amount_a = 0;
+        self.flash_loan_amount_b = 0;
+
+        let (protocol_fee_a, fee_growth_a) = self.split_flash_loan_fee(fee_amount_a);
+        let (protocol_fee_b, fee_growth_b) = self.split_flash_loan_fee(fee_amount_b);
+
+        self.protocol_fee_owed_a = self
+            .protocol_fee_owed_a
+            .checked_add(protocol_fee_a)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
+        self.protocol_fee_owed_b = self
+            .protocol_fee_owed_b
+            .checked_add(protocol_fee_b)
+            .ok_or(ErrorCode::AmountCalcOverflow)?;
+        self.fee_growth_global_a = self.fee_growth_global_a.wrapping_add(fee_growth_a);
+        self.fee_growth_global_b = self.fee_growth_global_b.wrapping_add(fee_growth_b);
+
+        Ok(())
+    }
+
+    fn split_flash_loan_fee(&self, fee_amount: u64) -> (u64, u128) {
+        if self.liquidity_available == 0 {
+            return (fee_amount, 0);
+        }
+
+        let protocol_fee = ((fee_amount as u128) * (self.protocol_fee_rate as u128)
+            / PROTOCOL_FEE_RATE_MUL_VALUE) as u64;
+        let lp_fee = fee_amount - protocol_fee;
+
+        (
+            protocol_fee,
+            ((lp_fee as u128) << Q64_RESOLUTION) / self.liquidity_available,
+        )
+    }
+
+    /// Enable or disable interest accrual for new trade positions, accruing the borrow index first
+    /// so that the time elapsed until `timestamp` is charged at the current rate.
+    pub fn update_interest_accrual_enabled(
+        &mut self,
+        interest_accrual_enabled: bool,
+        timestamp: u64,
+    ) -> Result<()> {
+        self.update_borrow_index(timestamp)?;
+        self.interest_accrual_enabled = interest_accrual_enabled;
+
+        Ok(())
+    }
+
+    /// Annual interest rate of the pool's interest rate model, at the utilization of all liquidity
+    /// (available and borrowed) by borrowed liquidity.
+    pub fn interest_rate_annual_bps(&self) -> Result<u16> {
+        let liquidity_total = self
+            .liquidity_available
+            .saturating_add(self.liquidity_borrowed);
+        let utilization = get_utilization_bps(self.liquidity_borrowed, liquidity_total, true)?;
+
+        Ok(get_interest_rate_annual_bps(
+            utilization,
+            self.interest_base_rate_bps,
+            self.interest_slope1_bps,
+            self.interest_optimal_utilization_bps,
+            self.interest_slope2_bps,
+        ))
+    }
+
+    /// Accrue interest on the borrow index since the last update. Must be called before the
+    /// borrowed or available liquidity changes, so that the elapsed time accrues at the previous
+    /// utilization.
+    pub fn update_borrow_index(&mut self, timestamp: u64) -> Result<()> {
+        let elapsed_seconds = timestamp.saturating_sub(self.borrow_index_updated_at);
+        if elapsed_seconds == 0 {
+            return Ok(());
+        }
+
+        self.borrow_index = get_next_borrow_index(
+            self.borrow_index,
+            self.interest_rate_annual_bps()?,
+            elapsed_seconds,
+        )?;
+        self.borrow_index_updated_at = timestamp;
+
         Ok(())
     }
 
@@ -145,18 +554,93 @@ impl Globalpool {
         self.liquidity_available = liquidity;
     }
 
+    /// Initialize the reward at `index`. Rewards are initialized in order.
+    pub fn initialize_reward(
+        &mut self,
+        index: usize,
+        mint: Pubkey,
+        vault: Pubkey,
+        authority: Pubkey,
+    ) -> Result<()> {
+        if index >= NUM_REWARDS {
+            return Err(ErrorCode::InvalidRewardIndex.into());
+        }
+
+        let lowest_index = match self.reward_infos.iter().position(|r| !r.initialized()) {
+            Some(lowest_index) => lowest_index,
+            None => return Err(ErrorCode::InvalidRewardIndex.into()),
+        };
+        if lowest_index != index {
+            return Err(ErrorCode::InvalidRewardIndex.into());
+        }
+
+        self.reward_infos[index].mint = mint;
+        self.reward_infos[index].vault = vault;
+        self.reward_infos[index].authority = authority;
+
+        Ok(())
+    }
+
+    /// Update the reward growths up to `timestamp`, before setting the emissions of the reward at
+    /// `index`.
+    pub fn update_emissions(
+        &mut self,
+        index: usize,
+        reward_infos: [GlobalpoolRewardInfo; NUM_REWARDS],
+        timestamp: u64,
+        emissions_per_second_x64: u128,
+    ) -> Result<()> {
+        if index >= NUM_REWARDS {
+            return Err(ErrorCode::InvalidRewardIndex.into());
+        }
+        self.update_rewards(reward_infos, timestamp);
+        self.reward_infos[index].emissions_per_second_x64 = emissions_per_second_x64;
+
+        Ok(())
+    }
+
+    pub fn update_rewards(
+        &mut self,
+        reward_infos: [GlobalpoolRewardInfo; NUM_REWARDS],
+        timestamp: u64,
+    ) {
+        self.reward_last_updated_timestamp = timestamp;
+        self.reward_infos = reward_infos;
+    }
+
+    pub fn update_rewards_and_liquidity(
+        &mut self,
+        reward_infos: [GlobalpoolRewardInfo; NUM_REWARDS],
+        liquidity: u128,
+        timestamp: u64,
+    ) -> Result<()> {
+        self.update_borrow_index(timestamp)?;
+        self.update_rewards(reward_infos, timestamp);
+        self.liquidity_available = liquidity;
+
+        Ok(())
+    }
+
     pub fn update_after_swap(
         &mut self,
         liquidity_available: u128,
+        liquidity_limit_orders: u128,
         tick_index: i32,
         sqrt_price: u128,
         fee_growth_global: u128,
+        reward_infos: [GlobalpoolRewardInfo; NUM_REWARDS],
+        volatility_info: GlobalpoolVolatilityInfo,
         protocol_fee: u64,
         is_token_fee_in_a: bool,
-    ) {
+        timestamp: u64,
+    ) -> Result<()> {
+        self.update_borrow_index(timestamp)?;
+        self.update_rewards(reward_infos, timestamp);
+        self.volatility_info = volatility_info;
         self.tick_current_index = tick_index;
         self.sqrt_price = sqrt_price;
         self.liquidity_available = liquidity_available;
+        self.liquidity_limit_orders = liquidity_limit_orders;
         if is_token_fee_in_a {
             // Add fees taken via a
             self.fee_growth_global_a = fee_growth_global;
@@ -166,6 +650,8 @@ impl Globalpool {
             self.fee_growth_global_b = fee_growth_global;
             self.protocol_fee_owed_b += protocol_fee;
         }
+
+        Ok(())
     }
 
     // NOTE: Follows the calculation from https://github.com/orca-so/whirlpools/blob/main/programs/whirlpool/src/manager/swap_manager.rs#L217-L219
@@ -183,7 +669,28 @@ impl Globalpool {
         liquidity_delta: i128,
         interest_amount: u64,
         is_token_fee_in_a: bool,
-    ) {
+    ) -> Result<()> {
+        self.update_fee_growth_from_interest(interest_amount, is_token_fee_in_a);
+
+        // Update the amount AFTER interest amount modification (above)
+        // liquidity_delta = borrowed (positive) or repaid (negative) amount of liquidity_u128
+        msg!("liquidity_delta: {}", liquidity_delta);
+        msg!("liquidity_available: {}", self.liquidity_available);
+        msg!("liquidity_borrowed: {}", self.liquidity_borrowed);
+
+        // TODO: Only add/sub delta if it's in the same tick array as the current tick index (where liquidity_available is calculated)
+
+        // self.liquidity_available =
+        //     add_liquidity_delta(self.liquidity_available, -liquidity_delta).unwrap();
+
+        // Total liquidity borrowed by open trade positions, used for the pool's utilization
+        self.liquidity_borrowed = add_liquidity_delta(self.liquidity_borrowed, liquidity_delta)?;
+
+        Ok(())
+    }
+
+    /// Add interest paid by trade positions to the pool fee growth (for LP payout).
+    pub fn update_fee_growth_from_interest(&mut self, interest_amount: u64, is_token_fee_in_a: bool) {
         if interest_amount > 0 {
             let liquidity_available = if self.liquidity_available > 0 {
                 self.liquidity_available
@@ -209,19 +716,6 @@ impl Globalpool {
                     .unwrap();
             }
         }
-
-        // Update the amount AFTER interest amount modification (above)
-        // liquidity_delta = borrowed (positive) or repaid (negative) amount of liquidity_u128
-        msg!("liquidity_delta: {}", liquidity_delta);
-        msg!("liquidity_available: {}", self.liquidity_available);
-        msg!("liquidity_borrowed: {}", self.liquidity_borrowed);
-
-        // TODO: Only add/sub delta if it's in the same tick array as the current tick index (where liquidity_available is calculated)
-
-        // self.liquidity_available =
-        //     add_liquidity_delta(self.liquidity_available, -liquidity_delta).unwrap();
-        // self.liquidity_borrowed =
-        //     add_liquidity_delta(self.liquidity_borrowed, liquidity_delta).unwrap();
     }
 
     pub fn reset_protocol_fees_owed(&mut self) {
@@ -306,3 +800,349 @@ pub mod globalpool_builder {
         }
     }
 }
+
+#[cfg(test)]
+mod update_fee_tests {
+    use {
+        super::*,
+        crate::state::globalpool_builder::GlobalpoolBuilder,
+    };
+
+    #[test]
+    fn test_update_fee_rate_keeps_seed() {
+        let mut globalpool = GlobalpoolBuilder::new().fee_rate(3000).build();
+        globalpool.fee_rate_seed = globalpool.fee_rate.to_le_bytes();
+
+        globalpool.update_fee_rate(500).unwrap();
+        assert_eq!(globalpool.fee_rate, 500);
+        assert_eq!(globalpool.fee_rate_seed, 3000u16.to_le_bytes());
+    }
+
+    #[test]
+    #[should_panic(expected = "FeeRateMaxExceeded")]
+    fn test_update_fee_rate_max_exceeded() {
+        let mut globalpool = GlobalpoolBuilder::new().build();
+        globalpool.update_fee_rate(MAX_FEE_RATE + 1).unwrap();
+    }
+
+    #[test]
+    fn test_update_protocol_fee_rate() {
+        let mut globalpool = GlobalpoolBuilder::new().protocol_fee_rate(300).build();
+        globalpool
+            .update_protocol_fee_rate(MAX_PROTOCOL_FEE_RATE)
+            .unwrap();
+        assert_eq!(globalpool.protocol_fee_rate, MAX_PROTOCOL_FEE_RATE);
+    }
+
+    #[test]
+    #[should_panic(expected = "ProtocolFeeRateMaxExceeded")]
+    fn test_update_protocol_fee_rate_max_exceeded() {
+        let mut globalpool = GlobalpoolBuilder::new().build();
+        globalpool
+            .update_protocol_fee_rate(MAX_PROTOCOL_FEE_RATE + 1)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_accept_fee_authority() {
+        let mut globalpool = GlobalpoolBuilder::new().build();
+        let fee_authority = Pubkey::new_unique();
+        globalpool.fee_authority = fee_authority;
+
+        let new_fee_authority = Pubkey::new_unique();
+        globalpool.update_pending_fee_authority(new_fee_authority);
+        assert_eq!(globalpool.fee_authority, fee_authority);
+        assert_eq!(globalpool.pending_fee_authority, new_fee_authority);
+
+        globalpool.accept_fee_authority();
+        assert_eq!(globalpool.fee_authority, new_fee_authority);
+        assert_eq!(globalpool.pending_fee_authority, Pubkey::default());
+    }
+
+    #[test]
+    fn test_update_liquidation_config() {
+        let mut globalpool = GlobalpoolBuilder::new().build();
+        globalpool.update_liquidation_config(11_000, 500).unwrap();
+        assert_eq!(globalpool.maintenance_margin_bps, 11_000);
+        assert_eq!(globalpool.liquidation_bonus_bps, 500);
+    }
+
+    #[test]
+    #[should_panic(expected = "LiquidationBonusMaxExceeded")]
+    fn test_update_liquidation_config_bonus_max_exceeded() {
+        let mut globalpool = GlobalpoolBuilder::new().build();
+        globalpool
+            .update_liquidation_config(11_000, Globalpool::MAX_LIQUIDATION_BONUS_BPS + 1)
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "InvalidMaintenanceMargin")]
+    fn test_update_liquidation_config_margin_below_min() {
+        let mut globalpool = GlobalpoolBuilder::new().build();
+        globalpool
+            .update_liquidation_config(Globalpool::MIN_MAINTENANCE_MARGIN_BPS - 1, 500)
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "InvalidMaintenanceMargin")]
+    fn test_update_liquidation_config_margin_above_max() {
+        let mut globalpool = GlobalpoolBuilder::new().build();
+        globalpool
+            .update_liquidation_config(Globalpool::MAX_MAINTENANCE_MARGIN_BPS + 1, 500)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_update_price_feeds() {
+        let mut globalpool = GlobalpoolBuilder::new().build();
+        let token_price_feed_a = Pubkey::new_unique();
+        let token_price_feed_b = Pubkey::new_unique();
+
+        assert!(!globalpool.has_price_feeds());
+
+        globalpool
+            .update_price_feeds(token_price_feed_a, token_price_feed_b, 30, 100)
+            .unwrap();
+        assert!(globalpool.has_price_feeds());
+        assert_eq!(globalpool.token_price_feed_a, token_price_feed_a);
+        assert_eq!(globalpool.token_price_feed_b, token_price_feed_b);
+        assert_eq!(globalpool.oracle_stale_seconds, 30);
+        assert_eq!(globalpool.oracle_price_band_bps, 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "OraclePriceBandMaxExceeded")]
+    fn test_update_price_feeds_band_max_exceeded() {
+        let mut globalpool = GlobalpoolBuilder::new().build();
+        globalpool
+            .update_price_feeds(
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                30,
+                Globalpool::MAX_ORACLE_PRICE_BAND_BPS + 1,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_update_interest_rate_model() {
+        let mut globalpool = GlobalpoolBuilder::new().build();
+        globalpool
+            .update_interest_rate_model(200, 500, 9_000, 10_000, 0)
+            .unwrap();
+        assert_eq!(globalpool.interest_base_rate_bps, 200);
+        assert_eq!(globalpool.interest_slope1_bps, 500);
+        assert_eq!(globalpool.interest_optimal_utilization_bps, 9_000);
+        assert_eq!(globalpool.interest_slope2_bps, 10_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "InvalidInterestRateModel")]
+    fn test_update_interest_rate_model_zero_optimal_utilization() {
+        let mut globalpool = GlobalpoolBuilder::new().build();
+        globalpool
+            .update_interest_rate_model(200, 500, 0, 10_000, 0)
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "InvalidInterestRateModel")]
+    fn test_update_interest_rate_model_optimal_utilization_exceeded() {
+        let mut globalpool = GlobalpoolBuilder::new().build();
+        globalpool
+            .update_interest_rate_model(200, 500, MAX_UTILIZATION_BPS + 1, 10_000, 0)
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod borrow_index_tests {
+    use {
+        super::*,
+        crate::{math::SECONDS_PER_YEAR, state::globalpool_builder::GlobalpoolBuilder},
+    };
+
+    fn globalpool_with_interest_rate_model() -> Globalpool {
+        let mut globalpool = GlobalpoolBuilder::new().liquidity(1_000_000).build();
+        globalpool.liquidity_borrowed = 1_000_000;
+        globalpool.borrow_index = INITIAL_BORROW_INDEX;
+        globalpool
+            .update_interest_rate_model(100, 400, 8_000, 7_500, 0)
+            .unwrap();
+        globalpool
+    }
+
+    #[test]
+    fn test_interest_rate_at_pool_utilization() {
+        let globalpool = globalpool_with_interest_rate_model();
+        // 50% utilized: 1% + 4% * 50 / 80
+        assert_eq!(globalpool.interest_rate_annual_bps().unwrap(), 350);
+    }
+
+    #[test]
+    fn test_update_borrow_index() {
+        let mut globalpool = globalpool_with_interest_rate_model();
+
+        globalpool.update_borrow_index(SECONDS_PER_YEAR).unwrap();
+        assert_eq!(globalpool.borrow_index_updated_at, SECONDS_PER_YEAR);
+        assert_eq!(
+            globalpool.borrow_index,
+            get_next_borrow_index(INITIAL_BORROW_INDEX, 350, SECONDS_PER_YEAR).unwrap()
+        );
+
+        // No time elapsed
+        let borrow_index = globalpool.borrow_index;
+        globalpool.update_borrow_index(SECONDS_PER_YEAR).unwrap();
+        assert_eq!(globalpool.borrow_index, borrow_index);
+    }
+
+    #[test]
+    fn test_update_interest_rate_model_keeps_accrued_borrow_index() {
+        let mut globalpool = globalpool_with_interest_rate_model();
+
+        // The year elapsed accrues at the previous rate of 3.5%
+        globalpool
+            .update_interest_rate_model(1_000, 400, 8_000, 7_500, SECONDS_PER_YEAR)
+            .unwrap();
+        let borrow_index =
+            get_next_borrow_index(INITIAL_BORROW_INDEX, 350, SECONDS_PER_YEAR).unwrap();
+        assert_eq!(globalpool.borrow_index, borrow_index);
+        assert_eq!(globalpool.borrow_index_updated_at, SECONDS_PER_YEAR);
+
+        globalpool.update_borrow_index(SECONDS_PER_YEAR).unwrap();
+        assert_eq!(globalpool.borrow_index, borrow_index);
+
+        // Only the time after the change accrues at the new rate of 12.5%
+        globalpool
+            .update_borrow_index(2 * SECONDS_PER_YEAR)
+            .unwrap();
+        assert_eq!(
+            globalpool.borrow_index,
+            get_next_borrow_index(borrow_index, 1_250, SECONDS_PER_YEAR).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_update_liquidity_keeps_accrued_borrow_index() {
+        let mut globalpool = globalpool_with_interest_rate_model();
+        let reward_infos = globalpool.reward_infos;
+
+        // The year elapsed accrues at the previous utilization of 50%
+        globalpool
+            .update_rewards_and_liquidity(reward_infos, 3_000_000, SECONDS_PER_YEAR)
+            .unwrap();
+        let borrow_index =
+            get_next_borrow_index(INITIAL_BORROW_INDEX, 350, SECONDS_PER_YEAR).unwrap();
+        assert_eq!(globalpool.borrow_index, borrow_index);
+
+        // 25% utilized after the change: 1% + 4% * 25 / 80
+        globalpool
+            .update_borrow_index(2 * SECONDS_PER_YEAR)
+            .unwrap();
+        assert_eq!(
+            globalpool.borrow_index,
+            get_next_borrow_index(borrow_index, 225, SECONDS_PER_YEAR).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_update_interest_accrual_enabled_accrues_borrow_index() {
+        let mut globalpool = globalpool_with_interest_rate_model();
+
+        globalpool
+            .update_interest_accrual_enabled(true, SECONDS_PER_YEAR)
+            .unwrap();
+        assert!(globalpool.interest_accrual_enabled);
+        assert_eq!(globalpool.borrow_index_updated_at, SECONDS_PER_YEAR);
+        assert_eq!(
+            globalpool.borrow_index,
+            get_next_borrow_index(INITIAL_BORROW_INDEX, 350, SECONDS_PER_YEAR).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_update_after_loan_tracks_liquidity_borrowed() {
+        let mut globalpool = globalpool_with_interest_rate_model();
+
+        globalpool.update_after_loan(500, 0, true).unwrap();
+        assert_eq!(globalpool.liquidity_borrowed, 1_000_500);
+
+        globalpool.update_after_loan(-1_000_500, 0, true).unwrap();
+        assert_eq!(globalpool.liquidity_borrowed, 0);
+    }
+}
+
+#[cfg(test)]
+mod flash_loan_tests {
+    use {
+        super::*,
+        crate::state::globalpool_builder::GlobalpoolBuilder,
+    };
+
+    fn globalpool_with_flash_loan_fee() -> Globalpool {
+        let mut globalpool = GlobalpoolBuilder::new()
+            .liquidity(1 << 32)
+            .protocol_fee_rate(2_500)
+            .build();
+        globalpool.update_flash_loan_fee_rate(900).unwrap();
+        globalpool
+    }
+
+    #[test]
+    fn test_flash_loan_fee_rounds_up() {
+        let globalpool = globalpool_with_flash_loan_fee();
+        assert_eq!(globalpool.flash_loan_fee(1_000_000).unwrap(), 900);
+        assert_eq!(globalpool.flash_loan_fee(1_001).unwrap(), 1);
+        assert_eq!(globalpool.flash_loan_fee(0).unwrap(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "FeeRateMaxExceeded")]
+    fn test_update_flash_loan_fee_rate_max_exceeded() {
+        let mut globalpool = GlobalpoolBuilder::new().build();
+        globalpool
+            .update_flash_loan_fee_rate(MAX_FEE_RATE + 1)
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "FlashLoanInProgress")]
+    fn test_start_flash_loan_in_progress() {
+        let mut globalpool = globalpool_with_flash_loan_fee();
+        globalpool.start_flash_loan(100, 0).unwrap();
+        globalpool.start_flash_loan(0, 100).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "FlashLoanNotInProgress")]
+    fn test_update_after_flash_loan_not_in_progress() {
+        let mut globalpool = globalpool_with_flash_loan_fee();
+        globalpool.update_after_flash_loan(1, 1).unwrap();
+    }
+
+    #[test]
+    fn test_update_after_flash_loan_splits_fees() {
+        let mut globalpool = globalpool_with_flash_loan_fee();
+        globalpool.start_flash_loan(1_000_000, 2_000_000).unwrap();
+        globalpool.update_after_flash_loan(900, 1_800).unwrap();
+
+        assert!(!globalpool.is_flash_loan_in_progress());
+        assert_eq!(globalpool.protocol_fee_owed_a, 225);
+        assert_eq!(globalpool.protocol_fee_owed_b, 450);
+        assert_eq!(globalpool.fee_growth_global_a, (675u128 << 64) >> 32);
+        assert_eq!(globalpool.fee_growth_global_b, (1_350u128 << 64) >> 32);
+    }
+
+    #[test]
+    fn test_update_after_flash_loan_without_liquidity() {
+        let mut globalpool = globalpool_with_flash_loan_fee();
+        globalpool.liquidity_available = 0;
+        globalpool.start_flash_loan(1_000_000, 0).unwrap();
+        globalpool.update_after_flash_loan(900, 0).unwrap();
+
+        assert_eq!(globalpool.protocol_fee_owed_a, 900);
+        assert_eq!(globalpool.fee_growth_global_a, 0);
+    }
+}
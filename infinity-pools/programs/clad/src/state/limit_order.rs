@@ -0,0 +1,290 @@
+use {
+    super::{TickArray, TICK_ARRAY_SIZE, TICK_ARRAY_SIZE_USIZE},
+    crate::errors::ErrorCode,
+    anchor_lang::prelude::*,
+};
+
+//
+// A range order on a single tick-spacing range [tick_lower_index, tick_lower_index + tick_spacing),
+// placed entirely on one side of the price. The order holds token A above the price and converts it
+// into token B once a swap crosses the range upwards, or holds token B below the price and converts
+// it into token A once a swap crosses it downwards.
+//
+// Orders on the same range are filled together by the swap, which removes their liquidity from the
+// ticks so the proceeds can't flip back. Each fill starts a new epoch of the range, so an order
+// placed before the range's current epoch is filled and can be claimed by the owner or a keeper.
+// Swaps through a tick array with pending orders must pass its LimitOrderArray, so no swap can
+// cross a range without filling its orders.
+//
+// Order liquidity earns swap fees in range like any other liquidity, but neither liquidity mining
+// rewards nor loan interest. It is left out of the liquidity the rewards are emitted to while in
+// range (Globalpool::liquidity_limit_orders), and is never lent.
+//
+#[account]
+#[derive(Default, Debug)]
+pub struct LimitOrder {
+    pub globalpool: Pubkey,
+    pub owner: Pubkey,
+    pub tick_lower_index: i32,
+    pub a_to_b: bool, // Converts token A into B
+    pub liquidity: u128,
+    pub epoch: u32,
+
+    // Q64.64, fee growth inside the range when the order was opened
+    pub fee_growth_checkpoint_a: u128,
+    pub fee_growth_checkpoint_b: u128,
+}
+
+impl LimitOrder {
+    pub const LEN: usize = 8 + std::mem::size_of::<LimitOrder>();
+
+    pub fn open(
+        &mut self,
+        globalpool: Pubkey,
+        owner: Pubkey,
+        tick_lower_index: i32,
+        a_to_b: bool,
+        liquidity: u128,
+        epoch: u32,
+        fee_growth_checkpoint_a: u128,
+        fee_growth_checkpoint_b: u128,
+    ) {
+        self.globalpool = globalpool;
+        self.owner = owner;
+        self.tick_lower_index = tick_lower_index;
+        self.a_to_b = a_to_b;
+        self.liquidity = liquidity;
+        self.epoch = epoch;
+        self.fee_growth_checkpoint_a = fee_growth_checkpoint_a;
+        self.fee_growth_checkpoint_b = fee_growth_checkpoint_b;
+    }
+
+    pub fn tick_upper_index(&self, tick_spacing: u16) -> i32 {
+        self.tick_lower_index + tick_spacing as i32
+    }
+
+    pub fn is_filled(&self, range: &LimitOrderRange) -> bool {
+        self.epoch != range.epoch
+    }
+}
+
+//
+// Limit orders pending on the range starting at a tick, and the fill of the last orders swapped
+// through it. New orders can't join the range until the filled orders are claimed, since only the
+// last fill is kept.
+//
+#[zero_copy]
+#[repr(packed)]
+#[derive(Default, Debug, PartialEq)]
+pub struct LimitOrderRange {
+    pub liquidity: u128, // Pending liquidity of the current epoch
+    pub a_to_b: bool,    // Direction of the pending orders
+    pub epoch: u32,      // Number of fills of the range
+
+    pub liquidity_unclaimed: u128, // Filled liquidity of the last epoch not yet claimed
+
+    // Q64.64, fee growth inside the range when the last epoch was filled
+    pub fee_growth_inside_a: u128,
+    pub fee_growth_inside_b: u128,
+} // 69
+
+impl LimitOrderRange {
+    pub fn has_pending_orders(&self) -> bool {
+        self.liquidity > 0
+    }
+}
+
+//
+// Limit orders of the ranges starting at the ticks of a tick array. Tick arrays are at their size
+// limit, so the ranges live in a companion account, created before the first order in the array
+// and closed along with the tick array.
+// Orders can't be placed on the last range of an array, so both ticks of a range are in its array.
+//
+#[account(zero_copy)]
+#[repr(packed)]
+pub struct LimitOrderArray {
+    pub globalpool: Pubkey,
+    pub start_tick_index: i32,
+    pub funder: Pubkey, // Refunded the rent when the tick array is closed
+    pub ranges: [LimitOrderRange; TICK_ARRAY_SIZE_USIZE],
+}
+
+impl Default for LimitOrderArray {
+    #[inline]
+    fn default() -> LimitOrderArray {
+        LimitOrderArray {
+            globalpool: Pubkey::default(),
+            start_tick_index: 0,
+            funder: Pubkey::default(),
+            ranges: [LimitOrderRange::default(); TICK_ARRAY_SIZE_USIZE],
+        }
+    }
+}
+
+impl LimitOrderArray {
+    pub const LEN: usize = 8 + std::mem::size_of::<LimitOrderArray>();
+
+    pub fn initialize(&mut self, tick_array: &TickArray, funder: Pubkey) {
+        self.globalpool = tick_array.globalpool;
+        self.start_tick_index = tick_array.start_tick_index;
+        self.funder = funder;
+    }
+
+    /// Whether no orders are pending on any range, nor filled orders left to claim.
+    pub fn is_empty(&self) -> bool {
+        self.ranges
+            .iter()
+            .all(|range| !range.has_pending_orders() && { range.liquidity_unclaimed } == 0)
+    }
+
+    /// Whether this array holds the ranges of the tick array.
+    pub fn is_for_tick_array(&self, tick_array: &TickArray) -> bool {
+        self.globalpool == tick_array.globalpool
+            && self.start_tick_index == tick_array.start_tick_index
+    }
+
+    /// Offset of the range starting at `tick_index`, or None if the range is not in this array.
+    pub fn range_offset(&self, tick_index: i32, tick_spacing: u16) -> Option<usize> {
+        let tick_spacing = tick_spacing as i32;
+        let tick_delta = tick_index - self.start_tick_index;
+        if tick_spacing == 0 || tick_delta < 0 || tick_delta % tick_spacing != 0 {
+            return None;
+        }
+
+        // The last range ends at the start of the next tick array
+        let offset = tick_delta / tick_spacing;
+        if offset >= TICK_ARRAY_SIZE - 1 {
+            return None;
+        }
+        Some(offset as usize)
+    }
+
+    pub fn get_range(&self, tick_index: i32, tick_spacing: u16) -> Result<LimitOrderRange> {
+        match self.range_offset(tick_index, tick_spacing) {
+            Some(offset) => Ok(self.ranges[offset]),
+            None => Err(ErrorCode::InvalidLimitOrderTickIndex.into()),
+        }
+    }
+
+    pub fn update_range(
+        &mut self,
+        tick_index: i32,
+        tick_spacing: u16,
+        range: &LimitOrderRange,
+    ) -> Result<()> {
+        match self.range_offset(tick_index, tick_spacing) {
+            Some(offset) => {
+                self.ranges[offset] = *range;
+                Ok(())
+            }
+            None => Err(ErrorCode::InvalidLimitOrderTickIndex.into()),
+        }
+    }
+
+    /// Pending order liquidity at a tick, ie. of the ranges starting and ending at it.
+    pub fn liquidity_at_tick(&self, tick_index: i32, tick_spacing: u16) -> u128 {
+        [tick_index, tick_index - tick_spacing as i32]
+            .iter()
+            .filter_map(|range_tick_index| self.range_offset(*range_tick_index, tick_spacing))
+            .map(|offset| self.ranges[offset].liquidity)
+            .sum()
+    }
+
+    /// Pending order liquidity at a tick of the tick array whose orders are at `account_info`, or
+    /// zero if the account is not created. The caller must verify the account address.
+    pub fn load_liquidity_at_tick<'info>(
+        account_info: &AccountInfo<'info>,
+        tick_index: i32,
+        tick_spacing: u16,
+    ) -> Result<u128> {
+        if account_info.owner != &crate::ID || account_info.data_is_empty() {
+            return Ok(0);
+        }
+
+        let limit_order_array = AccountLoader::<LimitOrderArray>::try_from(account_info)?;
+        let liquidity = limit_order_array
+            .load()?
+            .liquidity_at_tick(tick_index, tick_spacing);
+        Ok(liquidity)
+    }
+}
+
+#[cfg(test)]
+mod limit_order_array_tests {
+    use super::*;
+
+    #[test]
+    fn test_range_offset() {
+        let limit_order_array = LimitOrderArray {
+            start_tick_index: -704,
+            ..Default::default()
+        };
+
+        assert_eq!(limit_order_array.range_offset(-704, 8), Some(0));
+        assert_eq!(limit_order_array.range_offset(-16, 8), Some(86));
+        // Last range crosses into the next tick array
+        assert_eq!(limit_order_array.range_offset(-8, 8), None);
+        assert_eq!(limit_order_array.range_offset(0, 8), None);
+        assert_eq!(limit_order_array.range_offset(-712, 8), None);
+        assert_eq!(limit_order_array.range_offset(-700, 8), None);
+    }
+
+    #[test]
+    fn test_liquidity_at_tick() {
+        let mut limit_order_array = LimitOrderArray::default();
+        for (tick_index, liquidity) in [(0, 1_000), (64, 300), (5504, 50)] {
+            let range = LimitOrderRange {
+                liquidity,
+                ..Default::default()
+            };
+            limit_order_array.update_range(tick_index, 64, &range).unwrap();
+        }
+
+        assert_eq!(limit_order_array.liquidity_at_tick(0, 64), 1_000);
+        assert_eq!(limit_order_array.liquidity_at_tick(64, 64), 1_300);
+        assert_eq!(limit_order_array.liquidity_at_tick(128, 64), 300);
+        // The range ending at the last tick of the array
+        assert_eq!(limit_order_array.liquidity_at_tick(5568, 64), 50);
+        assert_eq!(limit_order_array.liquidity_at_tick(192, 64), 0);
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let mut limit_order_array = LimitOrderArray::default();
+        assert!(limit_order_array.is_empty());
+
+        // Filled orders left to claim
+        let range = LimitOrderRange {
+            liquidity_unclaimed: 1_000,
+            ..Default::default()
+        };
+        limit_order_array.update_range(64, 64, &range).unwrap();
+        assert!(!limit_order_array.is_empty());
+
+        // Pending orders
+        let range = LimitOrderRange {
+            liquidity: 1_000,
+            ..Default::default()
+        };
+        limit_order_array.update_range(64, 64, &range).unwrap();
+        assert!(!limit_order_array.is_empty());
+    }
+
+    #[test]
+    fn test_update_range() {
+        let mut limit_order_array = LimitOrderArray::default();
+        let range = LimitOrderRange {
+            liquidity: 1_000,
+            a_to_b: true,
+            ..Default::default()
+        };
+
+        limit_order_array.update_range(64, 64, &range).unwrap();
+        assert_eq!(limit_order_array.get_range(64, 64).unwrap(), range);
+        assert_eq!(
+            limit_order_array.get_range(0, 64).unwrap(),
+            LimitOrderRange::default()
+        );
+        assert!(limit_order_array.update_range(87 * 64, 64, &range).is_err());
+    }
+}
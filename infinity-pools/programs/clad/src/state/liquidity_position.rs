@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 
 use crate::errors::ErrorCode;
 
-use super::{Tick, Globalpool};
+use super::{Tick, Globalpool, NUM_REWARDS};
 
 #[account]
 #[derive(Default)]
@@ -19,6 +19,25 @@ pub struct LiquidityPosition {
     // Q64.64
     pub fee_growth_checkpoint_b: u128, // 16
     pub fee_owed_b: u64,               // 8
+
+    pub reward_infos: [LiquidityPositionRewardInfo; NUM_REWARDS], // 48
+
+    // Q64.64, interest growths of the lower & upper ticks (summed) at the last checkpoint
+    pub interest_growth_checkpoint_a: u128, // 16
+    pub interest_owed_a: u64,               // 8
+    // Q64.64
+    pub interest_growth_checkpoint_b: u128, // 16
+    pub interest_owed_b: u64,               // 8
+
+    // Liquidity queued for withdrawal while borrowed by trade positions, redeemable as they repay
+    pub liquidity_queued: u128, // 16
+}
+
+#[derive(Copy, Clone, AnchorSerialize, AnchorDeserialize, Default, Debug, PartialEq)]
+pub struct LiquidityPositionRewardInfo {
+    // Q64.64
+    pub growth_inside_checkpoint: u128,
+    pub amount_owed: u64,
 }
 
 impl LiquidityPosition {
@@ -26,7 +45,12 @@ impl LiquidityPosition {
 
     pub fn is_position_empty<'info>(position: &LiquidityPosition) -> bool {
         let fees_not_owed = position.fee_owed_a == 0 && position.fee_owed_b == 0;
-        position.liquidity == 0 && fees_not_owed
+        let rewards_not_owed = position
+            .reward_infos
+            .iter()
+            .all(|reward_info| reward_info.amount_owed == 0);
+        let interest_not_owed = position.interest_owed_a == 0 && position.interest_owed_b == 0;
+        position.liquidity == 0 && fees_not_owed && rewards_not_owed && interest_not_owed
     }
 
     pub fn update(&mut self, update: &LiquidityPositionUpdate) {
@@ -35,6 +59,7 @@ impl LiquidityPosition {
         self.fee_growth_checkpoint_b = update.fee_growth_checkpoint_b;
         self.fee_owed_a = update.fee_owed_a;
         self.fee_owed_b = update.fee_owed_b;
+        self.reward_infos = update.reward_infos;
     }
 
     pub fn open_position(
@@ -44,25 +69,155 @@ impl LiquidityPosition {
         tick_lower_index: i32,
         tick_upper_index: i32,
     ) -> Result<()> {
-        if !Tick::check_is_usable_tick(tick_lower_index, globalpool.tick_spacing)
-            || !Tick::check_is_usable_tick(tick_upper_index, globalpool.tick_spacing)
+        LiquidityPosition::check_range(globalpool.tick_spacing, tick_lower_index, tick_upper_index)?;
+
+        self.globalpool = globalpool.key();
+        self.position_mint = position_mint;
+
+        self.tick_lower_index = tick_lower_index;
+        self.tick_upper_index = tick_upper_index;
+        Ok(())
+    }
+
+    fn check_range(tick_spacing: u16, tick_lower_index: i32, tick_upper_index: i32) -> Result<()> {
+        if !Tick::check_is_usable_tick(tick_lower_index, tick_spacing)
+            || !Tick::check_is_usable_tick(tick_upper_index, tick_spacing)
             || tick_lower_index >= tick_upper_index
         {
             return Err(ErrorCode::InvalidTickIndex.into());
         }
+        Ok(())
+    }
 
-        self.globalpool = globalpool.key();
-        self.position_mint = position_mint;
+    /// Moves the position to a new range. The position must have no liquidity left, and is
+    /// checkpointed at the growths of the new range when liquidity is added to it.
+    pub fn reset_range(
+        &mut self,
+        tick_spacing: u16,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+    ) -> Result<()> {
+        if self.liquidity != 0 {
+            return Err(ErrorCode::LiquidityNonZero.into());
+        }
+        LiquidityPosition::check_range(tick_spacing, tick_lower_index, tick_upper_index)?;
 
         self.tick_lower_index = tick_lower_index;
         self.tick_upper_index = tick_upper_index;
         Ok(())
     }
 
+    /// Moves `liquidity` of this position into `new_position`, opened on the same range. The new
+    /// position takes over the growth checkpoints, while the amounts owed stay with this position.
+    ///
+    /// Both positions must be checkpointed at the current growths of the range beforehand.
+    pub fn split(&mut self, new_position: &mut LiquidityPosition, liquidity: u128) -> Result<()> {
+        if liquidity == 0 {
+            return Err(ErrorCode::LiquidityZero.into());
+        }
+        // Liquidity queued for withdrawal stays with this position
+        if liquidity > self.liquidity - self.liquidity_queued {
+            return Err(ErrorCode::LiquidityUnderflow.into());
+        }
+
+        new_position.liquidity = liquidity;
+        new_position.fee_growth_checkpoint_a = self.fee_growth_checkpoint_a;
+        new_position.fee_growth_checkpoint_b = self.fee_growth_checkpoint_b;
+        for (new_reward_info, reward_info) in new_position
+            .reward_infos
+            .iter_mut()
+            .zip(self.reward_infos.iter())
+        {
+            new_reward_info.growth_inside_checkpoint = reward_info.growth_inside_checkpoint;
+        }
+        new_position.interest_growth_checkpoint_a = self.interest_growth_checkpoint_a;
+        new_position.interest_growth_checkpoint_b = self.interest_growth_checkpoint_b;
+
+        self.liquidity -= liquidity;
+        Ok(())
+    }
+
+    /// Moves the liquidity and the amounts owed of `position` into this position.
+    ///
+    /// Both positions must be checkpointed at the current growths of the range beforehand, unless
+    /// they have no liquidity.
+    pub fn merge(&mut self, position: &LiquidityPosition) -> Result<()> {
+        if self.globalpool != position.globalpool
+            || self.tick_lower_index != position.tick_lower_index
+            || self.tick_upper_index != position.tick_upper_index
+        {
+            return Err(ErrorCode::LiquidityPositionRangeMismatch.into());
+        }
+
+        // Positions without liquidity are not synced, so take over the merged position's checkpoints
+        if self.liquidity == 0 {
+            self.fee_growth_checkpoint_a = position.fee_growth_checkpoint_a;
+            self.fee_growth_checkpoint_b = position.fee_growth_checkpoint_b;
+            for (reward_info, merged_reward_info) in
+                self.reward_infos.iter_mut().zip(position.reward_infos.iter())
+            {
+                reward_info.growth_inside_checkpoint = merged_reward_info.growth_inside_checkpoint;
+            }
+            self.interest_growth_checkpoint_a = position.interest_growth_checkpoint_a;
+            self.interest_growth_checkpoint_b = position.interest_growth_checkpoint_b;
+        }
+
+        self.liquidity = self
+            .liquidity
+            .checked_add(position.liquidity)
+            .ok_or(ErrorCode::LiquidityOverflow)?;
+        self.liquidity_queued = self
+            .liquidity_queued
+            .checked_add(position.liquidity_queued)
+            .ok_or(ErrorCode::LiquidityOverflow)?;
+
+        self.fee_owed_a = checked_add_owed(self.fee_owed_a, position.fee_owed_a)?;
+        self.fee_owed_b = checked_add_owed(self.fee_owed_b, position.fee_owed_b)?;
+        for (reward_info, merged_reward_info) in
+            self.reward_infos.iter_mut().zip(position.reward_infos.iter())
+        {
+            reward_info.amount_owed =
+                checked_add_owed(reward_info.amount_owed, merged_reward_info.amount_owed)?;
+        }
+        self.interest_owed_a = checked_add_owed(self.interest_owed_a, position.interest_owed_a)?;
+        self.interest_owed_b = checked_add_owed(self.interest_owed_b, position.interest_owed_b)?;
+        Ok(())
+    }
+
     pub fn reset_fees_owed(&mut self) {
         self.fee_owed_a = 0;
         self.fee_owed_b = 0;
     }
+
+    pub fn update_reward_owed(&mut self, index: usize, amount_owed: u64) {
+        self.reward_infos[index].amount_owed = amount_owed;
+    }
+
+    pub fn update_interest(&mut self, update: &LiquidityPositionInterestUpdate) {
+        self.interest_growth_checkpoint_a = update.interest_growth_checkpoint_a;
+        self.interest_growth_checkpoint_b = update.interest_growth_checkpoint_b;
+        self.interest_owed_a = update.interest_owed_a;
+        self.interest_owed_b = update.interest_owed_b;
+    }
+
+    pub fn update_liquidity_queued(&mut self, liquidity_queued: u128) -> Result<()> {
+        if liquidity_queued > self.liquidity {
+            return Err(ErrorCode::LiquidityQueuedExceeded.into());
+        }
+        self.liquidity_queued = liquidity_queued;
+        Ok(())
+    }
+
+    pub fn reset_interest_owed(&mut self) {
+        self.interest_owed_a = 0;
+        self.interest_owed_b = 0;
+    }
+}
+
+fn checked_add_owed(amount_owed: u64, amount: u64) -> Result<u64> {
+    Ok(amount_owed
+        .checked_add(amount)
+        .ok_or(ErrorCode::AmountCalcOverflow)?)
 }
 
 #[derive(Default, Debug, PartialEq)]
@@ -72,6 +227,15 @@ pub struct LiquidityPositionUpdate {
     pub fee_owed_a: u64,
     pub fee_growth_checkpoint_b: u128,
     pub fee_owed_b: u64,
+    pub reward_infos: [LiquidityPositionRewardInfo; NUM_REWARDS],
+}
+
+#[derive(Default, Debug, PartialEq)]
+pub struct LiquidityPositionInterestUpdate {
+    pub interest_growth_checkpoint_a: u128,
+    pub interest_owed_a: u64,
+    pub interest_growth_checkpoint_b: u128,
+    pub interest_owed_b: u64,
 }
 
 #[cfg(test)]
@@ -93,6 +257,8 @@ mod is_liquidity_position_empty_tests {
             fee_owed_a,
             fee_growth_checkpoint_b: 0,
             fee_owed_b,
+            reward_infos: [LiquidityPositionRewardInfo::default(); NUM_REWARDS],
+            ..Default::default()
         }
     }
 
@@ -119,6 +285,138 @@ mod is_liquidity_position_empty_tests {
         let pos = build_test_position(0, 0, 100);
         assert_eq!(LiquidityPosition::is_position_empty(&pos), false);
     }
+
+    #[test]
+    fn test_interest_non_zero() {
+        let mut pos = build_test_position(0, 0, 0);
+        pos.interest_owed_b = 100;
+        assert_eq!(LiquidityPosition::is_position_empty(&pos), false);
+    }
+}
+
+#[cfg(test)]
+mod split_and_merge_tests {
+    use super::*;
+
+    fn build_test_position(liquidity: u128) -> LiquidityPosition {
+        LiquidityPosition {
+            liquidity,
+            tick_lower_index: -128,
+            tick_upper_index: 128,
+            fee_growth_checkpoint_a: 100,
+            fee_owed_a: 10,
+            fee_growth_checkpoint_b: 200,
+            fee_owed_b: 20,
+            reward_infos: [LiquidityPositionRewardInfo {
+                growth_inside_checkpoint: 300,
+                amount_owed: 30,
+            }; NUM_REWARDS],
+            interest_growth_checkpoint_a: 400,
+            interest_owed_a: 40,
+            interest_growth_checkpoint_b: 500,
+            interest_owed_b: 50,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_split_carries_checkpoints_over() {
+        let mut position = build_test_position(1_000);
+        let mut new_position = LiquidityPosition {
+            tick_lower_index: -128,
+            tick_upper_index: 128,
+            ..Default::default()
+        };
+
+        position.split(&mut new_position, 400).unwrap();
+
+        assert_eq!(position.liquidity, 600);
+        assert_eq!(new_position.liquidity, 400);
+        assert_eq!(new_position.fee_growth_checkpoint_a, 100);
+        assert_eq!(new_position.fee_growth_checkpoint_b, 200);
+        assert_eq!(new_position.reward_infos[0].growth_inside_checkpoint, 300);
+        assert_eq!(new_position.interest_growth_checkpoint_a, 400);
+        assert_eq!(new_position.interest_growth_checkpoint_b, 500);
+
+        // Amounts owed stay with the split position
+        assert_eq!(position.fee_owed_a, 10);
+        assert_eq!(new_position.fee_owed_a, 0);
+        assert_eq!(new_position.reward_infos[0].amount_owed, 0);
+        assert_eq!(new_position.interest_owed_b, 0);
+    }
+
+    #[test]
+    fn test_split_excludes_queued_liquidity() {
+        let mut position = build_test_position(1_000);
+        position.liquidity_queued = 700;
+        let mut new_position = LiquidityPosition::default();
+
+        assert!(position.split(&mut new_position, 301).is_err());
+        assert!(position.split(&mut new_position, 0).is_err());
+        position.split(&mut new_position, 300).unwrap();
+        assert_eq!(position.liquidity, 700);
+    }
+
+    #[test]
+    fn test_merge_sums_liquidity_and_amounts_owed() {
+        let mut position = build_test_position(1_000);
+        let mut merged_position = build_test_position(500);
+        merged_position.liquidity_queued = 200;
+
+        position.merge(&merged_position).unwrap();
+
+        assert_eq!(position.liquidity, 1_500);
+        assert_eq!(position.liquidity_queued, 200);
+        assert_eq!(position.fee_owed_a, 20);
+        assert_eq!(position.fee_owed_b, 40);
+        assert_eq!(position.reward_infos[0].amount_owed, 60);
+        assert_eq!(position.interest_owed_a, 80);
+        assert_eq!(position.interest_owed_b, 100);
+        assert_eq!(position.fee_growth_checkpoint_a, 100);
+    }
+
+    #[test]
+    fn test_merge_into_empty_position_takes_checkpoints() {
+        let mut position = LiquidityPosition {
+            tick_lower_index: -128,
+            tick_upper_index: 128,
+            fee_owed_a: 5,
+            ..Default::default()
+        };
+        let merged_position = build_test_position(500);
+
+        position.merge(&merged_position).unwrap();
+
+        assert_eq!(position.liquidity, 500);
+        assert_eq!(position.fee_owed_a, 15);
+        assert_eq!(position.fee_growth_checkpoint_a, 100);
+        assert_eq!(position.fee_growth_checkpoint_b, 200);
+        assert_eq!(position.reward_infos[0].growth_inside_checkpoint, 300);
+        assert_eq!(position.interest_growth_checkpoint_a, 400);
+        assert_eq!(position.interest_growth_checkpoint_b, 500);
+    }
+
+    #[test]
+    fn test_merge_different_range_fails() {
+        let mut position = build_test_position(1_000);
+        let mut merged_position = build_test_position(500);
+        merged_position.tick_upper_index = 256;
+
+        assert!(position.merge(&merged_position).is_err());
+    }
+
+    #[test]
+    fn test_reset_range() {
+        let mut position = build_test_position(1_000);
+        assert!(position.reset_range(64, 0, 256).is_err());
+
+        position.liquidity = 0;
+        assert!(position.reset_range(64, 256, 0).is_err());
+        assert!(position.reset_range(64, 0, 100).is_err());
+        position.reset_range(64, 0, 256).unwrap();
+        assert_eq!(position.tick_lower_index, 0);
+        assert_eq!(position.tick_upper_index, 256);
+    }
 }
 
 #[cfg(test)]
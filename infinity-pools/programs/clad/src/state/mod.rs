@@ -1,13 +1,21 @@
 pub mod clad;
+pub mod fee_tier;
 pub mod globalpool;
+pub mod limit_order;
 pub mod liquidity_position;
+pub mod oracle;
 pub mod pyth;
 pub mod trade_position;
 pub mod tick;
+pub mod tick_interest;
 
 pub use clad::*;
+pub use fee_tier::*;
 pub use globalpool::*;
+pub use limit_order::*;
 pub use liquidity_position::*;
+pub use oracle::*;
 pub use pyth::*;
 pub use trade_position::*;
 pub use tick::*;
+pub use tick_interest::*;
@@ -0,0 +1,279 @@
+use {crate::errors::ErrorCode, anchor_lang::prelude::*};
+
+pub const ORACLE_OBSERVATION_CAPACITY: usize = 256;
+
+#[repr(packed)]
+#[zero_copy]
+#[derive(Default, Debug, PartialEq)]
+pub struct Observation {
+    pub initialized: bool,
+    pub timestamp: u64,       // UNIX time of the observation (in seconds)
+    pub tick_cumulative: i64, // Sum of tick_current_index * seconds elapsed, since the first observation
+}
+
+impl Observation {
+    /// Observation at `timestamp`, accumulating `tick_current_index` since this observation.
+    pub fn transform(&self, timestamp: u64, tick_current_index: i32) -> Observation {
+        let elapsed_seconds = timestamp.saturating_sub(self.timestamp) as i64;
+
+        Observation {
+            initialized: true,
+            timestamp,
+            tick_cumulative: self
+                .tick_cumulative
+                .wrapping_add(tick_current_index as i64 * elapsed_seconds),
+        }
+    }
+}
+
+//
+// Ring buffer of tick observations of a Globalpool (Uniswap v3 style), written on swaps.
+// Used for the time-weighted average tick over a window, which is harder to manipulate than the
+// spot `sqrt_price`.
+//
+#[repr(packed)]
+#[account(zero_copy)]
+pub struct Oracle {
+    pub globalpool: Pubkey,
+    pub observation_index: u16, // Index of the latest observation
+    pub observations: [Observation; ORACLE_OBSERVATION_CAPACITY],
+}
+
+impl Default for Oracle {
+    #[inline]
+    fn default() -> Oracle {
+        Oracle {
+            globalpool: Pubkey::default(),
+            observation_index: 0,
+            observations: [Observation::default(); ORACLE_OBSERVATION_CAPACITY],
+        }
+    }
+}
+
+impl Oracle {
+    pub const LEN: usize = 8 + std::mem::size_of::<Oracle>();
+
+    // Window of the average tick that trade positions are valued at for liquidation
+    pub const LIQUIDATION_TWAP_SECONDS: u64 = 600; // 10 minutes
+
+    pub fn initialize(&mut self, globalpool: Pubkey, timestamp: u64) {
+        self.globalpool = globalpool;
+        self.observation_index = 0;
+        self.observations[0] = Observation {
+            initialized: true,
+            timestamp,
+            tick_cumulative: 0,
+        };
+    }
+
+    /// Write an observation at `timestamp`, where `tick_current_index` is the tick since the latest
+    /// observation (ie. before a swap moves it). At most one observation is written per second.
+    pub fn write(&mut self, timestamp: u64, tick_current_index: i32) -> Result<()> {
+        let latest = self.observations[self.observation_index as usize];
+
+        if timestamp < latest.timestamp {
+            return Err(ErrorCode::InvalidTimestamp.into());
+        }
+        if timestamp == latest.timestamp {
+            return Ok(());
+        }
+
+        let next_index = (self.observation_index as usize + 1) % ORACLE_OBSERVATION_CAPACITY;
+        self.observations[next_index] = latest.transform(timestamp, tick_current_index);
+        self.observation_index = next_index as u16;
+
+        Ok(())
+    }
+
+    /// Time-weighted average tick over the last `window_seconds` before `timestamp`, rounded down.
+    pub fn observe(
+        &self,
+        timestamp: u64,
+        window_seconds: u64,
+        tick_current_index: i32,
+    ) -> Result<i32> {
+        if window_seconds == 0 || window_seconds > timestamp {
+            return Err(ErrorCode::InvalidOracleObservationWindow.into());
+        }
+
+        let tick_cumulative = self.observe_tick_cumulative(timestamp, tick_current_index)?;
+        let tick_cumulative_window_start =
+            self.observe_tick_cumulative(timestamp - window_seconds, tick_current_index)?;
+
+        let tick_cumulative_delta = tick_cumulative.wrapping_sub(tick_cumulative_window_start);
+
+        Ok(tick_cumulative_delta.div_euclid(window_seconds as i64) as i32)
+    }
+
+    /// Tick cumulative at `target_timestamp`, interpolated between the surrounding observations or
+    /// extrapolated from the latest observation with `tick_current_index`.
+    pub fn observe_tick_cumulative(
+        &self,
+        target_timestamp: u64,
+        tick_current_index: i32,
+    ) -> Result<i64> {
+        let latest = self.observations[self.observation_index as usize];
+
+        if target_timestamp >= latest.timestamp {
+            return Ok(latest
+                .transform(target_timestamp, tick_current_index)
+                .tick_cumulative);
+        }
+
+        // Oldest observation is the one after the latest, unless the buffer hasn't wrapped yet
+        let observation_count = self.observation_count();
+        let oldest_index = (self.observation_index as usize + 1) % observation_count;
+        let observation_at =
+            |offset: usize| self.observations[(oldest_index + offset) % observation_count];
+
+        let oldest_timestamp = observation_at(0).timestamp;
+        if target_timestamp < oldest_timestamp {
+            return Err(ErrorCode::OracleObservationTooOld.into());
+        }
+
+        // Binary search for the observations at or before & after the target
+        let mut low = 0;
+        let mut high = observation_count - 1;
+        while high - low > 1 {
+            let mid = (low + high) / 2;
+            let mid_timestamp = observation_at(mid).timestamp;
+            if mid_timestamp <= target_timestamp {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+
+        let before = observation_at(low);
+        let after = observation_at(high);
+        let before_timestamp = before.timestamp;
+        let after_timestamp = after.timestamp;
+
+        if target_timestamp == before_timestamp {
+            return Ok(before.tick_cumulative);
+        }
+
+        // Tick was constant between the two observations
+        let tick_cumulative_delta = after.tick_cumulative.wrapping_sub(before.tick_cumulative);
+        let tick = tick_cumulative_delta / (after_timestamp - before_timestamp) as i64;
+
+        Ok(before
+            .tick_cumulative
+            .wrapping_add(tick * (target_timestamp - before_timestamp) as i64))
+    }
+
+    /// Timestamp of the oldest observation still in the ring buffer.
+    pub fn oldest_timestamp(&self) -> u64 {
+        let oldest_index = (self.observation_index as usize + 1) % self.observation_count();
+        self.observations[oldest_index].timestamp
+    }
+
+    fn observation_count(&self) -> usize {
+        let next_index = (self.observation_index as usize + 1) % ORACLE_OBSERVATION_CAPACITY;
+        if self.observations[next_index].initialized {
+            ORACLE_OBSERVATION_CAPACITY
+        } else {
+            self.observation_index as usize + 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod oracle_tests {
+    use super::*;
+
+    fn new_oracle(timestamp: u64) -> Box<Oracle> {
+        let mut oracle = Box::new(Oracle::default());
+        oracle.initialize(Pubkey::new_unique(), timestamp);
+        oracle
+    }
+
+    #[test]
+    fn test_write_accumulates_tick() {
+        let mut oracle = new_oracle(1_000);
+        oracle.write(1_010, 100).unwrap();
+        oracle.write(1_030, -50).unwrap();
+
+        let index = oracle.observation_index;
+        let latest = oracle.observations[index as usize];
+        let tick_cumulative = latest.tick_cumulative;
+        assert_eq!(index, 2);
+        assert_eq!(tick_cumulative, 100 * 10 - 50 * 20);
+    }
+
+    #[test]
+    fn test_write_once_per_second() {
+        let mut oracle = new_oracle(1_000);
+        oracle.write(1_010, 100).unwrap();
+        oracle.write(1_010, 200).unwrap();
+
+        let index = oracle.observation_index;
+        assert_eq!(index, 1);
+    }
+
+    #[test]
+    fn test_observe_twap() {
+        let mut oracle = new_oracle(1_000);
+        oracle.write(1_100, 10).unwrap(); // tick 10 for [1000, 1100)
+        oracle.write(1_200, 30).unwrap(); // tick 30 for [1100, 1200)
+
+        // Tick 50 since the latest observation
+        assert_eq!(oracle.observe(1_300, 100, 50).unwrap(), 50);
+        assert_eq!(oracle.observe(1_300, 200, 50).unwrap(), 40);
+        assert_eq!(oracle.observe(1_300, 300, 50).unwrap(), 30);
+
+        // Interpolated within [1100, 1200)
+        assert_eq!(oracle.observe(1_300, 150, 50).unwrap(), 43);
+    }
+
+    #[test]
+    fn test_observe_rounds_down() {
+        let mut oracle = new_oracle(1_000);
+        oracle.write(1_001, -1).unwrap();
+
+        assert_eq!(oracle.observe(1_002, 2, 0).unwrap(), -1);
+    }
+
+    #[test]
+    fn test_observe_too_old() {
+        let mut oracle = new_oracle(1_000);
+        oracle.write(1_100, 10).unwrap();
+
+        let result = oracle.observe(1_100, 101, 10);
+        assert_eq!(
+            result.unwrap_err(),
+            ErrorCode::OracleObservationTooOld.into()
+        );
+    }
+
+    #[test]
+    fn test_oldest_timestamp() {
+        let mut oracle = new_oracle(1_000);
+        assert_eq!(oracle.oldest_timestamp(), 1_000);
+
+        oracle.write(1_100, 10).unwrap();
+        assert_eq!(oracle.oldest_timestamp(), 1_000);
+
+        for i in 1..=ORACLE_OBSERVATION_CAPACITY as u64 {
+            oracle.write(1_100 + i, 10).unwrap();
+        }
+        assert_eq!(oracle.oldest_timestamp(), 1_101);
+    }
+
+    #[test]
+    fn test_observe_after_wrap() {
+        let mut oracle = new_oracle(0);
+        for i in 1..=(ORACLE_OBSERVATION_CAPACITY as u64 + 10) {
+            oracle.write(i * 10, 7).unwrap();
+        }
+
+        // Oldest observations were overwritten
+        let result = oracle.observe(2_660, 2_660, 7);
+        assert_eq!(
+            result.unwrap_err(),
+            ErrorCode::OracleObservationTooOld.into()
+        );
+
+        assert_eq!(oracle.observe(2_660, 2_000, 7).unwrap(), 7);
+    }
+}
@@ -50,14 +50,22 @@ impl Deref for PriceFeed {
 }
 
 impl PriceFeed {
-    // NOTE: 30 seconds for mainnet, but keep it long for localnet testing
-    // pub const STALE_SECONDS: u64 = 30;
-    pub const STALE_SECONDS: u64 = 100_000;
+    /// Loads a Pyth price feed from an account not deserialized by Anchor, checking its owner.
+    pub fn load(account_info: &AccountInfo) -> Result<PriceFeed> {
+        if *account_info.owner != PriceFeed::owner() {
+            return Err(ErrorCode::InvalidOracleAccount.into());
+        }
+        PriceFeed::try_deserialize(&mut &account_info.data.borrow()[..])
+    }
 
-    pub fn read_price(&self, current_timestamp: UnixTimestamp) -> Result<OraclePrice> {
-        // Load the price from the price feed. Here, the price can be no older than `STALE_SECONDS` seconds.
+    pub fn read_price(
+        &self,
+        current_timestamp: UnixTimestamp,
+        stale_seconds: u64,
+    ) -> Result<OraclePrice> {
+        // Load the price from the price feed. Here, the price can be no older than `stale_seconds` seconds.
         let price: pyth_sdk::Price = self
-            .get_price_no_older_than(current_timestamp, PriceFeed::STALE_SECONDS)
+            .get_price_no_older_than(current_timestamp, stale_seconds)
             .ok_or(ErrorCode::StaleOraclePrice)?;
 
         PriceFeed::format_price(price)
@@ -67,8 +75,10 @@ impl PriceFeed {
         &self,
         quote_price_feed: &PriceFeed,
         current_timestamp: UnixTimestamp,
+        stale_seconds: u64,
     ) -> Result<OraclePrice> {
-        let (price, quote) = self._read_price_in_quote(quote_price_feed, current_timestamp)?;
+        let (price, quote) =
+            self._read_price_in_quote(quote_price_feed, current_timestamp, stale_seconds)?;
         // TODO: validate that we want the price_in_quote to use `quote.expo` as result_expo
         let price_in_quote = price.get_price_in_quote(&quote, quote.expo).unwrap();
         PriceFeed::format_price(price_in_quote)
@@ -78,9 +88,11 @@ impl PriceFeed {
         &self,
         quote_price_feed: &PriceFeed,
         current_timestamp: UnixTimestamp,
+        stale_seconds: u64,
         expo: i32,
     ) -> Result<OraclePrice> {
-        let (price, quote) = self._read_price_in_quote(quote_price_feed, current_timestamp)?;
+        let (price, quote) =
+            self._read_price_in_quote(quote_price_feed, current_timestamp, stale_seconds)?;
         let price_in_quote = price.get_price_in_quote(&quote, expo).unwrap();
         PriceFeed::format_price(price_in_quote)
     }
@@ -89,18 +101,19 @@ impl PriceFeed {
         &self,
         quote_price_feed: &PriceFeed,
         current_timestamp: UnixTimestamp,
+        stale_seconds: u64,
     ) -> Result<(pyth_sdk::Price, pyth_sdk::Price)> {
         // let pyth_price = self.get_price_unchecked();
         // msg!("pyth_price: {:?}", pyth_price);
         // msg!("diff time: {:?}", current_timestamp - pyth_price.publish_time);
 
         let price: pyth_sdk::Price = self
-            .get_price_no_older_than(current_timestamp, PriceFeed::STALE_SECONDS)
+            .get_price_no_older_than(current_timestamp, stale_seconds)
             .ok_or(ErrorCode::StaleOraclePrice)?;
         msg!("price: {:?}", price);
 
         let quote: pyth_sdk::Price = quote_price_feed
-            .get_price_no_older_than(current_timestamp, PriceFeed::STALE_SECONDS)
+            .get_price_no_older_than(current_timestamp, stale_seconds)
             .ok_or(ErrorCode::StaleOraclePrice)?;
 
         Ok((price, quote))
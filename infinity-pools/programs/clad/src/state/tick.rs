@@ -1,4 +1,8 @@
-use {super::Globalpool, crate::errors::ErrorCode, anchor_lang::prelude::*};
+use {
+    super::{Globalpool, NUM_REWARDS},
+    crate::errors::ErrorCode,
+    anchor_lang::prelude::*,
+};
 
 // Max & min tick index based on sqrt(1.0001) & max.min price of 2^64
 pub const MAX_TICK_INDEX: i32 = 443636;
@@ -18,14 +22,15 @@ pub struct Tick {
     pub initialized: bool,
     pub liquidity_net: i128,
     pub liquidity_gross: u128,
-    
-    pub liquidity_borrowed: i128,
 
     // Q64.64
     pub fee_growth_outside_a: u128,
     // Q64.64
     pub fee_growth_outside_b: u128,
-} // 81
+
+    // Array of Q64.64
+    pub reward_growths_outside: [u128; NUM_REWARDS],
+} // 113
 
 impl Tick {
     pub const LEN: usize = std::mem::size_of::<Tick>();
@@ -38,9 +43,25 @@ impl Tick {
         self.initialized = update.initialized;
         self.liquidity_net = update.liquidity_net;
         self.liquidity_gross = update.liquidity_gross;
-        self.liquidity_borrowed = update.liquidity_borrowed;
         self.fee_growth_outside_a = update.fee_growth_outside_a;
         self.fee_growth_outside_b = update.fee_growth_outside_b;
+        self.reward_growths_outside = update.reward_growths_outside;
+    }
+
+    /// Liquidity that liquidity positions & limit orders have at this tick, ie. gross of what trade
+    /// positions have borrowed at it (see TickInterest).
+    pub fn liquidity_lent(&self, liquidity_borrowed: u128) -> u128 {
+        self.liquidity_gross.saturating_sub(liquidity_borrowed)
+    }
+
+    /// Liquidity that liquidity positions have at this tick and is neither borrowed nor reserved,
+    /// ie. what they can withdraw right away, or trade positions can borrow. Reserved liquidity is
+    /// that of limit orders, never lent as swaps fill the orders without knowing what is borrowed at
+    /// their ticks, and liquidity queued for withdrawal (see TickInterest).
+    pub fn liquidity_idle(&self, liquidity_borrowed: u128, liquidity_reserved: u128) -> u128 {
+        self.liquidity_lent(liquidity_borrowed)
+            .saturating_sub(liquidity_borrowed)
+            .saturating_sub(liquidity_reserved)
     }
 
     /// Check that the tick index is within the supported range of this contract
@@ -116,9 +137,9 @@ pub struct TickUpdate {
     pub initialized: bool,
     pub liquidity_net: i128,
     pub liquidity_gross: u128,
-    pub liquidity_borrowed: i128,
     pub fee_growth_outside_a: u128,
     pub fee_growth_outside_b: u128,
+    pub reward_growths_outside: [u128; NUM_REWARDS],
 }
 
 impl TickUpdate {
@@ -127,9 +148,9 @@ impl TickUpdate {
             initialized: tick.initialized,
             liquidity_net: tick.liquidity_net,
             liquidity_gross: tick.liquidity_gross,
-            liquidity_borrowed: tick.liquidity_borrowed,
             fee_growth_outside_a: tick.fee_growth_outside_a,
             fee_growth_outside_b: tick.fee_growth_outside_b,
+            reward_growths_outside: tick.reward_growths_outside,
         }
     }
 }
@@ -140,6 +161,8 @@ pub struct TickArray {
     pub start_tick_index: i32,
     pub ticks: [Tick; TICK_ARRAY_SIZE_USIZE],
     pub globalpool: Pubkey,
+    pub limit_order_range_count: u16, // Ranges with pending limit orders, see LimitOrderArray
+    pub funder: Pubkey,               // Paid the rent, refunded by close_tick_array
 }
 
 impl Default for TickArray {
@@ -149,6 +172,8 @@ impl Default for TickArray {
             globalpool: Pubkey::default(),
             ticks: [Tick::default(); TICK_ARRAY_SIZE_USIZE],
             start_tick_index: 0,
+            limit_order_range_count: 0,
+            funder: Pubkey::default(),
         }
     }
 }
@@ -220,6 +245,7 @@ impl TickArray {
         &mut self,
         globalpool: &Account<Globalpool>,
         start_tick_index: i32,
+        funder: Pubkey,
     ) -> Result<()> {
         if !Tick::check_is_valid_start_tick(start_tick_index, globalpool.tick_spacing) {
             return Err(ErrorCode::InvalidStartTick.into());
@@ -227,9 +253,18 @@ impl TickArray {
 
         self.globalpool = globalpool.key();
         self.start_tick_index = start_tick_index;
+        self.funder = funder;
         Ok(())
     }
 
+    /// Whether no liquidity is left at any tick of this array, so that it can be closed.
+    pub fn is_empty(&self) -> bool {
+        // Borrowed liquidity is part of the liquidity gross
+        self.ticks
+            .iter()
+            .all(|tick| !tick.initialized && tick.liquidity_gross == 0)
+    }
+
     /// Get the Tick object at the given tick-index & tick-spacing
     ///
     /// # Parameters
@@ -318,6 +353,24 @@ impl TickArray {
         self.start_tick_index + TICK_ARRAY_SIZE * (tick_spacing as i32) > MAX_TICK_INDEX
     }
 
+    /// Count a range of this array in or out of the ranges with pending limit orders.
+    ///
+    /// # Parameters
+    /// - `had_pending_orders` - If the range had pending limit orders before the update
+    /// - `has_pending_orders` - If the range has pending limit orders after the update
+    pub fn update_limit_order_range_count(
+        &mut self,
+        had_pending_orders: bool,
+        has_pending_orders: bool,
+    ) {
+        let count = self.limit_order_range_count;
+        self.limit_order_range_count = match (had_pending_orders, has_pending_orders) {
+            (false, true) => count + 1,
+            (true, false) => count - 1,
+            _ => count,
+        };
+    }
+
     // Calculates an offset from a tick index that can be used to access the tick data
     pub fn tick_offset(&self, tick_index: i32, tick_spacing: u16) -> Result<isize> {
         if tick_spacing == 0 {
@@ -363,7 +416,6 @@ pub mod tick_builder {
         initialized: bool,
         liquidity_net: i128,
         liquidity_gross: u128,
-        liquidity_borrowed: i128,
         fee_growth_outside_a: u128,
         fee_growth_outside_b: u128,
     }
@@ -384,11 +436,6 @@ pub mod tick_builder {
             self
         }
 
-        pub fn liquidity_borrowed(mut self, liquidity_borrowed: i128) -> Self {
-            self.liquidity_borrowed = liquidity_borrowed;
-            self
-        }
-
         pub fn fee_growth_outside_a(mut self, fee_growth_outside_a: u128) -> Self {
             self.fee_growth_outside_a = fee_growth_outside_a;
             self
@@ -404,9 +451,9 @@ pub mod tick_builder {
                 initialized: self.initialized,
                 liquidity_net: self.liquidity_net,
                 liquidity_gross: self.liquidity_gross,
-                liquidity_borrowed: self.liquidity_borrowed,
                 fee_growth_outside_a: self.fee_growth_outside_a,
                 fee_growth_outside_b: self.fee_growth_outside_b,
+                ..Default::default()
             }
         }
     }
@@ -595,9 +642,9 @@ mod array_update_tests {
             initialized: true,
             liquidity_net: 2525252i128,
             liquidity_gross: 2525252u128,
-            liquidity_borrowed: 0,
             fee_growth_outside_a: 28728282u128,
             fee_growth_outside_b: 22528728282u128,
+            ..Default::default()
         };
 
         array.ticks[1] = original;
@@ -606,9 +653,9 @@ mod array_update_tests {
             initialized: true,
             liquidity_net: 24128472184712i128,
             liquidity_gross: 353873892732u128,
-            liquidity_borrowed: 0,
             fee_growth_outside_a: 3928372892u128,
             fee_growth_outside_b: 12242u128,
+            ..Default::default()
         };
 
         let tick_spacing = 8;
@@ -621,11 +668,80 @@ mod array_update_tests {
             initialized: true,
             liquidity_net: 24128472184712i128,
             liquidity_gross: 353873892732u128,
-            liquidity_borrowed: 0,
             fee_growth_outside_a: 3928372892u128,
             fee_growth_outside_b: 12242u128,
+            ..Default::default()
         };
         let result = array.get_tick(tick_index, tick_spacing).unwrap();
         assert_eq!(*result, expected);
     }
 }
+
+#[cfg(test)]
+mod array_is_empty_tests {
+    use super::*;
+
+    #[test]
+    fn test_default_array_is_empty() {
+        assert!(TickArray::default().is_empty());
+    }
+
+    #[test]
+    fn test_array_with_initialized_tick_is_not_empty() {
+        let mut array = TickArray::default();
+        array.ticks[10] = Tick {
+            initialized: true,
+            liquidity_net: 1_000,
+            liquidity_gross: 1_000,
+            ..Default::default()
+        };
+        assert!(!array.is_empty());
+    }
+
+    #[test]
+    fn test_array_with_borrowed_liquidity_is_not_empty() {
+        let mut array = TickArray::default();
+        // Borrowing adds to the tick's liquidity gross, even if it is left uninitialized
+        array.ticks[87] = Tick {
+            liquidity_gross: 1_000,
+            ..Default::default()
+        };
+        assert!(!array.is_empty());
+    }
+
+    #[test]
+    fn test_array_with_only_growths_outside_is_empty() {
+        let mut array = TickArray::default();
+        array.ticks[0] = Tick {
+            fee_growth_outside_a: 100,
+            fee_growth_outside_b: 200,
+            ..Default::default()
+        };
+        assert!(array.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tick_liquidity_tests {
+    use super::*;
+
+    #[test]
+    fn test_liquidity_idle_excludes_borrowed_and_reserved() {
+        // 1_000 of positions, 300 of limit orders, and 400 borrowed (borrowing adds to the gross)
+        let tick = Tick {
+            initialized: true,
+            liquidity_gross: 1_700,
+            ..Default::default()
+        };
+        assert_eq!(tick.liquidity_lent(400), 1_300);
+        assert_eq!(tick.liquidity_idle(400, 300), 600);
+        assert_eq!(tick.liquidity_idle(0, 0), 1_700);
+        assert_eq!(tick.liquidity_idle(1_000, 0), 0);
+    }
+
+    #[test]
+    fn test_tick_array_fits_cpi_account_limit() {
+        assert_eq!(Tick::LEN, 113);
+        assert!(TickArray::LEN <= 10_240);
+    }
+}
@@ -0,0 +1,339 @@
+use {
+    crate::{
+        errors::ErrorCode,
+        math::{Q64_RESOLUTION, U256},
+    },
+    anchor_lang::prelude::*,
+};
+
+//
+// Loans of trade positions at a tick: the liquidity they borrowed and the interest they paid to the
+// liquidity positions bounded by the tick. Tick arrays are at their size limit, so both live in a
+// PDA per tick, created when a trade position first borrows at the tick.
+//
+// Liquidity positions whose withdrawal is queued behind the loans have the first claim on the
+// liquidity repaid at the tick, so the queued liquidity is neither borrowed nor withdrawn by others.
+//
+// Trade positions borrow the liquidity at their lower & upper ticks, so the interest they pay is
+// split between the two. It is not paid out to positions at once, but released by the second until
+// the end of the time it pays for, and grows per unit of liquidity that positions lend at the tick
+// meanwhile. Positions so earn it by liquidity × time, and none of what was released before they
+// lent. The tick is checkpointed whenever the liquidity positions lend at it changes.
+//
+#[account]
+#[derive(Default, Debug)]
+pub struct TickInterest {
+    pub globalpool: Pubkey,
+    pub tick_index: i32,
+
+    // Borrowed by trade positions, also counted in the tick's liquidity gross
+    pub liquidity_borrowed: u128,
+    // Queued for withdrawal by liquidity positions bounded by the tick
+    pub liquidity_queued: u128,
+
+    // Q64.64
+    pub interest_growth_a: u128,
+    // Q64.64
+    pub interest_growth_b: u128,
+
+    // Interest released per second until the release end, Q64.64
+    pub interest_release_rate_a: u128,
+    // Interest released per second until the release end, Q64.64
+    pub interest_release_rate_b: u128,
+    pub interest_release_end: u64,
+    // Time of the last checkpoint, up to which the interest is released
+    pub interest_release_timestamp: u64,
+    // Lent by liquidity positions since the last checkpoint
+    pub liquidity_lent: u128,
+}
+
+impl TickInterest {
+    pub const LEN: usize = 8 + std::mem::size_of::<TickInterest>();
+
+    pub fn initialize(&mut self, globalpool: Pubkey, tick_index: i32) {
+        self.globalpool = globalpool;
+        self.tick_index = tick_index;
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.globalpool != Pubkey::default()
+    }
+
+    /// Apply a change of the liquidity borrowed at this tick, positive when borrowing.
+    pub fn update_liquidity_borrowed(&mut self, liquidity_delta: i128) -> Result<()> {
+        self.liquidity_borrowed = if liquidity_delta >= 0 {
+            self.liquidity_borrowed
+                .checked_add(liquidity_delta.unsigned_abs())
+                .ok_or(ErrorCode::LiquidityOverflow)?
+        } else {
+            self.liquidity_borrowed
+                .checked_sub(liquidity_delta.unsigned_abs())
+                .ok_or(ErrorCode::LiquidityUnderflow)?
+        };
+        Ok(())
+    }
+
+    /// Apply a change of the liquidity queued for withdrawal at this tick, positive when queuing.
+    pub fn update_liquidity_queued(&mut self, liquidity_delta: i128) -> Result<()> {
+        self.liquidity_queued = if liquidity_delta >= 0 {
+            self.liquidity_queued
+                .checked_add(liquidity_delta.unsigned_abs())
+                .ok_or(ErrorCode::LiquidityOverflow)?
+        } else {
+            self.liquidity_queued
+                .checked_sub(liquidity_delta.unsigned_abs())
+                .ok_or(ErrorCode::LiquidityUnderflow)?
+        };
+        Ok(())
+    }
+
+    /// Release the interest up to `timestamp` to the liquidity lent since the last checkpoint, and
+    /// lend `liquidity_lent` from then on. While nothing is lent the release pauses, ending as much
+    /// later. Must be called whenever the liquidity that positions lend at the tick changes.
+    pub fn checkpoint(&mut self, liquidity_lent: u128, timestamp: u64) -> Result<()> {
+        if timestamp > self.interest_release_timestamp {
+            if self.liquidity_lent > 0 {
+                (self.interest_growth_a, self.interest_growth_b) =
+                    self.interest_growths(timestamp)?;
+            } else if self.interest_release_end > self.interest_release_timestamp {
+                self.interest_release_end = self
+                    .interest_release_end
+                    .saturating_add(timestamp - self.interest_release_timestamp);
+            }
+
+            if timestamp >= self.interest_release_end {
+                self.interest_release_rate_a = 0;
+                self.interest_release_rate_b = 0;
+            }
+            self.interest_release_timestamp = timestamp;
+        }
+
+        self.liquidity_lent = liquidity_lent;
+        Ok(())
+    }
+
+    /// Add interest paid for the time until `release_end`, released along with what is left to
+    /// release until the later of the two ends. The tick must be checkpointed at `timestamp` first.
+    pub fn add_interest(
+        &mut self,
+        interest_amount: u64,
+        is_token_a: bool,
+        release_end: u64,
+        timestamp: u64,
+    ) -> Result<()> {
+        if interest_amount == 0 {
+            return Ok(());
+        }
+
+        let release_end = release_end
+            .max(self.interest_release_end)
+            .max(timestamp + 1);
+        let remaining_duration = U256::from(self.interest_release_end.saturating_sub(timestamp));
+        let release_duration = U256::from(release_end - timestamp);
+
+        self.interest_release_rate_a =
+            (U256::from(self.interest_release_rate_a) * remaining_duration / release_duration)
+                .try_into_u128()?;
+        self.interest_release_rate_b =
+            (U256::from(self.interest_release_rate_b) * remaining_duration / release_duration)
+                .try_into_u128()?;
+
+        let interest_rate = (U256::from(interest_amount) << Q64_RESOLUTION) / release_duration;
+        if is_token_a {
+            self.interest_release_rate_a =
+                (U256::from(self.interest_release_rate_a) + interest_rate).try_into_u128()?;
+        } else {
+            self.interest_release_rate_b =
+                (U256::from(self.interest_release_rate_b) + interest_rate).try_into_u128()?;
+        }
+
+        self.interest_release_end = release_end;
+        Ok(())
+    }
+
+    /// Interest growths with the interest released up to `timestamp`, not yet checkpointed.
+    pub fn interest_growths(&self, timestamp: u64) -> Result<(u128, u128)> {
+        let released_duration = timestamp
+            .min(self.interest_release_end)
+            .saturating_sub(self.interest_release_timestamp);
+        if released_duration == 0 || self.liquidity_lent == 0 {
+            return Ok((self.interest_growth_a, self.interest_growth_b));
+        }
+
+        let released_growth = |release_rate: u128| -> Result<u128> {
+            Ok((U256::from(release_rate) * U256::from(released_duration)
+                / U256::from(self.liquidity_lent))
+            .try_into_u128()?)
+        };
+
+        // Overflows allowed, as for the growths of fees.
+        Ok((
+            self.interest_growth_a
+                .wrapping_add(released_growth(self.interest_release_rate_a)?),
+            self.interest_growth_b
+                .wrapping_add(released_growth(self.interest_release_rate_b)?),
+        ))
+    }
+
+    /// Interest growths of the tick at `account_info` up to `timestamp`, or zero if no trade
+    /// position has borrowed at the tick yet (ie. the account is not created). The caller must
+    /// verify the account address.
+    pub fn load_interest_growths<'info>(
+        account_info: &AccountInfo<'info>,
+        timestamp: u64,
+    ) -> Result<(u128, u128)> {
+        if account_info.owner != &crate::ID || account_info.data_is_empty() {
+            return Ok((0, 0));
+        }
+
+        let tick_interest = Account::<TickInterest>::try_from(account_info)?;
+        tick_interest.interest_growths(timestamp)
+    }
+
+    /// The TickInterest at `account_info`, or the default if the account is not created, ie. nothing
+    /// is borrowed or queued at the tick. The caller must verify the account address.
+    pub fn load<'info>(account_info: &AccountInfo<'info>) -> Result<TickInterest> {
+        if account_info.owner != &crate::ID || account_info.data_is_empty() {
+            return Ok(TickInterest::default());
+        }
+
+        let tick_interest = Account::<TickInterest>::try_from(account_info)?;
+        Ok(tick_interest.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tick_interest_tests {
+    use super::*;
+
+    #[test]
+    fn test_add_interest() {
+        let mut tick_interest = TickInterest::default();
+        tick_interest.checkpoint(1_000, 100).unwrap();
+        tick_interest.add_interest(1_000, true, 1_100, 100).unwrap();
+        tick_interest
+            .add_interest(3_000, false, 1_100, 100)
+            .unwrap();
+
+        // Nothing released at once
+        assert_eq!(tick_interest.interest_growths(100).unwrap(), (0, 0));
+
+        assert_eq!(
+            tick_interest.interest_growths(600).unwrap(),
+            (1 << (Q64_RESOLUTION - 1), 3 << (Q64_RESOLUTION - 1))
+        );
+        assert_eq!(
+            tick_interest.interest_growths(5_000).unwrap(),
+            (1 << Q64_RESOLUTION, 3 << Q64_RESOLUTION)
+        );
+    }
+
+    #[test]
+    fn test_add_interest_respreads_remaining() {
+        let mut tick_interest = TickInterest::default();
+        tick_interest.checkpoint(1_000, 0).unwrap();
+        tick_interest.add_interest(1_000, true, 1_000, 0).unwrap();
+
+        tick_interest.checkpoint(1_000, 500).unwrap();
+        tick_interest.add_interest(500, true, 1_500, 500).unwrap();
+        assert_eq!(tick_interest.interest_release_end, 1_500);
+
+        // Half of the first payment released by its end, the rest along with the second
+        assert_eq!(
+            tick_interest.interest_growths(1_000).unwrap().0,
+            1 << Q64_RESOLUTION
+        );
+        assert_eq!(
+            tick_interest.interest_growths(1_500).unwrap().0,
+            3 << (Q64_RESOLUTION - 1)
+        );
+    }
+
+    #[test]
+    fn test_liquidity_added_after_the_borrow_earns_no_interest() {
+        let mut tick_interest = TickInterest::default();
+        tick_interest.checkpoint(1_000, 0).unwrap();
+        tick_interest.add_interest(1_000, true, 1_000, 0).unwrap();
+
+        // A second position lends 1_000 more once the loan's interest is released
+        tick_interest.checkpoint(2_000, 1_000).unwrap();
+        let later_checkpoint = tick_interest.interest_growths(1_000).unwrap().0;
+        assert_eq!(later_checkpoint, 1 << Q64_RESOLUTION);
+
+        let growth = tick_interest.interest_growths(2_000).unwrap().0;
+        assert_eq!(growth - later_checkpoint, 0);
+        // The first position's 1_000 earned all of it
+        assert_eq!((growth * 1_000) >> Q64_RESOLUTION, 1_000);
+    }
+
+    #[test]
+    fn test_interest_released_by_liquidity_and_time() {
+        let mut tick_interest = TickInterest::default();
+        tick_interest.checkpoint(1_000, 0).unwrap();
+        tick_interest.add_interest(1_500, true, 1_000, 0).unwrap();
+
+        // 1_000 lent for the whole release, 2_000 more added halfway
+        tick_interest.checkpoint(3_000, 500).unwrap();
+        let growth_halfway = tick_interest.interest_growths(500).unwrap().0;
+        let growth = tick_interest.interest_growths(1_000).unwrap().0;
+
+        let earned_first = (growth * 1_000) >> Q64_RESOLUTION;
+        let earned_second = ((growth - growth_halfway) * 2_000) >> Q64_RESOLUTION;
+        assert_eq!(earned_first, 1_000);
+        assert_eq!(earned_second, 500);
+    }
+
+    #[test]
+    fn test_just_in_time_liquidity_earns_little_of_repaid_interest() {
+        let mut tick_interest = TickInterest::default();
+        tick_interest.checkpoint(1_000, 0).unwrap();
+
+        // Liquidity added right before a repayment paying the interest of 1_000 seconds, and
+        // withdrawn a second later
+        tick_interest.checkpoint(1_001_000, 1_000).unwrap();
+        tick_interest
+            .add_interest(1_000, true, 2_000, 1_000)
+            .unwrap();
+        let growth_added = tick_interest.interest_growths(1_000).unwrap().0;
+        tick_interest.checkpoint(1_000, 1_001).unwrap();
+
+        let growth = tick_interest.interest_growths(1_001).unwrap().0;
+        assert!(((growth - growth_added) * 1_000_000) >> Q64_RESOLUTION <= 1);
+    }
+
+    #[test]
+    fn test_release_pauses_without_liquidity_lent() {
+        let mut tick_interest = TickInterest::default();
+        tick_interest.checkpoint(1_000, 0).unwrap();
+        tick_interest.add_interest(1_000, true, 1_000, 0).unwrap();
+
+        tick_interest.checkpoint(0, 500).unwrap();
+        tick_interest.checkpoint(1_000, 800).unwrap();
+        assert_eq!(tick_interest.interest_release_end, 1_300);
+
+        assert_eq!(
+            tick_interest.interest_growths(1_300).unwrap().0,
+            1 << Q64_RESOLUTION
+        );
+    }
+
+    #[test]
+    fn test_update_liquidity_borrowed() {
+        let mut tick_interest = TickInterest::default();
+        tick_interest.update_liquidity_borrowed(1_000).unwrap();
+        tick_interest.update_liquidity_borrowed(-400).unwrap();
+        assert_eq!(tick_interest.liquidity_borrowed, 600);
+
+        assert!(tick_interest.update_liquidity_borrowed(-601).is_err());
+    }
+
+    #[test]
+    fn test_update_liquidity_queued() {
+        let mut tick_interest = TickInterest::default();
+        tick_interest.update_liquidity_queued(500).unwrap();
+        tick_interest.update_liquidity_queued(-200).unwrap();
+        assert_eq!(tick_interest.liquidity_queued, 300);
+
+        assert!(tick_interest.update_liquidity_queued(-301).is_err());
+    }
+}
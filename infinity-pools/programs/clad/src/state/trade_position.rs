@@ -1,6 +1,9 @@
 use {
     super::{Globalpool, Tick},
-    crate::errors::ErrorCode,
+    crate::{
+        errors::ErrorCode,
+        math::{get_interest_owed_liquidity, U256},
+    },
     anchor_lang::prelude::*,
 };
 
@@ -29,6 +32,11 @@ pub struct TradePosition {
     pub open_time: u64,     // UNIX time at which the loan was opened (in seconds)
     pub duration: u64,      // The duration of the loan, in seconds
     pub interest_rate: u32, // Interest rate paid upfront, for accounting purposes
+
+    pub is_interest_accrued: bool, // Interest accrues by the second (paid on repay) instead of upfront
+    pub borrow_index_checkpoint: u128, // Globalpool borrow index at the time of loan opening (Q64.64)
+
+    pub maintenance_margin_bps: u16, // Globalpool maintenance margin at the time of loan opening
 }
 
 #[derive(Default, Debug, PartialEq)]
@@ -39,9 +47,24 @@ pub struct TradePositionUpdate {
     // pub ticks: Vec<TickLoan>,
 }
 
+/// Share of a trade position to be repaid, in liquidity and the proportional token amounts.
+#[derive(Default, Debug, PartialEq)]
+pub struct TradePositionRepayment {
+    pub liquidity: u128,
+    pub loan_token_swapped: u64,
+    pub trade_token_amount: u64,
+    pub collateral_amount: u64,
+    pub interest_owed_liquidity: u128,
+}
+
 impl TradePosition {
     pub const LEN: usize = 8 + std::mem::size_of::<TradePosition>();
 
+    pub const MIN_LOAN_DURATION: u64 = 3_600; // 1 hour
+
+    // Health kept above the maintenance margin when withdrawing collateral
+    pub const WITHDRAWAL_HEALTH_BUFFER_BPS: u16 = 500; // 5%
+
     pub fn is_position_empty(position: &TradePosition) -> bool {
         position.loan_token_swapped == 0
     }
@@ -99,6 +122,9 @@ impl TradePosition {
 
         self.liquidity_borrowed = liquidity_borrowed;
 
+        // Later margin changes of the pool don't apply to this loan
+        self.maintenance_margin_bps = globalpool.maintenance_margin_bps;
+
         Ok(())
     }
 
@@ -123,6 +149,90 @@ impl TradePosition {
         self.interest_rate = interest_rate;
     }
 
+    /// Extend the loan by `additional_duration` seconds, up to `max_loan_duration` since opening.
+    pub fn extend_duration(&mut self, additional_duration: u64, max_loan_duration: u64) -> Result<()> {
+        let duration = self
+            .duration
+            .checked_add(additional_duration)
+            .ok_or(ErrorCode::InvalidLoanDuration)?;
+
+        if additional_duration == 0 || duration > max_loan_duration {
+            return Err(ErrorCode::InvalidLoanDuration.into());
+        }
+        self.duration = duration;
+
+        Ok(())
+    }
+
+    pub fn enable_interest_accrual(&mut self, borrow_index: u128) {
+        self.is_interest_accrued = true;
+        self.borrow_index_checkpoint = borrow_index;
+    }
+
+    pub fn update_borrow_index_checkpoint(&mut self, borrow_index: u128) {
+        self.borrow_index_checkpoint = borrow_index;
+    }
+
+    /// Liquidity owed as interest at the given borrow index. Zero if interest was paid upfront.
+    pub fn interest_owed_liquidity(&self, borrow_index: u128) -> Result<u128> {
+        if !self.is_interest_accrued {
+            return Ok(0);
+        }
+
+        Ok(get_interest_owed_liquidity(
+            self.liquidity_borrowed,
+            borrow_index,
+            self.borrow_index_checkpoint,
+        )?)
+    }
+
+    /// Repayment of the whole position, at the given borrow index.
+    pub fn full_repayment(&self, borrow_index: u128) -> Result<TradePositionRepayment> {
+        Ok(TradePositionRepayment {
+            liquidity: self.liquidity_borrowed,
+            loan_token_swapped: self.loan_token_swapped,
+            trade_token_amount: self.trade_token_amount,
+            collateral_amount: self.collateral_amount,
+            interest_owed_liquidity: self.interest_owed_liquidity(borrow_index)?,
+        })
+    }
+
+    /// Repayment of `liquidity` out of the borrowed liquidity, at the given borrow index.
+    /// Token amounts are prorated (rounded down) so that the remainder stays on the position.
+    pub fn partial_repayment(
+        &self,
+        liquidity: u128,
+        borrow_index: u128,
+    ) -> Result<TradePositionRepayment> {
+        if liquidity == 0 || liquidity >= self.liquidity_borrowed {
+            return Err(ErrorCode::InvalidRepaymentAmount.into());
+        }
+
+        let interest_owed_liquidity = if self.is_interest_accrued {
+            get_interest_owed_liquidity(liquidity, borrow_index, self.borrow_index_checkpoint)?
+        } else {
+            0
+        };
+
+        Ok(TradePositionRepayment {
+            liquidity,
+            loan_token_swapped: self.prorate_amount(self.loan_token_swapped, liquidity)?,
+            trade_token_amount: self.prorate_amount(self.trade_token_amount, liquidity)?,
+            collateral_amount: self.prorate_amount(self.collateral_amount, liquidity)?,
+            interest_owed_liquidity,
+        })
+    }
+
+    fn prorate_amount(&self, amount: u64, liquidity: u128) -> Result<u64> {
+        let prorated =
+            U256::from(amount) * U256::from(liquidity) / U256::from(self.liquidity_borrowed);
+        Ok(prorated.try_into_u64()?)
+    }
+
+    pub fn update_liquidity_borrowed(&mut self, liquidity_borrowed: u128) {
+        self.liquidity_borrowed = liquidity_borrowed;
+    }
+
     pub fn update_liquidity_swapped(
         &mut self,
         loan_token_swapped: i64,
@@ -258,3 +368,117 @@ impl TradePosition {
     //     Ok(liquidity)
     // }
 }
+
+#[cfg(test)]
+mod repayment_tests {
+    use super::*;
+    use crate::math::INITIAL_BORROW_INDEX;
+
+    fn test_position() -> TradePosition {
+        TradePosition {
+            liquidity_borrowed: 3_000,
+            loan_token_swapped: 1_000,
+            trade_token_amount: 500,
+            collateral_amount: 100,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_full_repayment() {
+        let repayment = test_position().full_repayment(INITIAL_BORROW_INDEX).unwrap();
+        assert_eq!(
+            repayment,
+            TradePositionRepayment {
+                liquidity: 3_000,
+                loan_token_swapped: 1_000,
+                trade_token_amount: 500,
+                collateral_amount: 100,
+                interest_owed_liquidity: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_partial_repayment_rounds_down() {
+        let repayment = test_position()
+            .partial_repayment(1_000, INITIAL_BORROW_INDEX)
+            .unwrap();
+        assert_eq!(
+            repayment,
+            TradePositionRepayment {
+                liquidity: 1_000,
+                loan_token_swapped: 333,
+                trade_token_amount: 166,
+                collateral_amount: 33,
+                interest_owed_liquidity: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_partial_repayment_interest_owed() {
+        let mut position = test_position();
+        position.enable_interest_accrual(INITIAL_BORROW_INDEX);
+
+        // 10% accrued since opening, owed only on the repaid liquidity
+        let borrow_index = INITIAL_BORROW_INDEX + INITIAL_BORROW_INDEX / 10;
+        let repayment = position.partial_repayment(1_000, borrow_index).unwrap();
+        assert_eq!(repayment.interest_owed_liquidity, 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "InvalidRepaymentAmount")]
+    fn test_partial_repayment_zero() {
+        test_position()
+            .partial_repayment(0, INITIAL_BORROW_INDEX)
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "InvalidRepaymentAmount")]
+    fn test_partial_repayment_whole_position() {
+        test_position()
+            .partial_repayment(3_000, INITIAL_BORROW_INDEX)
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod extend_duration_tests {
+    use super::*;
+
+    #[test]
+    fn test_extend_duration() {
+        let mut position = TradePosition {
+            duration: 3_600,
+            ..Default::default()
+        };
+
+        position.extend_duration(7_200, 864_000).unwrap();
+        assert_eq!(position.duration, 10_800);
+
+        position.extend_duration(853_200, 864_000).unwrap();
+        assert_eq!(position.duration, 864_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "InvalidLoanDuration")]
+    fn test_extend_duration_exceeds_max() {
+        let mut position = TradePosition {
+            duration: 3_600,
+            ..Default::default()
+        };
+        position.extend_duration(860_401, 864_000).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "InvalidLoanDuration")]
+    fn test_extend_duration_zero() {
+        let mut position = TradePosition {
+            duration: 3_600,
+            ..Default::default()
+        };
+        position.extend_duration(0, 864_000).unwrap();
+    }
+}
@@ -3,3 +3,6 @@ mod swap_integration_tests;
 
 #[cfg(test)]
 pub use swap_integration_tests::*;
+
+#[cfg(test)]
+mod swap_fuzz_tests;
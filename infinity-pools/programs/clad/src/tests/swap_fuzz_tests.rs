@@ -0,0 +1,303 @@
+use crate::manager::swap_manager::*;
+use crate::math::*;
+use crate::state::{Globalpool, TickArray, TickUpdate, TICK_ARRAY_SIZE};
+use crate::util::TickSequence;
+use proptest::prelude::*;
+use std::cell::RefCell;
+
+const NUM_TICK_ARRAYS: i32 = 3;
+const NUM_TICKS: i32 = NUM_TICK_ARRAYS * TICK_ARRAY_SIZE;
+
+/// A liquidity position over tick offsets (in tick spacings) from the start of the first tick array.
+#[derive(Debug, Clone)]
+struct TestPosition {
+    lower_offset: i32,
+    upper_offset: i32,
+    liquidity: u128,
+}
+
+fn position_strategy() -> impl Strategy<Value = TestPosition> {
+    (0..NUM_TICKS - 1, 1..NUM_TICKS, 1u128..1_000_000_000_000).prop_map(
+        |(lower_offset, width, liquidity)| TestPosition {
+            lower_offset,
+            upper_offset: (lower_offset + width).min(NUM_TICKS - 1),
+            liquidity,
+        },
+    )
+}
+
+/// Random pool layout: three consecutive tick arrays holding `positions`, with the current tick
+/// anywhere in the middle array.
+#[derive(Debug)]
+struct TestLayout {
+    tick_spacing: u16,
+    start_tick_index: i32,
+    tick_current_index: i32,
+    positions: Vec<TestPosition>,
+}
+
+fn layout_strategy() -> impl Strategy<Value = TestLayout> {
+    (
+        prop::sample::select(vec![1u16, 8, 64, 128]),
+        -4i32..4,
+        0..TICK_ARRAY_SIZE * 128,
+        prop::collection::vec(position_strategy(), 0..8),
+    )
+        .prop_map(|(tick_spacing, array_offset, tick_offset, positions)| {
+            let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing as i32;
+            let start_tick_index = (array_offset - 1) * ticks_in_array;
+            TestLayout {
+                tick_spacing,
+                start_tick_index,
+                tick_current_index: start_tick_index
+                    + ticks_in_array
+                    + tick_offset % ticks_in_array,
+                positions,
+            }
+        })
+}
+
+impl TestLayout {
+    fn tick_index(&self, offset: i32) -> i32 {
+        self.start_tick_index + offset * self.tick_spacing as i32
+    }
+
+    fn active_liquidity(&self, tick_current_index: i32) -> u128 {
+        self.positions
+            .iter()
+            .filter(|position| {
+                self.tick_index(position.lower_offset) <= tick_current_index
+                    && tick_current_index < self.tick_index(position.upper_offset)
+            })
+            .map(|position| position.liquidity)
+            .sum()
+    }
+
+    fn globalpool(
+        &self,
+        fee_rate: u16,
+        protocol_fee_rate: u16,
+        fee_growth_global: u128,
+    ) -> Globalpool {
+        Globalpool {
+            tick_spacing: self.tick_spacing,
+            fee_rate,
+            protocol_fee_rate,
+            fee_growth_global_a: fee_growth_global,
+            fee_growth_global_b: fee_growth_global,
+            liquidity_available: self.active_liquidity(self.tick_current_index),
+            sqrt_price: sqrt_price_from_tick_index(self.tick_current_index),
+            tick_current_index: self.tick_current_index,
+            ..Default::default()
+        }
+    }
+
+    fn tick_arrays(&self) -> Vec<RefCell<TickArray>> {
+        let ticks_in_array = TICK_ARRAY_SIZE * self.tick_spacing as i32;
+        let mut tick_arrays: Vec<TickArray> = (0..NUM_TICK_ARRAYS)
+            .map(|i| TickArray {
+                start_tick_index: self.start_tick_index + i * ticks_in_array,
+                ..Default::default()
+            })
+            .collect();
+
+        for position in self.positions.iter() {
+            let liquidity_net = position.liquidity as i128;
+            for (offset, liquidity_net) in [
+                (position.lower_offset, liquidity_net),
+                (position.upper_offset, -liquidity_net),
+            ] {
+                let tick_index = self.tick_index(offset);
+                let tick_array = &mut tick_arrays[(offset / TICK_ARRAY_SIZE) as usize];
+                let tick = *tick_array.get_tick(tick_index, self.tick_spacing).unwrap();
+                let update = TickUpdate {
+                    initialized: true,
+                    liquidity_net: tick.liquidity_net + liquidity_net,
+                    liquidity_gross: tick.liquidity_gross + position.liquidity,
+                    ..TickUpdate::from(&tick)
+                };
+                tick_array
+                    .update_tick(tick_index, self.tick_spacing, &update)
+                    .unwrap();
+            }
+        }
+
+        tick_arrays.into_iter().map(RefCell::new).collect()
+    }
+
+    /// Sqrt price limit one tick spacing inside the outermost tick array in the swap direction, so
+    /// the swap never runs past the tick arrays.
+    fn sqrt_price_limit(&self, a_to_b: bool) -> u128 {
+        if a_to_b {
+            sqrt_price_from_tick_index(self.tick_index(1))
+        } else {
+            sqrt_price_from_tick_index(self.tick_index(NUM_TICKS - 1))
+        }
+    }
+}
+
+/// Swap on `globalpool` with the tick arrays from the one the swap starts in onwards in the swap
+/// direction.
+fn run_swap(
+    globalpool: &Globalpool,
+    tick_arrays: &[RefCell<TickArray>],
+    amount: u64,
+    sqrt_price_limit: u128,
+    amount_specified_is_input: bool,
+    a_to_b: bool,
+) -> PostSwapUpdate {
+    let ticks_in_array = TICK_ARRAY_SIZE * globalpool.tick_spacing as i32;
+    let start_tick_index = tick_arrays[0].borrow().start_tick_index;
+    // b to a searches exclude the current tick, so they start in the tick array holding the next one
+    let search_tick_index = if a_to_b {
+        globalpool.tick_current_index
+    } else {
+        globalpool.tick_current_index + globalpool.tick_spacing as i32
+    };
+    let array_index = (search_tick_index - start_tick_index).div_euclid(ticks_in_array) as usize;
+
+    let swap_tick_arrays: Vec<&RefCell<TickArray>> = if a_to_b {
+        tick_arrays[..=array_index].iter().rev().collect()
+    } else {
+        tick_arrays[array_index..].iter().collect()
+    };
+    let mut swap_tick_arrays = swap_tick_arrays.into_iter().map(|ta| ta.borrow_mut());
+    let mut tick_sequence = TickSequence::new(
+        swap_tick_arrays.next().unwrap(),
+        swap_tick_arrays.next(),
+        swap_tick_arrays.next(),
+    );
+
+    swap(
+        globalpool,
+        &mut tick_sequence,
+        amount,
+        sqrt_price_limit,
+        amount_specified_is_input,
+        a_to_b,
+        0,
+    )
+    .unwrap()
+}
+
+fn swap_amounts(post_swap: &PostSwapUpdate, a_to_b: bool) -> (u64, u64) {
+    if a_to_b {
+        (post_swap.amount_a, post_swap.amount_b)
+    } else {
+        (post_swap.amount_b, post_swap.amount_a)
+    }
+}
+
+proptest! {
+    #[test]
+    /// Swap invariants over random tick layouts:
+    /// - The specified amount is never exceeded and the price moves towards the limit
+    /// - No tokens are created: the output is worth at most the input at the starting price
+    /// - Fee growth and protocol fees never decrease
+    /// - Liquidity after crossing ticks matches the positions in range of the final tick
+    fn test_swap_invariants(
+        layout in layout_strategy(),
+        amount in 1u64..1_000_000_000_000,
+        fee_rate in 0..=MAX_FEE_RATE,
+        protocol_fee_rate in 0..=MAX_PROTOCOL_FEE_RATE,
+        fee_growth_global in 0u128..(u64::MAX as u128) << 64,
+        amount_specified_is_input in any::<bool>(),
+        a_to_b in any::<bool>(),
+    ) {
+        let globalpool = layout.globalpool(fee_rate, protocol_fee_rate, fee_growth_global);
+        let tick_arrays = layout.tick_arrays();
+        let sqrt_price_limit = layout.sqrt_price_limit(a_to_b);
+
+        let post_swap = run_swap(
+            &globalpool,
+            &tick_arrays,
+            amount,
+            sqrt_price_limit,
+            amount_specified_is_input,
+            a_to_b,
+        );
+        let (amount_in, amount_out) = swap_amounts(&post_swap, a_to_b);
+
+        if amount_specified_is_input {
+            assert!(amount_in <= amount);
+        } else {
+            assert!(amount_out <= amount);
+        }
+
+        if a_to_b {
+            assert!(post_swap.next_sqrt_price <= globalpool.sqrt_price);
+            assert!(post_swap.next_sqrt_price >= sqrt_price_limit);
+        } else {
+            assert!(post_swap.next_sqrt_price >= globalpool.sqrt_price);
+            assert!(post_swap.next_sqrt_price <= sqrt_price_limit);
+        }
+
+        // Price of A in B is sqrt_price^2 >> 128, which only decreases through an a to b swap
+        // and only increases through a b to a swap
+        let price_x128 = U256::from(globalpool.sqrt_price) * U256::from(globalpool.sqrt_price);
+        if a_to_b {
+            assert!(U256::from(amount_out) << 128 <= U256::from(amount_in) * price_x128);
+        } else {
+            assert!(U256::from(amount_out) * price_x128 <= U256::from(amount_in) << 128);
+        }
+
+        let fee_growth_global_input = if a_to_b {
+            globalpool.fee_growth_global_a
+        } else {
+            globalpool.fee_growth_global_b
+        };
+        assert!(post_swap.next_fee_growth_global >= fee_growth_global_input);
+        assert!(post_swap.next_protocol_fee <= amount_in);
+
+        assert_eq!(
+            post_swap.next_liquidity,
+            layout.active_liquidity(post_swap.next_tick_index)
+        );
+    }
+
+    #[test]
+    /// Swapping the output of an exact input swap back never returns more than the original input.
+    fn test_swap_round_trip(
+        layout in layout_strategy(),
+        amount in 1u64..1_000_000_000_000,
+        fee_rate in 0..=MAX_FEE_RATE,
+        protocol_fee_rate in 0..=MAX_PROTOCOL_FEE_RATE,
+        a_to_b in any::<bool>(),
+    ) {
+        let globalpool = layout.globalpool(fee_rate, protocol_fee_rate, 0);
+        let tick_arrays = layout.tick_arrays();
+
+        let post_swap = run_swap(
+            &globalpool,
+            &tick_arrays,
+            amount,
+            layout.sqrt_price_limit(a_to_b),
+            true,
+            a_to_b,
+        );
+        let (amount_in, amount_out) = swap_amounts(&post_swap, a_to_b);
+        if amount_out == 0 {
+            // Nothing to swap back without liquidity in range
+            return Ok(());
+        }
+
+        let globalpool = Globalpool {
+            liquidity_available: post_swap.next_liquidity,
+            sqrt_price: post_swap.next_sqrt_price,
+            tick_current_index: post_swap.next_tick_index,
+            ..globalpool
+        };
+        let post_swap_back = run_swap(
+            &globalpool,
+            &tick_arrays,
+            amount_out,
+            layout.sqrt_price_limit(!a_to_b),
+            true,
+            !a_to_b,
+        );
+        let (amount_in_back, amount_out_back) = swap_amounts(&post_swap_back, !a_to_b);
+
+        assert!(amount_in_back <= amount_out);
+        assert!(amount_out_back <= amount_in);
+    }
+}
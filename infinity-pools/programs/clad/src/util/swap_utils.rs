@@ -1,9 +1,48 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 
-use crate::{manager::swap_manager::PostSwapUpdate, state::Globalpool};
+use crate::{
+    manager::swap_manager::PostSwapUpdate,
+    state::{Globalpool, LimitOrderArray, TickArray},
+};
 
-use super::{transfer_from_owner_to_vault, transfer_from_vault_to_owner};
+use super::{transfer_from_owner_to_vault, transfer_from_vault_to_owner, TickSequence};
+
+// Tick arrays of a swap, in swap order. A swap that needs fewer than three tick arrays can pass the
+// same one again, which is left out of the sequence. Any other load error fails the swap.
+pub fn load_tick_sequence<'a, 'info>(
+    tick_array_0: &'a AccountLoader<'info, TickArray>,
+    tick_array_1: Option<&'a AccountLoader<'info, TickArray>>,
+    tick_array_2: Option<&'a AccountLoader<'info, TickArray>>,
+) -> Result<TickSequence<'a>> {
+    let key_0 = tick_array_0.key();
+    let key_1 = tick_array_1.map(|tick_array| tick_array.key());
+
+    let ta0 = tick_array_0.load_mut()?;
+    let ta1 = match tick_array_1 {
+        Some(tick_array) if tick_array.key() != key_0 => Some(tick_array.load_mut()?),
+        _ => None,
+    };
+    let ta2 = match tick_array_2 {
+        Some(tick_array) if tick_array.key() != key_0 && Some(tick_array.key()) != key_1 => {
+            Some(tick_array.load_mut()?)
+        }
+        _ => None,
+    };
+
+    Ok(TickSequence::new(ta0, ta1, ta2))
+}
+
+// LimitOrderArrays passed to a swap as remaining accounts. The tick arrays with pending limit orders
+// need theirs, or the swap fails once it reaches them.
+pub fn load_limit_order_arrays<'info>(
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<Vec<AccountLoader<'info, LimitOrderArray>>> {
+    remaining_accounts
+        .iter()
+        .map(AccountLoader::<LimitOrderArray>::try_from)
+        .collect()
+}
 
 pub fn update_and_swap_globalpool<'info>(
     globalpool: &mut Account<'info, Globalpool>,
@@ -15,15 +54,20 @@ pub fn update_and_swap_globalpool<'info>(
     token_program: &Program<'info, Token>,
     swap_update: PostSwapUpdate,
     is_token_fee_in_a: bool,
+    timestamp: u64,
 ) -> Result<()> {
     globalpool.update_after_swap(
         swap_update.next_liquidity,
+        swap_update.next_liquidity_limit_orders,
         swap_update.next_tick_index,
         swap_update.next_sqrt_price,
         swap_update.next_fee_growth_global,
+        swap_update.next_reward_infos,
+        swap_update.next_volatility_info,
         swap_update.next_protocol_fee,
         is_token_fee_in_a,
-    );
+        timestamp,
+    )?;
 
     perform_swap(
         globalpool,
@@ -97,3 +141,84 @@ fn perform_swap<'info>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod load_tick_sequence_tests {
+    use {super::*, anchor_lang::Discriminator};
+
+    fn tick_array_data(start_tick_index: i32) -> Vec<u8> {
+        let tick_array = TickArray {
+            start_tick_index,
+            ..TickArray::default()
+        };
+        let mut data = TickArray::discriminator().to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&tick_array));
+        data
+    }
+
+    #[test]
+    fn repeated_tick_arrays_are_left_out() {
+        let (key_0, key_1) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (mut lamports_0, mut lamports_1) = (0, 0);
+        let (mut data_0, mut data_1) = (tick_array_data(0), tick_array_data(5632));
+        let account_0 = AccountInfo::new(
+            &key_0,
+            false,
+            true,
+            &mut lamports_0,
+            &mut data_0,
+            &crate::ID,
+            false,
+            0,
+        );
+        let account_1 = AccountInfo::new(
+            &key_1,
+            false,
+            true,
+            &mut lamports_1,
+            &mut data_1,
+            &crate::ID,
+            false,
+            0,
+        );
+        let tick_array_0 = AccountLoader::<TickArray>::try_from(&account_0).unwrap();
+        let tick_array_1 = AccountLoader::<TickArray>::try_from(&account_1).unwrap();
+
+        // Passed again for the third array
+        let tick_sequence =
+            load_tick_sequence(&tick_array_0, Some(&tick_array_1), Some(&tick_array_1)).unwrap();
+        tick_sequence.get_tick(1, 5632, 64).unwrap();
+        assert!(tick_sequence.get_tick(2, 5632, 64).is_err());
+    }
+
+    #[test]
+    fn read_only_tick_array_fails() {
+        let (key_0, key_1) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (mut lamports_0, mut lamports_1) = (0, 0);
+        let (mut data_0, mut data_1) = (tick_array_data(0), tick_array_data(5632));
+        let account_0 = AccountInfo::new(
+            &key_0,
+            false,
+            true,
+            &mut lamports_0,
+            &mut data_0,
+            &crate::ID,
+            false,
+            0,
+        );
+        let account_1 = AccountInfo::new(
+            &key_1,
+            false,
+            false,
+            &mut lamports_1,
+            &mut data_1,
+            &crate::ID,
+            false,
+            0,
+        );
+        let tick_array_0 = AccountLoader::<TickArray>::try_from(&account_0).unwrap();
+        let tick_array_1 = AccountLoader::<TickArray>::try_from(&account_1).unwrap();
+
+        assert!(load_tick_sequence(&tick_array_0, Some(&tick_array_1), None).is_err());
+    }
+}
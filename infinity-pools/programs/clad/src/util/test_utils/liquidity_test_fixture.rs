@@ -113,6 +113,7 @@ impl LiquidityTestFixture {
             tick,
             self.globalpool.fee_growth_global_a,
             self.globalpool.fee_growth_global_b,
+            &self.globalpool.reward_infos,
         )
         .unwrap();
 
@@ -141,6 +142,7 @@ impl LiquidityTestFixture {
 
     pub fn apply_update(&mut self, update: &ModifyLiquidityUpdate) {
         self.globalpool.liquidity_available = update.globalpool_liquidity;
+        self.globalpool.reward_infos = update.reward_infos;
         self.tick_lower.update(&update.tick_lower_update);
         self.tick_upper.update(&update.tick_upper_update);
         self.position.update(&update.position_update);
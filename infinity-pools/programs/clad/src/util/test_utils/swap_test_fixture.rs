@@ -141,6 +141,8 @@ impl SwapTestFixture {
                 start_tick_index: array_start_tick_index,
                 ticks: [Tick::default(); TICK_ARRAY_SIZE_USIZE],
                 globalpool: Pubkey::default(),
+                limit_order_range_count: 0,
+                funder: Pubkey::default(),
             };
 
             if array.is_none() {
@@ -5,6 +5,7 @@ use std::cell::RefMut;
 
 pub struct TickSequence<'info> {
     arrays: Vec<RefMut<'info, TickArray>>,
+    limit_order_arrays: Vec<Option<RefMut<'info, LimitOrderArray>>>,
 }
 
 impl<'info> TickSequence<'info> {
@@ -21,7 +22,124 @@ impl<'info> TickSequence<'info> {
         if ta2.is_some() {
             vec.push(ta2.unwrap());
         }
-        Self { arrays: vec }
+        let limit_order_arrays = vec.iter().map(|_| None).collect();
+        Self {
+            arrays: vec,
+            limit_order_arrays,
+        }
+    }
+
+    /// Add the LimitOrderArray of one of the tick arrays in this sequence, so the limit orders
+    /// pending on its ranges can be filled.
+    ///
+    /// # Errors
+    /// - `InvalidLimitOrderArray` - The LimitOrderArray is not for any tick array in this sequence.
+    pub fn add_limit_order_array(
+        &mut self,
+        limit_order_array: RefMut<'info, LimitOrderArray>,
+    ) -> Result<()> {
+        let array_index = self
+            .arrays
+            .iter()
+            .position(|array| limit_order_array.is_for_tick_array(array))
+            .ok_or(ErrorCode::InvalidLimitOrderArray)?;
+        self.limit_order_arrays[array_index] = Some(limit_order_array);
+        Ok(())
+    }
+
+    /// Get the limit orders on the range starting at the given tick-index
+    ///
+    /// # Parameters
+    /// - `array_index` - the array index that the range of this given tick-index would be stored in
+    /// - `tick_index` - the lower tick index of the range
+    /// - `tick_spacing` - A u8 integer of the tick spacing for this globalpool
+    ///
+    /// # Returns
+    /// - `Some(LimitOrderRange)`: The limit orders on the range
+    /// - `None`: No limit orders are pending in the tick array, or the range is not in it
+    /// - `TickArrayIndexOutofBounds` - The provided array-index is out of bounds
+    /// - `LimitOrderArrayMissing` - Limit orders are pending in the tick array, but its
+    ///                              LimitOrderArray was not added
+    pub fn get_limit_order_range(
+        &self,
+        array_index: usize,
+        tick_index: i32,
+        tick_spacing: u16,
+    ) -> Result<Option<LimitOrderRange>> {
+        let array = match self.arrays.get(array_index) {
+            Some(array) => array,
+            None => return Err(ErrorCode::TickArrayIndexOutofBounds.into()),
+        };
+        if array.limit_order_range_count == 0 {
+            return Ok(None);
+        }
+
+        let limit_order_array = match self.limit_order_arrays[array_index].as_ref() {
+            Some(limit_order_array) => limit_order_array,
+            None => return Err(ErrorCode::LimitOrderArrayMissing.into()),
+        };
+        Ok(limit_order_array
+            .range_offset(tick_index, tick_spacing)
+            .map(|offset| limit_order_array.ranges[offset]))
+    }
+
+    /// Get the liquidity of the limit orders pending on the range the given tick-index is in.
+    ///
+    /// # Returns
+    /// - `u128`: The pending liquidity, or zero if the range is not in any tick array of this
+    ///           sequence
+    /// - `LimitOrderArrayMissing` - Limit orders are pending in the tick array of the range, but its
+    ///                              LimitOrderArray was not added
+    pub fn get_limit_order_liquidity_in_range(
+        &self,
+        tick_index: i32,
+        tick_spacing: u16,
+    ) -> Result<u128> {
+        let range_tick_index = tick_index.div_euclid(tick_spacing as i32) * tick_spacing as i32;
+        let array_index = match self
+            .arrays
+            .iter()
+            .position(|array| array.check_in_array_bounds(range_tick_index, tick_spacing))
+        {
+            Some(array_index) => array_index,
+            None => return Ok(0),
+        };
+
+        Ok(self
+            .get_limit_order_range(array_index, range_tick_index, tick_spacing)?
+            .map_or(0, |range| range.liquidity))
+    }
+
+    /// Updates the limit orders on the range starting at the given tick-index, and counts the range
+    /// in or out of the tick array's ranges with pending orders.
+    ///
+    /// # Errors
+    /// - `TickArrayIndexOutofBounds` - The provided array-index is out of bounds
+    /// - `LimitOrderArrayMissing` - The LimitOrderArray of the tick array was not added
+    /// - `InvalidLimitOrderTickIndex` - The range is not in the tick array
+    pub fn update_limit_order_range(
+        &mut self,
+        array_index: usize,
+        tick_index: i32,
+        tick_spacing: u16,
+        range: &LimitOrderRange,
+    ) -> Result<()> {
+        let array = match self.arrays.get_mut(array_index) {
+            Some(array) => array,
+            None => return Err(ErrorCode::TickArrayIndexOutofBounds.into()),
+        };
+        let limit_order_array = match self.limit_order_arrays[array_index].as_mut() {
+            Some(limit_order_array) => limit_order_array,
+            None => return Err(ErrorCode::LimitOrderArrayMissing.into()),
+        };
+
+        let prev_range = limit_order_array.get_range(tick_index, tick_spacing)?;
+        limit_order_array.update_range(tick_index, tick_spacing, range)?;
+        array.update_limit_order_range_count(
+            prev_range.has_pending_orders(),
+            range.has_pending_orders(),
+        );
+        Ok(())
     }
 
     /// Get the Tick object at the given tick-index & tick-spacing
@@ -89,6 +207,32 @@ impl<'info> TickSequence<'info> {
         }
     }
 
+    /// Get the index of the first array from `start_array_index` on to search the next initialized
+    /// tick from `tick_index` in, after a swap step moved past the arrays before it without
+    /// crossing an initialized tick.
+    ///
+    /// # Parameters
+    /// - `tick_index` - the tick index to start searching from
+    /// - `tick_spacing` - A u8 integer of the tick spacing for this globalpool
+    /// - `a_to_b` - The direction of the trade, see `get_next_initialized_tick_index`
+    /// - `start_array_index` - the array index to start looking from
+    ///
+    /// # Errors
+    /// - `TickArraySequenceInvalidIndex` - No array of the sequence from `start_array_index` on covers the tick-index.
+    pub fn get_search_array_index(
+        &self,
+        tick_index: i32,
+        tick_spacing: u16,
+        a_to_b: bool,
+        start_array_index: usize,
+    ) -> Result<usize> {
+        (start_array_index..self.arrays.len())
+            .find(|array_index| {
+                self.arrays[*array_index].in_search_range(tick_index, tick_spacing, !a_to_b)
+            })
+            .ok_or(ErrorCode::TickArraySequenceInvalidIndex.into())
+    }
+
     /// Get the next initialized tick in the provided tick range
     ///
     /// # Parameters
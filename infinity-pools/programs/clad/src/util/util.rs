@@ -35,6 +35,19 @@ pub fn verify_position_authority<'info>(
     Ok(())
 }
 
+/// Verify that the position token is still held, for instructions that act on a position
+/// without its authority's signature (e.g. liquidations).
+///
+/// # Arguments
+///
+/// * `position_token_account`
+pub fn verify_position_token_held(position_token_account: &TokenAccount) -> Result<()> {
+    if position_token_account.amount != 1 {
+        return Err(ErrorCode::InvalidPositionTokenAmount.into());
+    }
+    Ok(())
+}
+
 fn validate_owner(expected_owner: &Pubkey, owner_account_info: &AccountInfo) -> Result<()> {
     if expected_owner != owner_account_info.key || !owner_account_info.is_signer {
         return Err(ErrorCode::MissingOrInvalidDelegate.into());
@@ -0,0 +1,134 @@
+use {
+    anchor_lang::{error::ErrorCode, prelude::*, AccountDeserialize, Discriminator},
+    clad::state::{Globalpool, LimitOrderArray, Tick, TickArray, TICK_ARRAY_SIZE},
+};
+
+/// Deserialize a Globalpool from its account data.
+pub fn parse_globalpool(data: &[u8]) -> Result<Globalpool> {
+    Globalpool::try_deserialize(&mut &data[..])
+}
+
+/// Deserialize a TickArray (zero-copy) from its account data.
+pub fn parse_tick_array(data: &[u8]) -> Result<TickArray> {
+    if data.len() < 8 + std::mem::size_of::<TickArray>() {
+        return Err(ErrorCode::AccountDidNotDeserialize.into());
+    }
+    if data[..8] != TickArray::discriminator() {
+        return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+
+    bytemuck::try_pod_read_unaligned(&data[8..8 + std::mem::size_of::<TickArray>()])
+        .map_err(|_| ErrorCode::AccountDidNotDeserialize.into())
+}
+
+/// Deserialize a LimitOrderArray (zero-copy) from its account data.
+pub fn parse_limit_order_array(data: &[u8]) -> Result<LimitOrderArray> {
+    if data.len() < 8 + std::mem::size_of::<LimitOrderArray>() {
+        return Err(ErrorCode::AccountDidNotDeserialize.into());
+    }
+    if data[..8] != LimitOrderArray::discriminator() {
+        return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+
+    bytemuck::try_pod_read_unaligned(&data[8..8 + std::mem::size_of::<LimitOrderArray>()])
+        .map_err(|_| ErrorCode::AccountDidNotDeserialize.into())
+}
+
+/// Start tick index of the TickArray that holds `tick_index`.
+pub fn get_start_tick_index(tick_index: i32, tick_spacing: u16) -> i32 {
+    let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing as i32;
+    tick_index.div_euclid(ticks_in_array) * ticks_in_array
+}
+
+/// Address of the TickArray starting at `start_tick_index`.
+pub fn get_tick_array_address(globalpool: &Pubkey, start_tick_index: i32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"tick_array",
+            globalpool.as_ref(),
+            start_tick_index.to_string().as_bytes(),
+        ],
+        &clad::ID,
+    )
+    .0
+}
+
+/// Address of the LimitOrderArray of the TickArray at `tick_array`.
+pub fn get_limit_order_array_address(tick_array: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"limit_order_array", tick_array.as_ref()], &clad::ID).0
+}
+
+/// Start tick indexes of the (up to 3) TickArrays a swap traverses, in swap order. Swaps from B to
+/// A search from one tick-spacing above the current tick, like the program's tick sequence.
+pub fn get_swap_tick_array_start_indexes(
+    tick_current_index: i32,
+    tick_spacing: u16,
+    a_to_b: bool,
+) -> Vec<i32> {
+    let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing as i32;
+    let shift = if a_to_b { 0 } else { tick_spacing as i32 };
+
+    let mut start_tick_index = get_start_tick_index(tick_current_index + shift, tick_spacing);
+    let mut start_tick_indexes = Vec::with_capacity(3);
+    while start_tick_indexes.len() < 3
+        && Tick::check_is_valid_start_tick(start_tick_index, tick_spacing)
+    {
+        start_tick_indexes.push(start_tick_index);
+        start_tick_index += if a_to_b {
+            -ticks_in_array
+        } else {
+            ticks_in_array
+        };
+    }
+    start_tick_indexes
+}
+
+#[cfg(test)]
+mod account_tests {
+    use {super::*, clad::state::MIN_TICK_INDEX};
+
+    #[test]
+    fn test_get_start_tick_index() {
+        assert_eq!(get_start_tick_index(0, 64), 0);
+        assert_eq!(get_start_tick_index(5631, 64), 0);
+        assert_eq!(get_start_tick_index(5632, 64), 5632);
+        assert_eq!(get_start_tick_index(-1, 64), -5632);
+    }
+
+    #[test]
+    fn test_swap_tick_array_start_indexes() {
+        assert_eq!(
+            get_swap_tick_array_start_indexes(100, 64, true),
+            vec![0, -5632, -11264]
+        );
+        assert_eq!(
+            get_swap_tick_array_start_indexes(100, 64, false),
+            vec![0, 5632, 11264]
+        );
+        // The last tick of an array searches from the next array when swapping from B to A
+        assert_eq!(
+            get_swap_tick_array_start_indexes(5631, 64, false),
+            vec![5632, 11264, 16896]
+        );
+    }
+
+    #[test]
+    fn test_swap_tick_array_start_indexes_at_min_tick() {
+        let start_tick_indexes = get_swap_tick_array_start_indexes(MIN_TICK_INDEX, 64, true);
+        assert_eq!(start_tick_indexes, vec![-444928]);
+    }
+
+    #[test]
+    fn test_parse_tick_array() {
+        let mut tick_array = TickArray::default();
+        tick_array.start_tick_index = 5632;
+
+        let mut data = TickArray::discriminator().to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&tick_array));
+        let start_tick_index = parse_tick_array(&data).unwrap().start_tick_index;
+        assert_eq!(start_tick_index, 5632);
+
+        data[0] ^= 1;
+        assert!(parse_tick_array(&data).is_err());
+    }
+}
@@ -0,0 +1,10 @@
+//! Off-chain quoting for clad swaps and trade positions.
+//!
+//! Quotes run the program's own managers and math (through its `no-entrypoint` build) on
+//! deserialized `Globalpool` and `TickArray` accounts, so they match what the program executes.
+
+pub mod account;
+pub mod loan;
+pub mod swap;
+
+pub use {account::*, loan::*, swap::*};
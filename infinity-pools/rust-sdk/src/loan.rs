@@ -0,0 +1,179 @@
+use {
+    anchor_lang::prelude::*,
+    clad::{
+        errors::ErrorCode,
+        manager::loan_manager,
+        math::convert_to_liquidity_delta,
+        state::{Globalpool, Tick},
+    },
+};
+
+#[derive(Debug, PartialEq)]
+pub struct LoanQuote {
+    pub borrow_amount: u64,
+    pub is_borrow_a: bool,
+    pub interest_rate_annual_bps: u16,
+    pub collateral_amount: u64,
+    // Paid upfront when opening, zero for Globalpools that accrue interest until repayment
+    pub interest_amount: u64,
+}
+
+/// Quote opening a trade position that borrows `liquidity_amount` at `tick_lower` & `tick_upper`,
+/// as the `open_trade_position` instruction would. `swapped_amount_out` is the trade token amount
+/// the borrowed token swaps to (eg. quoted by the swap router).
+pub fn quote_open_trade_position(
+    globalpool: &Globalpool,
+    tick_lower: &Tick,
+    tick_upper: &Tick,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    liquidity_amount: u128,
+    loan_duration: u64,
+    swapped_amount_out: u64,
+) -> Result<LoanQuote> {
+    let tick_current_index = globalpool.tick_current_index;
+
+    if tick_lower_index > tick_upper_index {
+        return Err(ErrorCode::InvalidTickRange.into());
+    }
+    if tick_lower_index <= tick_current_index && tick_upper_index >= tick_current_index {
+        return Err(ErrorCode::InvalidTickRangeAgainstCurrentTick.into());
+    }
+
+    let liquidity_delta = convert_to_liquidity_delta(liquidity_amount, true)?;
+    let (borrow_amount, is_borrow_a) = loan_manager::calculate_loan_liquidity_token_delta(
+        tick_current_index,
+        tick_lower_index,
+        tick_upper_index,
+        liquidity_delta,
+    )?;
+
+    // Priced at the ticks' liquidity gross after borrowing, which includes the borrowed liquidity
+    let interest_rate_annual_bps = loan_manager::_calculate_loan_interest_rate_annual(
+        globalpool,
+        tick_lower.liquidity_gross + liquidity_amount,
+        tick_upper.liquidity_gross + liquidity_amount,
+        liquidity_amount,
+        true,
+    )?;
+
+    let collateral_amount = loan_manager::calculate_collateral(
+        liquidity_amount,
+        tick_lower_index,
+        tick_upper_index,
+        swapped_amount_out,
+        is_borrow_a,
+    )?;
+
+    let interest_amount = if globalpool.interest_accrual_enabled {
+        0
+    } else {
+        loan_manager::calculate_prorated_interest_amount(
+            collateral_amount,
+            interest_rate_annual_bps,
+            loan_duration,
+        )?
+    };
+
+    Ok(LoanQuote {
+        borrow_amount,
+        is_borrow_a,
+        interest_rate_annual_bps,
+        collateral_amount,
+        interest_amount,
+    })
+}
+
+#[cfg(test)]
+mod loan_tests {
+    use {super::*, clad::math::sqrt_price_from_tick_index};
+
+    fn test_globalpool(interest_accrual_enabled: bool) -> Globalpool {
+        Globalpool {
+            tick_spacing: 64,
+            sqrt_price: sqrt_price_from_tick_index(-1000),
+            tick_current_index: -1000,
+            interest_base_rate_bps: 100,
+            interest_slope1_bps: 400,
+            interest_optimal_utilization_bps: 8000,
+            interest_slope2_bps: 6000,
+            interest_accrual_enabled,
+            ..Default::default()
+        }
+    }
+
+    fn test_tick(liquidity_gross: u128) -> Tick {
+        Tick {
+            initialized: true,
+            liquidity_gross,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_quote_open_trade_position() {
+        let globalpool = test_globalpool(false);
+        let tick = test_tick(3_000_000_000);
+
+        let quote = quote_open_trade_position(
+            &globalpool,
+            &tick,
+            &tick,
+            1024,
+            2048,
+            1_000_000_000,
+            86_400,
+            50_000_000,
+        )
+        .unwrap();
+
+        // Range is above the current tick, so token A is borrowed against token B collateral
+        assert!(quote.is_borrow_a);
+        assert!(quote.borrow_amount > 0);
+        // 25% utilization: base rate + slope1 * 25 / 80
+        assert_eq!(quote.interest_rate_annual_bps, 225);
+        assert!(quote.collateral_amount > 0);
+        assert_eq!(
+            quote.interest_amount,
+            loan_manager::calculate_prorated_interest_amount(quote.collateral_amount, 225, 86_400)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_quote_open_trade_position_accruing_interest() {
+        let globalpool = test_globalpool(true);
+        let tick = test_tick(3_000_000_000);
+
+        let quote = quote_open_trade_position(
+            &globalpool,
+            &tick,
+            &tick,
+            1024,
+            2048,
+            1_000_000_000,
+            86_400,
+            50_000_000,
+        )
+        .unwrap();
+        assert_eq!(quote.interest_amount, 0);
+    }
+
+    #[test]
+    fn test_quote_open_trade_position_across_current_tick() {
+        let globalpool = test_globalpool(false);
+        let tick = test_tick(3_000_000_000);
+
+        assert!(quote_open_trade_position(
+            &globalpool,
+            &tick,
+            &tick,
+            -2048,
+            1024,
+            1_000_000_000,
+            86_400,
+            0,
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,189 @@
+use {
+    anchor_lang::prelude::*,
+    clad::{
+        errors::ErrorCode,
+        manager::swap_manager,
+        math::{MAX_SQRT_PRICE_X64, MIN_SQRT_PRICE_X64},
+        state::{Globalpool, LimitOrderArray, TickArray},
+        util::TickSequence,
+    },
+    std::cell::RefCell,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct SwapQuote {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub protocol_fee: u64,
+    pub next_sqrt_price: u128,
+    pub next_tick_index: i32,
+}
+
+/// Sqrt price limit that lets a swap run until its amount or tick arrays are exhausted.
+pub fn default_sqrt_price_limit(a_to_b: bool) -> u128 {
+    if a_to_b {
+        MIN_SQRT_PRICE_X64
+    } else {
+        MAX_SQRT_PRICE_X64
+    }
+}
+
+/// Quote a swap on `globalpool` across `tick_arrays` (in swap order, see
+/// `get_swap_tick_array_start_indexes`), exactly as the `swap` instruction would execute it at
+/// `timestamp`. `limit_order_arrays` are those of the tick arrays with pending limit orders, which
+/// the swap fills. The given accounts are not modified.
+pub fn quote_swap(
+    globalpool: &Globalpool,
+    tick_arrays: &[TickArray],
+    limit_order_arrays: &[LimitOrderArray],
+    amount: u64,
+    sqrt_price_limit: u128,
+    amount_specified_is_input: bool,
+    a_to_b: bool,
+    timestamp: u64,
+) -> Result<SwapQuote> {
+    if tick_arrays.is_empty() || tick_arrays.len() > 3 {
+        return Err(ErrorCode::InvalidTickArraySequence.into());
+    }
+
+    let tick_arrays: Vec<RefCell<TickArray>> = tick_arrays
+        .iter()
+        .map(|tick_array| RefCell::new(*tick_array))
+        .collect();
+    let limit_order_arrays: Vec<RefCell<LimitOrderArray>> = limit_order_arrays
+        .iter()
+        .map(|limit_order_array| RefCell::new(*limit_order_array))
+        .collect();
+    let mut swap_tick_sequence = TickSequence::new(
+        tick_arrays[0].borrow_mut(),
+        tick_arrays.get(1).map(|tick_array| tick_array.borrow_mut()),
+        tick_arrays.get(2).map(|tick_array| tick_array.borrow_mut()),
+    );
+
+    for limit_order_array in limit_order_arrays.iter() {
+        swap_tick_sequence.add_limit_order_array(limit_order_array.borrow_mut())?;
+    }
+
+    let swap_update = swap_manager::swap(
+        globalpool,
+        &mut swap_tick_sequence,
+        amount,
+        sqrt_price_limit,
+        amount_specified_is_input,
+        a_to_b,
+        timestamp,
+    )?;
+
+    let (amount_in, amount_out) = if a_to_b {
+        (swap_update.amount_a, swap_update.amount_b)
+    } else {
+        (swap_update.amount_b, swap_update.amount_a)
+    };
+
+    Ok(SwapQuote {
+        amount_in,
+        amount_out,
+        protocol_fee: swap_update.next_protocol_fee,
+        next_sqrt_price: swap_update.next_sqrt_price,
+        next_tick_index: swap_update.next_tick_index,
+    })
+}
+
+#[cfg(test)]
+mod swap_tests {
+    use {
+        super::*,
+        crate::get_swap_tick_array_start_indexes,
+        clad::math::{sqrt_price_from_tick_index, tick_index_from_sqrt_price},
+    };
+
+    fn test_globalpool() -> Globalpool {
+        Globalpool {
+            tick_spacing: 64,
+            fee_rate: 3000,
+            liquidity_available: 1_000_000_000_000,
+            sqrt_price: sqrt_price_from_tick_index(100),
+            tick_current_index: 100,
+            ..Default::default()
+        }
+    }
+
+    fn test_tick_arrays(globalpool: &Globalpool, a_to_b: bool) -> Vec<TickArray> {
+        get_swap_tick_array_start_indexes(
+            globalpool.tick_current_index,
+            globalpool.tick_spacing,
+            a_to_b,
+        )
+        .into_iter()
+        .map(|start_tick_index| TickArray {
+            start_tick_index,
+            ..Default::default()
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_quote_swap_exact_input() {
+        let globalpool = test_globalpool();
+        let tick_arrays = test_tick_arrays(&globalpool, true);
+
+        let quote = quote_swap(
+            &globalpool,
+            &tick_arrays,
+            &[],
+            1_000_000,
+            default_sqrt_price_limit(true),
+            true,
+            true,
+            0,
+        )
+        .unwrap();
+
+        assert_eq!(quote.amount_in, 1_000_000);
+        // Price at tick 100 is ~1.01 B per A, less the 0.3% fee
+        assert!(quote.amount_out > 1_000_000 && quote.amount_out < 1_010_000);
+        assert!(quote.next_sqrt_price < globalpool.sqrt_price);
+        assert_eq!(
+            quote.next_tick_index,
+            tick_index_from_sqrt_price(&quote.next_sqrt_price)
+        );
+    }
+
+    #[test]
+    fn test_quote_swap_exact_output() {
+        let globalpool = test_globalpool();
+        let tick_arrays = test_tick_arrays(&globalpool, false);
+
+        let quote = quote_swap(
+            &globalpool,
+            &tick_arrays,
+            &[],
+            1_000_000,
+            default_sqrt_price_limit(false),
+            false,
+            false,
+            0,
+        )
+        .unwrap();
+
+        assert_eq!(quote.amount_out, 1_000_000);
+        assert!(quote.amount_in > 1_000_000);
+        assert!(quote.next_sqrt_price > globalpool.sqrt_price);
+    }
+
+    #[test]
+    fn test_quote_swap_without_tick_arrays() {
+        let globalpool = test_globalpool();
+        assert!(quote_swap(
+            &globalpool,
+            &[],
+            &[],
+            1_000,
+            MIN_SQRT_PRICE_X64,
+            true,
+            true,
+            0
+        )
+        .is_err());
+    }
+}
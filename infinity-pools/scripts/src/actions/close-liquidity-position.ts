@@ -11,7 +11,11 @@ import { ParsableGlobalpool, ParsableLiquidityPosition } from '../types/parsing'
 import { getAccountData } from '../utils'
 import { createAndMintToManyATAs } from '../utils/token'
 import { createTransactionChained } from '../utils/txix'
-import { getTickArrayKeyFromTickIndex } from '../utils/tick-arrays'
+import {
+  getLimitOrderArrayKey,
+  getTickArrayKeyFromTickIndex,
+  getTickInterestKey,
+} from '../utils/tick-arrays'
 
 async function main() {
   const argv = require('minimist')(process.argv.slice(2))
@@ -131,6 +135,7 @@ async function main() {
 
   const decreaseLiquidityPositionAccounts = {
     positionAuthority,
+    clad: cladKey,
     globalpool: globalpoolKey,
     position: positionKey,
     positionTokenAccount,
@@ -140,6 +145,10 @@ async function main() {
     tokenVaultB,
     tickArrayLower: tickArrayLowerKey,
     tickArrayUpper: tickArrayUpperKey,
+    tickInterestLower: getTickInterestKey(globalpoolKey, tickLowerIndex, programId),
+    tickInterestUpper: getTickInterestKey(globalpoolKey, tickUpperIndex, programId),
+    limitOrderArrayLower: getLimitOrderArrayKey(tickArrayLowerKey, programId),
+    limitOrderArrayUpper: getLimitOrderArrayKey(tickArrayUpperKey, programId),
     // sys
     tokenProgram: TOKEN_PROGRAM_ID,
   }
@@ -153,6 +162,9 @@ async function main() {
       program.instruction.collectFees({
         accounts: collectFeesAccounts,
       }),
+      program.instruction.collectInterest({
+        accounts: collectFeesAccounts,
+      }),
       program.instruction.decreaseLiquidity(decreaseLiquidityPositionParams, {
         accounts: decreaseLiquidityPositionAccounts,
       }),
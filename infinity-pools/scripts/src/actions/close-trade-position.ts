@@ -23,7 +23,11 @@ import { getPostPoolInitParams } from '../params'
 import { ParsableGlobalpool } from '../types/parsing'
 import { consoleLogFull, getAccountData, getTokenBalance } from '../utils'
 import { getRoutesFromJupiter } from '../utils/jupiter'
-import { getTickArrayKeyFromTickIndex } from '../utils/tick-arrays'
+import {
+  getLimitOrderArrayKey,
+  getTickArrayKeyFromTickIndex,
+  getTickInterestKey,
+} from '../utils/tick-arrays'
 import { createTransactionChained } from '../utils/txix'
 import { createAndMintToManyATAs } from '../utils/token'
 import { getUserTradePositions } from '../utils/position'
@@ -284,6 +288,7 @@ async function main() {
   const repayTradePositionAccounts = {
     owner: positionAuthority,
     liquidator: positionAuthority, // closing self.
+    clad: cladKey,
     globalpool: globalpoolKey,
 
     position: positionKey,
@@ -298,6 +303,25 @@ async function main() {
     tokenMintA: tokenMintAKey,
     tokenMintB: tokenMintBKey,
 
+    tickArrayLower: tickArrayLowerKey,
+    tickArrayUpper: tickArrayUpperKey,
+    tickInterestLower: getTickInterestKey(
+      globalpoolKey,
+      tradePositionData.tickLowerIndex,
+      programId
+    ),
+    tickInterestUpper: getTickInterestKey(
+      globalpoolKey,
+      tradePositionData.tickUpperIndex,
+      programId
+    ),
+    limitOrderArrayLower: getLimitOrderArrayKey(tickArrayLowerKey, programId),
+    limitOrderArrayUpper: getLimitOrderArrayKey(tickArrayUpperKey, programId),
+    oracle: PublicKey.findProgramAddressSync(
+      [Buffer.from('oracle'), globalpoolKey.toBuffer()],
+      programId
+    )[0],
+
     // sys
     tokenProgram: TOKEN_PROGRAM_ID,
     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -311,6 +335,7 @@ async function main() {
 
   const closeTradePositionAccounts = {
     owner: positionAuthority,
+    clad: cladKey,
     receiver: positionAuthority,
     globalpool: globalpoolKey,
 
@@ -327,6 +352,16 @@ async function main() {
 
     tickArrayLower: tickArrayLowerKey,
     tickArrayUpper: tickArrayUpperKey,
+    tickInterestLower: getTickInterestKey(
+      globalpoolKey,
+      tradePositionData.tickLowerIndex,
+      programId
+    ),
+    tickInterestUpper: getTickInterestKey(
+      globalpoolKey,
+      tradePositionData.tickUpperIndex,
+      programId
+    ),
 
     tokenProgram: TOKEN_PROGRAM_ID,
     // associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
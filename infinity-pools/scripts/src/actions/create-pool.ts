@@ -81,8 +81,37 @@ async function createPools(connection: Connection, program: anchor.Program<Clad>
     const tokenVaultA = getAssociatedTokenAddressSync(tokenMintAKey, globalpoolKey, true)
     const tokenVaultB = getAssociatedTokenAddressSync(tokenMintBKey, globalpoolKey, true)
 
+    const [feeTierKey] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('fee_tier'),
+        cladKey.toBuffer(),
+        new anchor.BN(tickSpacing).toArrayLike(Buffer, 'le', 2),
+      ],
+      program.programId
+    )
+
+    if (!(await connection.getAccountInfo(feeTierKey))) {
+      await createTransactionChained(
+        connection,
+        wallet,
+        program.instruction.initializeFeeTier(
+          { tickSpacing, defaultFeeRate: feeRate },
+          {
+            accounts: {
+              admin: wallet.publicKey,
+              clad: cladKey,
+              feeTier: feeTierKey,
+              systemProgram: SystemProgram.programId,
+            },
+          }
+        ),
+        []
+      ).buildAndExecute()
+
+      console.log('Initialized Fee Tier: ', feeTierKey.toBase58())
+    }
+
     const createPoolParams = {
-      feeRate,
       tickSpacing,
       initialSqrtPrice: initSqrtPrice,
     }
@@ -90,6 +119,7 @@ async function createPools(connection: Connection, program: anchor.Program<Clad>
     const createPoolAccounts = {
       funder: wallet.publicKey,
       clad: cladKey,
+      feeTier: feeTierKey,
       globalpool: globalpoolKey,
       tokenMintA: tokenMintAKey,
       tokenMintB: tokenMintBKey,
@@ -111,6 +141,25 @@ async function createPools(connection: Connection, program: anchor.Program<Clad>
       []
     ).buildAndExecute()
 
+    const [oracleKey] = PublicKey.findProgramAddressSync(
+      [Buffer.from('oracle'), globalpoolKey.toBuffer()],
+      program.programId
+    )
+
+    await createTransactionChained(
+      connection,
+      wallet,
+      program.instruction.initializeOracle({
+        accounts: {
+          funder: wallet.publicKey,
+          globalpool: globalpoolKey,
+          oracle: oracleKey,
+          systemProgram: SystemProgram.programId,
+        },
+      }),
+      []
+    ).buildAndExecute()
+
     console.log('Created Pool: ', globalpoolKey.toString())
     console.log('   Base:  ', poolConfig.baseToken.symbol)
     console.log('   Quote: ', poolConfig.quoteToken.symbol)
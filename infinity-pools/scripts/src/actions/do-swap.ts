@@ -5,12 +5,16 @@ import {
   getOrCreateAssociatedTokenAccount,
 } from '@solana/spl-token'
 import BN from 'bn.js'
+import { AccountMeta, PublicKey } from '@solana/web3.js'
 
 import { consoleLogFull, getAccountData, getTokenBalance } from '../utils'
 import { ParsableGlobalpool, ParsableTickArray } from '../types/parsing'
 import { getPostPoolInitParams } from '../params'
 import { TICK_ARRAY_SIZE } from '../constants'
-import { getTickArrayKeysForSwap } from '../utils/tick-arrays'
+import {
+  getLimitOrderArrayKey,
+  getTickArrayKeysForSwap,
+} from '../utils/tick-arrays'
 import { swapQuoteByInputToken } from '../utils/swap'
 import { createTransactionChained } from '../utils/txix'
 import { createAndMintToManyATAs } from '../utils/token'
@@ -88,6 +92,9 @@ async function main() {
     `${swapA2B ? 'A' : 'B'} to ${swapA2B ? 'B' : 'A'} tick array keys`
   )
 
+  // Tick arrays with pending limit orders can only be swapped through with their LimitOrderArray
+  const limitOrderArrayAccounts: AccountMeta[] = []
+
   for (const tickArrayKey of tickArrayKeys) {
     const tickArrayData = await getAccountData(
       tickArrayKey,
@@ -99,7 +106,14 @@ async function main() {
       continue
     }
 
-    const { startTickIndex } = tickArrayData
+    const { startTickIndex, limitOrderRangeCount } = tickArrayData
+    if (limitOrderRangeCount > 0) {
+      limitOrderArrayAccounts.push({
+        isSigner: false,
+        isWritable: true,
+        pubkey: getLimitOrderArrayKey(tickArrayKey, programId),
+      })
+    }
     const endTickIndex =
       tickArrayData.startTickIndex + tickSpacing * TICK_ARRAY_SIZE
 
@@ -207,8 +221,14 @@ async function main() {
       .toString()}`
   )
 
+  const [oracleKey] = PublicKey.findProgramAddressSync(
+    [Buffer.from('oracle'), globalpoolKey.toBuffer()],
+    programId
+  )
+
   const swapAccounts = {
     tokenAuthority,
+    clad: cladKey,
     globalpool: globalpoolKey,
     tokenOwnerAccountA: authorityTokenAccountA,
     tokenOwnerAccountB: authorityTokenAccountB,
@@ -217,6 +237,7 @@ async function main() {
     tickArray0: tickArrayKeys[0],
     tickArray1: tickArrayKeys[1],
     tickArray2: tickArrayKeys[2],
+    oracle: oracleKey,
     // sys
     tokenProgram: TOKEN_PROGRAM_ID,
   }
@@ -235,6 +256,7 @@ async function main() {
     [
       program.instruction.swap(swapParams, {
         accounts: swapAccounts,
+        remainingAccounts: limitOrderArrayAccounts,
       }),
     ],
     []
@@ -28,7 +28,11 @@ import { PositionStatus } from '../utils/liquidity-position/types'
 import { PositionUtil } from '../utils/liquidity-position/utils'
 import { createAndMintToManyATAs } from '../utils/token'
 import { createTransactionChained } from '../utils/txix'
-import { getTickArrayKeyFromTickIndex } from '../utils/tick-arrays'
+import {
+  getLimitOrderArrayKey,
+  getTickArrayKeyFromTickIndex,
+  getTickInterestKey,
+} from '../utils/tick-arrays'
 
 async function main() {
   const {
@@ -206,6 +210,7 @@ async function main() {
 
     const increaseLiquidityPositionAccounts = {
       positionAuthority,
+      clad: cladKey,
       globalpool: globalpoolKey,
       position: positionKey,
       positionTokenAccount,
@@ -215,6 +220,10 @@ async function main() {
       tokenVaultB,
       tickArrayLower: tickArrayLowerKey,
       tickArrayUpper: tickArrayUpperKey,
+      tickInterestLower: getTickInterestKey(globalpoolKey, tickLowerIndex, programId),
+      tickInterestUpper: getTickInterestKey(globalpoolKey, tickUpperIndex, programId),
+      limitOrderArrayLower: getLimitOrderArrayKey(tickArrayLowerKey, programId),
+      limitOrderArrayUpper: getLimitOrderArrayKey(tickArrayUpperKey, programId),
       // sys
       tokenProgram: TOKEN_PROGRAM_ID,
     }
@@ -25,7 +25,11 @@ import { getPostPoolInitParams } from '../params'
 import { ParsableGlobalpool } from '../types/parsing'
 import { consoleLogFull, getAccountData, priceToTickIndex } from '../utils'
 import { getRoutesFromJupiter } from '../utils/jupiter'
-import { getTickArrayKeyFromTickIndex } from '../utils/tick-arrays'
+import {
+  getLimitOrderArrayKey,
+  getTickArrayKeyFromTickIndex,
+  getTickInterestKey,
+} from '../utils/tick-arrays'
 import { createTransactionChained } from '../utils/txix'
 import { createAndMintToManyATAs } from '../utils/token'
 
@@ -235,6 +239,7 @@ async function main() {
 
   const openTradePositionAccounts = {
     owner: positionAuthority,
+    clad: cladKey,
     globalpool: globalpoolKey,
 
     position: positionKey,
@@ -247,9 +252,19 @@ async function main() {
     tokenVaultB,
     tokenMintA: tokenMintAKey,
     tokenMintB: tokenMintBKey,
+    tokenPriceFeedA: globalpoolInfo.tokenPriceFeedA,
+    tokenPriceFeedB: globalpoolInfo.tokenPriceFeedB,
 
     tickArrayLower: tickArrayLowerKey,
     tickArrayUpper: tickArrayUpperKey,
+    tickInterestLower: getTickInterestKey(globalpoolKey, tickLowerIndex, programId),
+    tickInterestUpper: getTickInterestKey(globalpoolKey, tickUpperIndex, programId),
+    limitOrderArrayLower: getLimitOrderArrayKey(tickArrayLowerKey, programId),
+    limitOrderArrayUpper: getLimitOrderArrayKey(tickArrayUpperKey, programId),
+    oracle: PublicKey.findProgramAddressSync(
+      [Buffer.from('oracle'), globalpoolKey.toBuffer()],
+      programId
+    )[0],
 
     // sys
     tokenProgram: TOKEN_PROGRAM_ID,
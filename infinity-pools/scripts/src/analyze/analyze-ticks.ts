@@ -109,12 +109,10 @@ async function main() {
     const { ticks } = ta.data
     for (let i = 0; i < ticks.length; i++) {
       const tick = ticks[i]
-      let [zeroNet, zeroGross, zeroBorrowed] = [
-        tick.liquidityNet,
-        tick.liquidityGross,
-        tick.liquidityBorrowed,
-      ].map((x) => x.isZero())
-      let isZero = zeroNet && zeroGross && zeroBorrowed
+      let [zeroNet, zeroGross] = [tick.liquidityNet, tick.liquidityGross].map(
+        (x) => x.isZero()
+      )
+      let isZero = zeroNet && zeroGross
 
       // const liquidityNet = tick.liquidityNet.ishrn(64) // from X64
       // const liquidityGross = tick.liquidityGross.ushrn(64) // from X64
@@ -131,7 +129,6 @@ async function main() {
         console.log(' '.repeat(4), 'initialized')
       }
 
-      // const liquidityNet = PriceMath.sqrtPriceX64ToPrice(tick.liquidityBorrowed)
       if (!zeroNet) {
         console.log(
           ' '.repeat(4),
@@ -145,15 +142,6 @@ async function main() {
           `gross:    ${liquidityGross.toLocaleString().padStart(30, ' ')}  `
         )
       }
-
-      if (!zeroBorrowed) {
-        console.log(
-          ' '.repeat(4),
-          `borrowed: ${tick.liquidityBorrowed
-            .toLocaleString()
-            .padStart(28, ' ')}  `
-        )
-      }
     }
   }
 }
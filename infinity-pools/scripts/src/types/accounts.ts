@@ -7,6 +7,8 @@ export enum AccountName {
   TradePosition = 'TradePosition',
   TickArray = 'TickArray',
   Globalpool = 'Globalpool',
+  LimitOrder = 'LimitOrder',
+  LimitOrderArray = 'LimitOrderArray',
 }
 
 export type BasicSupportedTypes = Account | Mint
@@ -26,12 +28,55 @@ export type GlobalpoolData = {
   protocolFeeOwedB: BN
   tokenMintA: PublicKey
   tokenVaultA: PublicKey
+  tokenPriceFeedA: PublicKey
   feeGrowthGlobalA: BN
   tokenMintB: PublicKey
   tokenVaultB: PublicKey
+  tokenPriceFeedB: PublicKey
   feeGrowthGlobalB: BN
   inceptionTime: BN
   feeAuthority: PublicKey
+  pendingFeeAuthority: PublicKey
+  maintenanceMarginBps: number
+  liquidationBonusBps: number
+  oracleStaleSeconds: BN
+  oraclePriceBandBps: number
+  interestBaseRateBps: number
+  interestSlope1Bps: number
+  interestOptimalUtilizationBps: number
+  interestSlope2Bps: number
+  interestAccrualEnabled: boolean
+  borrowIndex: BN
+  borrowIndexUpdatedAt: BN
+  maxLoanDuration: BN
+  swapRouter: PublicKey
+  flashLoanFeeRate: number
+  flashLoanAmountA: BN
+  flashLoanAmountB: BN
+  rewardLastUpdatedTimestamp: BN
+  rewardInfos: GlobalpoolRewardInfoData[]
+  liquidityLimitOrders: BN
+  variableFeeControl: number
+  volatilityFilterPeriod: number
+  volatilityDecayPeriod: number
+  volatilityReductionFactorBps: number
+  maxVolatilityAccumulator: number
+  volatilityInfo: GlobalpoolVolatilityInfoData
+}
+
+export type GlobalpoolRewardInfoData = {
+  mint: PublicKey
+  vault: PublicKey
+  authority: PublicKey
+  emissionsPerSecondX64: BN
+  growthGlobalX64: BN
+}
+
+export type GlobalpoolVolatilityInfoData = {
+  volatilityAccumulator: number
+  volatilityReference: number
+  tickGroupIndexReference: number
+  lastSwapTimestamp: BN
 }
 
 export type LiquidityPositionData = {
@@ -44,6 +89,17 @@ export type LiquidityPositionData = {
   feeOwedA: BN
   feeGrowthCheckpointB: BN
   feeOwedB: BN
+  rewardInfos: LiquidityPositionRewardInfoData[]
+  interestGrowthCheckpointA: BN
+  interestOwedA: BN
+  interestGrowthCheckpointB: BN
+  interestOwedB: BN
+  liquidityQueued: BN
+}
+
+export type LiquidityPositionRewardInfoData = {
+  growthInsideCheckpoint: BN
+  amountOwed: BN
 }
 
 export type TradePositionData = {
@@ -62,21 +118,53 @@ export type TradePositionData = {
   openTime: BN // u64
   duration: BN // u64
   interestRate: number // u32
+  isInterestAccrued: boolean
+  borrowIndexCheckpoint: BN // u128
+  maintenanceMarginBps: number // u16
 }
 
 export type TickData = {
   initialized: boolean
   liquidityNet: BN
   liquidityGross: BN
-  liquidityBorrowed: BN
   feeGrowthOutsideA: BN
   feeGrowthOutsideB: BN
+  rewardGrowthsOutside: BN[]
 }
 
 export type TickArrayData = {
   globalpool: PublicKey
   startTickIndex: number
   ticks: TickData[]
+  limitOrderRangeCount: number // u16
+  funder: PublicKey
+}
+
+export type LimitOrderData = {
+  globalpool: PublicKey
+  owner: PublicKey
+  tickLowerIndex: number
+  aToB: boolean
+  liquidity: BN // u128
+  epoch: number // u32
+  feeGrowthCheckpointA: BN // u128
+  feeGrowthCheckpointB: BN // u128
+}
+
+export type LimitOrderRangeData = {
+  liquidity: BN // u128
+  aToB: boolean
+  epoch: number // u32
+  liquidityUnclaimed: BN // u128
+  feeGrowthInsideA: BN // u128
+  feeGrowthInsideB: BN // u128
+}
+
+export type LimitOrderArrayData = {
+  globalpool: PublicKey
+  startTickIndex: number
+  funder: PublicKey
+  ranges: LimitOrderRangeData[]
 }
 
 export type CladSupportedTypes =
@@ -84,6 +172,8 @@ export type CladSupportedTypes =
   | LiquidityPositionData
   | TradePositionData
   | TickArrayData
+  | LimitOrderData
+  | LimitOrderArrayData
   | BasicSupportedTypes
 
 /**
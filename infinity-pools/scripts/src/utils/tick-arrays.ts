@@ -31,6 +31,41 @@ export function getTickArrayKey(
   )[0]
 }
 
+/**
+ * Get TickInterest key of a tick, which holds the liquidity borrowed at the tick and the interest paid for it
+ *
+ * @param globalpool
+ * @param tickIndex
+ * @param programId
+ * @returns
+ */
+export function getTickInterestKey(
+  globalpool: PublicKey,
+  tickIndex: number,
+  programId: PublicKey
+) {
+  const tickIndexBuffer = Buffer.alloc(4)
+  tickIndexBuffer.writeInt32LE(tickIndex)
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('tick_interest'), globalpool.toBuffer(), tickIndexBuffer],
+    programId
+  )[0]
+}
+
+/**
+ * Get LimitOrderArray key of a tick array, which holds the limit orders on its ranges
+ *
+ * @param tickArray
+ * @param programId
+ * @returns
+ */
+export function getLimitOrderArrayKey(tickArray: PublicKey, programId: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('limit_order_array'), tickArray.toBuffer()],
+    programId
+  )[0]
+}
+
 /**
  * Get TickArray key from any valid tick index, which doesn't have to be a start index.
  * 